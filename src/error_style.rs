@@ -1,4 +1,6 @@
 // Clean, ASCII-only terminal styling for error panels.
+use crate::kernel::ast::Span;
+
 const COLOR_RESET: &str = "\x1b[0m";
 const COLOR_HEADER: &str = "\x1b[38;5;220m";
 const COLOR_DETAIL: &str = "\x1b[38;5;203m";
@@ -60,6 +62,12 @@ pub fn render_error_box(
     out
 }
 
+// Runtime failures reuse the parse panel, pointing at the packet that raised them.
+pub fn render_runtime_error(src: &str, span: Span, detail: &str) -> String {
+    let snippet = src.lines().nth(span.line.saturating_sub(1)).unwrap_or("");
+    render_error_box(span.line, span.col, snippet, &runtime_hint(detail), detail)
+}
+
 // Helper to center the title and build footer with matching width
 fn centered_box_border(title_text: &str, content_width: usize) -> (String, String) {
     let side_space = content_width.saturating_sub(title_text.chars().count());
@@ -77,6 +85,11 @@ pub fn friendly_hint(detail: &str) -> String {
     format!("{} - {}", category.label(), message)
 }
 
+pub fn runtime_hint(detail: &str) -> String {
+    let (category, message) = classify_runtime(detail);
+    format!("{} - {}", category.label(), message)
+}
+
 pub fn unexpected_hint(ch: char, where_: &str) -> String {
     let (category, message) = classify_unexpected(ch, where_);
     format!("{} - {}", category.label(), message)
@@ -105,6 +118,7 @@ enum HintCategory {
     Packet,
     Variable,
     Syntax,
+    Runtime,
 }
 
 impl HintCategory {
@@ -114,6 +128,7 @@ impl HintCategory {
             HintCategory::Packet => "Packet",
             HintCategory::Variable => "Variable",
             HintCategory::Syntax => "Syntax",
+            HintCategory::Runtime => "Runtime",
         }
     }
 }
@@ -136,6 +151,21 @@ fn classify_detail(detail: &str) -> (HintCategory, String) {
     )
 }
 
+fn classify_runtime(detail: &str) -> (HintCategory, String) {
+    let lower = detail.to_ascii_lowercase();
+    if let Some(msg) = packet_hint(&lower) {
+        return (HintCategory::Packet, msg);
+    }
+    if let Some(msg) = variable_hint(&lower) {
+        return (HintCategory::Variable, msg);
+    }
+    (
+        HintCategory::Runtime,
+        "This packet parsed fine but tripped while running. Check its argument and the value flowing into it."
+            .to_string(),
+    )
+}
+
 fn classify_unexpected(ch: char, where_: &str) -> (HintCategory, String) {
     match (where_, ch) {
         (_, ']') => (
@@ -283,6 +313,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn runtime_error_renders_box() {
+        let src = "[math@1+1]\n[call@missing]\n";
+        let span = Span {
+            start: 11,
+            end: 25,
+            line: 2,
+            col: 1,
+        };
+        let out = render_runtime_error(src, span, "unknown funct 'missing'");
+        assert!(out.contains('╭') && out.contains('╰'), "missing box glyphs:\n{out}");
+        assert!(out.contains("[call@missing]"), "missing snippet:\n{out}");
+        assert!(out.contains("Line 2, Column 1"), "missing location:\n{out}");
+        assert!(out.contains("Variable"), "missing hint:\n{out}");
+    }
+
     #[test]
     fn unexpected_hint_marks_syntax() {
         let hint = unexpected_hint('p', "top-level");
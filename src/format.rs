@@ -0,0 +1,374 @@
+// Canonical source printer for `tagspeak fmt`.
+// Layout: one node per line, blocks indented by four spaces, conditionals
+// re-emitted as `[if(cond)]>[then]{...}` with `>[or(...)]` / `>[else]` on the
+// following lines. Formatting an already formatted script is a no-op.
+// Comments come back on their own line before the node they preceded, or at the
+// end of the line when they trailed a node.
+use std::collections::VecDeque;
+
+use crate::kernel::ast::{Arg, BExpr, CmpBase, Comparator, Comment, Node, Packet};
+use crate::router::{self, ParseError};
+
+const INDENT: &str = "    ";
+
+/// Parse `src` and re-emit it in canonical layout.
+pub fn format_source(src: &str) -> Result<String, ParseError> {
+    let ast = router::parse(src)?;
+    let mut out = Out {
+        buf: String::new(),
+        src,
+        comments: router::comments(src).into(),
+    };
+    write_root(&mut out, &ast);
+    out.flush_before(usize::MAX, 0);
+    Ok(out.buf)
+}
+
+// Output buffer plus the comments still waiting to be placed, in source order.
+struct Out<'a> {
+    buf: String,
+    src: &'a str,
+    comments: VecDeque<Comment>,
+}
+
+impl Out<'_> {
+    fn push_str(&mut self, s: &str) {
+        self.buf.push_str(s);
+    }
+
+    fn push(&mut self, c: char) {
+        self.buf.push(c);
+    }
+
+    // own-line comments that start before `pos`
+    fn flush_before(&mut self, pos: usize, depth: usize) {
+        while self.comments.front().is_some_and(|c| c.span.start < pos) {
+            let c = self.comments.pop_front().unwrap();
+            indent(self, depth);
+            self.push_str(&c.text);
+            self.push('\n');
+        }
+    }
+
+    // a comment sitting after `end` with only blanks (or a dangling `>`) between
+    fn trailing_after(&mut self, end: usize) {
+        let Some(c) = self.comments.front() else {
+            return;
+        };
+        let gap = self.src.get(end..c.span.start).unwrap_or("\n");
+        if c.trailing && !gap.contains('\n') && matches!(gap.trim(), "" | ">") {
+            let c = self.comments.pop_front().unwrap();
+            self.push_str("  ");
+            self.push_str(&c.text);
+        }
+    }
+}
+
+fn write_root(out: &mut Out, node: &Node) {
+    match node {
+        Node::Chain(nodes) => write_list(out, nodes, 0),
+        other => write_node(out, other, 0),
+    }
+}
+
+fn write_list(out: &mut Out, nodes: &[Node], depth: usize) {
+    for node in nodes {
+        write_node(out, node, depth);
+    }
+}
+
+fn write_node(out: &mut Out, node: &Node, depth: usize) {
+    let span = node.span();
+    if span.is_known() {
+        out.flush_before(span.start, depth);
+    }
+    indent(out, depth);
+    match node {
+        Node::Packet(p) => {
+            out.push_str(&packet_head(p));
+            if let Some(body) = &p.body {
+                write_body(out, body, depth, p.span.end);
+            }
+        }
+        Node::Block(body) => write_body(out, body, depth, span.end),
+        // chains only nest inside conditional atoms; keep them on one line
+        Node::Chain(nodes) => out.push_str(&inline_list(nodes)),
+        Node::If {
+            cond,
+            then_b,
+            else_b,
+            ..
+        } => {
+            out.push_str(&format!("[if({})]>[then]", cond_src(cond)));
+            write_body(out, then_b, depth, branch_end(else_b, span.end));
+            write_else(out, else_b, depth, span.end);
+        }
+    }
+    if span.is_known() {
+        out.trailing_after(span.end);
+    }
+    out.push('\n');
+}
+
+// a branch body owns the comments up to where the next branch starts
+fn branch_end(else_b: &[Node], end: usize) -> usize {
+    match else_b.first() {
+        Some(n) if n.span().is_known() => n.span().start,
+        _ => end,
+    }
+}
+
+fn write_body(out: &mut Out, body: &[Node], depth: usize, end: usize) {
+    let has_comments = out.comments.front().is_some_and(|c| c.span.start < end);
+    if body.is_empty() && !has_comments {
+        out.push_str("{}");
+        return;
+    }
+    out.push_str("{\n");
+    write_list(out, body, depth + 1);
+    out.flush_before(end, depth + 1);
+    indent(out, depth);
+    out.push('}');
+}
+
+fn write_else(out: &mut Out, else_b: &[Node], depth: usize, end: usize) {
+    if else_b.is_empty() {
+        return;
+    }
+    out.push('\n');
+    indent(out, depth);
+    if let [
+        Node::If {
+            cond,
+            then_b,
+            else_b,
+            ..
+        },
+    ] = else_b
+    {
+        out.push_str(&format!(">[or({})]>[then]", cond_src(cond)));
+        write_body(out, then_b, depth, branch_end(else_b, end));
+        write_else(out, else_b, depth, end);
+    } else {
+        out.push_str(">[else]>[then]");
+        write_body(out, else_b, depth, end);
+    }
+}
+
+fn indent(out: &mut Out, depth: usize) {
+    for _ in 0..depth {
+        out.push_str(INDENT);
+    }
+}
+
+// ---- single-line rendering (conditions, nested atoms) ----
+
+fn inline_node(node: &Node) -> String {
+    match node {
+        Node::Packet(p) => match &p.body {
+            Some(body) => format!("{}{{{}}}", packet_head(p), inline_list(body)),
+            None => packet_head(p),
+        },
+        Node::Block(body) => format!("{{{}}}", inline_list(body)),
+        Node::Chain(nodes) => inline_list(nodes),
+        Node::If {
+            cond,
+            then_b,
+            else_b,
+            ..
+        } => {
+            let mut out = format!("[if({})]>[then]{{{}}}", cond_src(cond), inline_list(then_b));
+            if !else_b.is_empty() {
+                out.push_str(&format!(">[else]>[then]{{{}}}", inline_list(else_b)));
+            }
+            out
+        }
+    }
+}
+
+fn inline_list(nodes: &[Node]) -> String {
+    nodes.iter().map(inline_node).collect::<Vec<_>>().join(">")
+}
+
+fn packet_head(p: &Packet) -> String {
+    let mut out = String::from("[");
+    if let Some(ns) = &p.ns {
+        out.push_str(ns);
+        out.push(':');
+    }
+    out.push_str(&p.op);
+    if let Some(arg) = &p.arg {
+        out.push('@');
+        out.push_str(&arg_src(arg));
+    }
+    out.push(']');
+    out
+}
+
+fn arg_src(arg: &Arg) -> String {
+    match arg {
+        Arg::Str(s) => str_src(s),
+        Arg::Ident(id) => id.clone(),
+        Arg::Number(n) => n.to_string(),
+        Arg::CondSrc(s) => s.clone(),
+    }
+}
+
+// Strings print bare when that parses back to the same Arg (paths, JSON
+// sugar, `"a" + b` concatenations) and quoted when they read like prose.
+fn str_src(s: &str) -> String {
+    let quoted = quote(s);
+    let raw_ok = round_trips(s, s);
+    let quoted_ok = round_trips(&quoted, s);
+    let prose = s.chars().any(char::is_whitespace) && !s.starts_with(['{', '[']);
+    if raw_ok && (!quoted_ok || !prose) {
+        s.to_string()
+    } else {
+        quoted
+    }
+}
+
+fn round_trips(src: &str, expect: &str) -> bool {
+    balanced(src, '[', ']')
+        && balanced(src, '{', '}')
+        && matches!(router::parse_arg(src), Ok(Some(Arg::Str(ref s))) if s == expect)
+}
+
+fn balanced(s: &str, open: char, close: char) -> bool {
+    let mut depth = 0i64;
+    let mut escape = false;
+    for c in s.chars() {
+        if escape {
+            escape = false;
+            continue;
+        }
+        if c == '\\' {
+            escape = true;
+        } else if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth < 0 {
+                return false;
+            }
+        }
+    }
+    depth == 0
+}
+
+fn quote(s: &str) -> String {
+    let escape_brackets = !balanced(s, '[', ']') || !balanced(s, '{', '}');
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '[' | ']' | '{' | '}' if escape_brackets => {
+                out.push('\\');
+                out.push(c);
+            }
+            other => out.push(other),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// ---- conditions ----
+
+fn cond_src(cond: &BExpr) -> String {
+    match cond {
+        BExpr::Lit(s) => s.trim().to_string(),
+        BExpr::And(a, b) => format!("{} && {}", cond_src(a), cond_src(b)),
+        BExpr::Or(a, b) => format!("{} || {}", cond_src(a), cond_src(b)),
+        BExpr::Not(e) => format!("!{}", cond_src(e)),
+        BExpr::Cmp { lhs, cmp, rhs } => {
+            format!("{} {} {}", atom_src(lhs), cmp_symbol(cmp), atom_src(rhs))
+        }
+    }
+}
+
+// Undo the literal wrapping done by `conditionals::parse_atom`.
+fn atom_src(node: &Node) -> String {
+    if let Node::Packet(Packet {
+        ns: None,
+        op,
+        arg: Some(arg),
+        body: None,
+        ..
+    }) = node
+    {
+        match (op.as_str(), arg) {
+            ("math", Arg::Number(n)) => return n.to_string(),
+            ("msg", Arg::Str(s)) => return format!("\"{s}\""),
+            ("var", Arg::Ident(id)) => return id.clone(),
+            _ => {}
+        }
+    }
+    inline_node(node)
+}
+
+fn cmp_symbol(cmp: &Comparator) -> &'static str {
+    match (&cmp.base, cmp.include_eq, cmp.negate) {
+        (CmpBase::Eq, _, false) => "==",
+        (CmpBase::Eq, _, true) => "!=",
+        (CmpBase::Lt, false, _) => "<",
+        (CmpBase::Lt, true, _) => "<=",
+        (CmpBase::Gt, false, _) => ">",
+        (CmpBase::Gt, true, _) => ">=",
+        (CmpBase::Approx, _, _) => "~=",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"[note@ Demo that 0 is falsey]
+[if@([math@0])]>[then]{[print@"no"]}
+>[or@(x>=2 && !y)]>[then]{[print@"maybe"]}
+>[else]>[then]{[print@"yes"]}
+[funct:step]{
+  [math@x + 1]>[store@x]   [loop@2]{[msg@"a"+name]>[print]}
+}
+[int@0]>[store@x]>[load@/data/config.json]>[array@[1,2,3]]
+"#;
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let once = format_source(SAMPLE).expect("sample parses");
+        let twice = format_source(&once).expect("formatted output parses");
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn emits_one_node_per_line_with_indented_blocks() {
+        let out = format_source("[funct:step]{[math@x+1]>[store@x]}>[print@\"hi there\"]").unwrap();
+        assert_eq!(
+            out,
+            "[funct:step]{\n    [math@x+1]\n    [store@x]\n}\n[print@\"hi there\"]\n"
+        );
+    }
+
+    #[test]
+    fn keeps_or_else_chain() {
+        let out = format_source(SAMPLE).unwrap();
+        assert!(out.contains("[if([math@0])]>[then]{"), "got:\n{out}");
+        assert!(out.contains(">[or(x >= 2 && !y)]>[then]{"), "got:\n{out}");
+        assert!(out.contains(">[else]>[then]{"), "got:\n{out}");
+    }
+
+    #[test]
+    fn keeps_comments_in_place() {
+        let src = "# header\n[int@1]>[store@x] // set x\n[funct:step]{\n  /* inside */\n  [print@x]\n  # tail\n}\n# end\n";
+        let once = format_source(src).unwrap();
+        assert_eq!(
+            once,
+            "# header\n[int@1]\n[store@x]  // set x\n[funct:step]{\n    /* inside */\n    [print@x]\n    # tail\n}\n# end\n"
+        );
+        assert_eq!(format_source(&once).unwrap(), once);
+    }
+}
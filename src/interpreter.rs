@@ -1,16 +1,15 @@
 use anyhow::{Result, bail};
+use std::rc::Rc;
 
-#[derive(Clone, Debug)]
-pub struct Span {
-    pub start: usize,
-    pub end: usize,
-}
+pub use crate::kernel::ast::Span;
 
 pub struct Scanner<'a> {
     src: &'a [u8],
     pub i: usize,
     len: usize,
     limit: usize,
+    // byte offsets where each line begins; shared with subscanners
+    line_starts: Rc<[usize]>,
 }
 
 impl<'a> Scanner<'a> {
@@ -34,6 +33,7 @@ impl<'a> Scanner<'a> {
             i: 0,
             len,
             limit: len,
+            line_starts: line_starts(bytes),
         }
     }
 
@@ -45,6 +45,7 @@ impl<'a> Scanner<'a> {
             i: start,
             len: self.len,
             limit: end,
+            line_starts: Rc::clone(&self.line_starts),
         }
     }
 
@@ -63,23 +64,6 @@ impl<'a> Scanner<'a> {
         Some(c)
     }
 
-    fn newline_len_at(&self, idx: usize) -> usize {
-        if idx >= self.limit {
-            return 0;
-        }
-        match self.src[idx] {
-            b'\n' => 1,
-            b'\r' => {
-                if idx + 1 < self.limit && self.src[idx + 1] == b'\n' {
-                    2
-                } else {
-                    1
-                }
-            }
-            _ => 0,
-        }
-    }
-
     fn starts_with(&self, s: &str) -> bool {
         let n = s.len();
         self.i + n <= self.limit && &self.src[self.i..self.i + n] == s.as_bytes()
@@ -156,26 +140,19 @@ impl<'a> Scanner<'a> {
     // --- location helpers ---
     pub fn line_col_at(&self, pos: usize) -> (usize, usize) {
         let pos = pos.min(self.limit);
-        let mut line: usize = 1;
-        let mut last_break_end: usize = 0;
-        let mut idx = 0usize;
-        while idx < pos {
-            let newline_len = self.newline_len_at(idx);
-            if newline_len > 0 {
-                let break_end = idx + newline_len;
-                if break_end > pos {
-                    break;
-                }
-                line += 1;
-                last_break_end = break_end;
-                idx = break_end;
-            } else {
-                idx += 1;
-            }
-        }
-        let col = pos.saturating_sub(last_break_end) + 1;
+        let line = self.line_starts.partition_point(|&start| start <= pos);
+        let col = pos - self.line_starts[line - 1] + 1;
         (line, col)
     }
+    pub fn span(&self, start: usize, end: usize) -> Span {
+        let (line, col) = self.line_col_at(start);
+        Span {
+            start,
+            end,
+            line,
+            col,
+        }
+    }
     pub fn cur_line_col(&self) -> (usize, usize) {
         self.line_col_at(self.i)
     }
@@ -185,7 +162,8 @@ impl<'a> Scanner<'a> {
             let (ln, col) = self.cur_line_col();
             bail!("expected '\"' at {}:{}", ln, col);
         }
-        let mut out = String::new();
+        // collect raw bytes so multi-byte UTF-8 sequences survive intact
+        let mut out: Vec<u8> = Vec::new();
         while let Some(c) = self.next() {
             match c {
                 '\\' => {
@@ -193,16 +171,16 @@ impl<'a> Scanner<'a> {
                         bail!("unterminated escape in string");
                     };
                     out.push(match nc {
-                        'n' => '\n',
-                        'r' => '\r',
-                        't' => '\t',
-                        '\\' => '\\',
-                        '"' => '"',
-                        other => other,
+                        'n' => b'\n',
+                        'r' => b'\r',
+                        't' => b'\t',
+                        '\\' => b'\\',
+                        '"' => b'"',
+                        other => other as u8,
                     });
                 }
-                '"' => return Ok(out),
-                other => out.push(other),
+                '"' => return Ok(String::from_utf8_lossy(&out).into_owned()),
+                other => out.push(other as u8),
             }
         }
         let (ln, col) = self.cur_line_col();
@@ -215,14 +193,14 @@ impl<'a> Scanner<'a> {
             let (ln, col) = self.cur_line_col();
             bail!("expected opener {} at {}:{}", open, ln, col);
         }
-        let mut out = String::new();
+        let mut out: Vec<u8> = Vec::new();
         let mut depth = 1usize;
         let inner_start = self.i;
         while let Some(c) = self.next() {
             if c == '\\' {
                 if let Some(nc) = self.next() {
-                    out.push(c);
-                    out.push(nc);
+                    out.push(c as u8);
+                    out.push(nc as u8);
                 }
                 continue;
             }
@@ -232,14 +210,11 @@ impl<'a> Scanner<'a> {
             if c == close {
                 depth -= 1;
                 if depth == 0 {
-                    let span = Span {
-                        start: inner_start,
-                        end: self.i - 1,
-                    };
-                    return Ok((out, span));
+                    let span = self.span(inner_start, self.i - 1);
+                    return Ok((String::from_utf8_lossy(&out).into_owned(), span));
                 }
             }
-            out.push(c);
+            out.push(c as u8);
         }
         let (ln, col) = self.cur_line_col();
         bail!("unbalanced {} ... {} before {}:{}", open, close, ln, col)
@@ -276,3 +251,22 @@ impl<'a> Scanner<'a> {
         s.trim().to_string()
     }
 }
+
+fn line_starts(bytes: &[u8]) -> Rc<[usize]> {
+    let mut starts = vec![0];
+    let mut idx = 0usize;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\r' if bytes.get(idx + 1) == Some(&b'\n') => {
+                idx += 2;
+                starts.push(idx);
+            }
+            b'\n' | b'\r' => {
+                idx += 1;
+                starts.push(idx);
+            }
+            _ => idx += 1,
+        }
+    }
+    starts.into()
+}
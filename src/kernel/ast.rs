@@ -8,15 +8,69 @@ pub enum Node {
         cond: BExpr,
         then_b: Vec<Node>,
         else_b: Vec<Node>,
+        span: Span,
     },
 }
 
+impl Node {
+    // chains and blocks cover the hull of their children
+    pub fn span(&self) -> Span {
+        match self {
+            Node::Packet(p) => p.span,
+            Node::If { span, .. } => *span,
+            Node::Chain(v) | Node::Block(v) => match (v.first(), v.last()) {
+                (Some(first), Some(last)) => first.span().to(last.span()),
+                _ => Span::default(),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Packet {
     pub ns: Option<String>,
     pub op: String,
     pub arg: Option<Arg>,
     pub body: Option<Vec<Node>>,
+    pub span: Span,
+}
+
+// A `#`, `//`, or `/* */` comment the parser skipped; `trailing` when code precedes it
+// on the same line. Kept beside the AST so `tagspeak fmt` can put comments back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub text: String,
+    pub span: Span,
+    pub trailing: bool,
+}
+
+// Byte range into the parsed source plus the 1-based line/col of `start`.
+// Packets synthesized at runtime carry the default span (line 0 = unknown).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl Span {
+    pub fn is_known(&self) -> bool {
+        self.line > 0
+    }
+
+    pub fn to(self, other: Span) -> Span {
+        if !self.is_known() {
+            return other;
+        }
+        if !other.is_known() {
+            return self;
+        }
+        Span {
+            end: self.end.max(other.end),
+            ..self
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -45,6 +99,7 @@ pub enum CmpBase {
     Eq,
     Lt,
     Gt,
+    Approx, // numbers within tolerance; everything else like Eq
 }
 
 #[derive(Debug, Clone)]
@@ -6,10 +6,49 @@ pub fn reduce_op_chain_is_valid() -> bool {
     true
 } // placeholder if needed
 
+// How close two numbers must be for `~=`: |a - b| <= max(rel * max(|a|, |b|), abs)
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    pub rel: f64,
+    pub abs: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Tolerance { rel: 1e-9, abs: 1e-12 }
+    }
+}
+
+impl Tolerance {
+    // .tagspeak.toml [compare] rel_tol/abs_tol, overridden by TAGSPEAK_APPROX_REL_TOL/_ABS_TOL
+    pub fn configured(cfg: &crate::kernel::config::Config) -> Self {
+        Tolerance { rel: cfg.approx_rel_tol, abs: cfg.approx_abs_tol }
+    }
+
+    fn close(&self, x: f64, y: f64) -> bool {
+        x == y || (x - y).abs() <= (self.rel * x.abs().max(y.abs())).max(self.abs)
+    }
+}
+
 pub fn cmp_eval(cmp: &Comparator, a: &Value, b: &Value) -> anyhow::Result<bool> {
+    cmp_eval_with(cmp, a, b, Tolerance::default())
+}
+
+// Like cmp_eval; `tol` only matters for `~=` (CmpBase::Approx).
+pub fn cmp_eval_with(cmp: &Comparator, a: &Value, b: &Value, tol: Tolerance) -> anyhow::Result<bool> {
     use CmpBase::*;
     let mut out = match cmp.base {
-        Eq => eq_values(a, b),
+        Eq | Approx => {
+            if matches!((a, b), (Value::Doc(_), Value::Num(_) | Value::Str(_) | Value::Bool(_)))
+                || matches!((a, b), (Value::Num(_) | Value::Str(_) | Value::Bool(_), Value::Doc(_)))
+            {
+                anyhow::bail!("E_TYPE_MISMATCH: cannot compare a document with a scalar");
+            }
+            match (&cmp.base, a, b) {
+                (Approx, Value::Num(x), Value::Num(y)) => tol.close(*x, *y),
+                _ => eq_values(a, b),
+            }
+        }
         Lt => match order(a, b, |x, y| x < y) { Ok(v) => v, Err(_) => false },
         Gt => match order(a, b, |x, y| x > y) { Ok(v) => v, Err(_) => false },
     };
@@ -22,13 +61,9 @@ pub fn cmp_eval(cmp: &Comparator, a: &Value, b: &Value) -> anyhow::Result<bool>
     Ok(out)
 }
 
+// Unit never equals anything here, not even Unit; otherwise Value's own PartialEq
 fn eq_values(a: &Value, b: &Value) -> bool {
-    match (a, b) {
-        (Value::Num(x), Value::Num(y)) => x == y,
-        (Value::Str(x), Value::Str(y)) => x == y,
-        (Value::Bool(x), Value::Bool(y)) => x == y,
-        _ => false,
-    }
+    !matches!(a, Value::Unit) && !matches!(b, Value::Unit) && a == b
 }
 
 fn order<F: Fn(f64, f64) -> bool>(a: &Value, b: &Value, f: F) -> anyhow::Result<bool> {
@@ -46,3 +81,61 @@ fn to_num(v: &Value) -> anyhow::Result<f64> {
         _ => Err(anyhow::anyhow!("non-numeric value")),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::values::Document;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn doc(json: serde_json::Value) -> Value {
+        Value::Doc(Document::new(
+            json,
+            PathBuf::from("doc.json"),
+            "json".into(),
+            SystemTime::now(),
+            PathBuf::new(),
+        ))
+    }
+
+    const EQ: Comparator = Comparator {
+        base: CmpBase::Eq,
+        include_eq: false,
+        negate: false,
+    };
+
+    #[test]
+    fn docs_equal_regardless_of_key_order() -> anyhow::Result<()> {
+        let a = doc(serde_json::json!({"name": "Ana", "tags": [1, 2], "meta": {"x": 1, "y": 2.0}}));
+        let b = doc(serde_json::json!({"meta": {"y": 2, "x": 1}, "tags": [1, 2], "name": "Ana"}));
+        assert!(cmp_eval(&EQ, &a, &b)?);
+        Ok(())
+    }
+
+    #[test]
+    fn unequal_docs_and_scalar_mismatch() -> anyhow::Result<()> {
+        let a = doc(serde_json::json!({"name": "Ana", "tags": [1, 2]}));
+        let b = doc(serde_json::json!({"name": "Ana", "tags": [2, 1]}));
+        assert!(!cmp_eval(&EQ, &a, &b)?);
+        let ne = Comparator { negate: true, ..EQ };
+        assert!(cmp_eval(&ne, &a, &b)?);
+
+        let err = cmp_eval(&EQ, &a, &Value::Num(1.0)).unwrap_err();
+        assert!(err.to_string().contains("E_TYPE_MISMATCH"), "unexpected error: {err}");
+        Ok(())
+    }
+
+    #[test]
+    fn approx_tolerates_float_noise() -> anyhow::Result<()> {
+        let approx = Comparator { base: CmpBase::Approx, ..EQ };
+        let (sum, third) = (Value::Num(0.1 + 0.2), Value::Num(0.3));
+        assert!(!cmp_eval(&EQ, &sum, &third)?);
+        assert!(cmp_eval(&approx, &sum, &third)?);
+        assert!(!cmp_eval(&approx, &Value::Num(1.0), &Value::Num(1.001))?);
+        let loose = Tolerance { rel: 0.01, abs: 0.0 };
+        assert!(cmp_eval_with(&approx, &Value::Num(1.0), &Value::Num(1.001), loose)?);
+        assert!(cmp_eval(&approx, &Value::Num(0.0), &Value::Num(1e-15))?);
+        Ok(())
+    }
+}
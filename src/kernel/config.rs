@@ -5,10 +5,13 @@ pub struct Config {
     pub allow_exec: bool,
     pub exec_allowlist: Vec<String>,
     pub run_max_depth: usize,
+    pub doc_max_depth: usize,
     pub prompts_noninteractive: bool,
     pub require_yellow_run: bool,
     pub net_enabled: bool,
     pub net_allow: Vec<String>,
+    pub approx_rel_tol: f64, // `~=` tolerance relative to the larger magnitude
+    pub approx_abs_tol: f64, // `~=` floor for values near zero
 }
 
 fn parse_bool_env(key: &str) -> Option<bool> {
@@ -26,6 +29,10 @@ fn parse_usize_env(key: &str) -> Option<usize> {
     std::env::var(key).ok()?.parse::<usize>().ok()
 }
 
+fn parse_f64_env(key: &str) -> Option<f64> {
+    std::env::var(key).ok()?.trim().parse::<f64>().ok().filter(|n| n.is_finite() && *n >= 0.0)
+}
+
 fn parse_list_env(key: &str) -> Option<Vec<String>> {
     std::env::var(key).ok().map(|s| {
         s.split(',')
@@ -41,10 +48,13 @@ pub fn load(root: Option<&Path>) -> Config {
         allow_exec: false,
         exec_allowlist: Vec::new(),
         run_max_depth: 8,
+        doc_max_depth: 128,
         prompts_noninteractive: false,
         require_yellow_run: false,
         net_enabled: false,
         net_allow: Vec::new(),
+        approx_rel_tol: 1e-9,
+        approx_abs_tol: 1e-12,
     };
 
     // Read TOML if present
@@ -79,6 +89,14 @@ pub fn load(root: Option<&Path>) -> Config {
                     && n > 0 {
                         cfg.run_max_depth = n as usize;
                     }
+                // docs.max_depth (usize)
+                if let Some(n) = val
+                    .get("docs")
+                    .and_then(|t| t.get("max_depth"))
+                    .and_then(|v| v.as_integer())
+                    && n > 0 {
+                        cfg.doc_max_depth = n as usize;
+                    }
                 // run.require_yellow (bool)
                 if let Some(b) = val
                     .get("run")
@@ -103,6 +121,21 @@ pub fn load(root: Option<&Path>) -> Config {
                 {
                     cfg.net_enabled = b;
                 }
+                // compare.rel_tol / compare.abs_tol (floats, >= 0)
+                if let Some(n) = val
+                    .get("compare")
+                    .and_then(|t| t.get("rel_tol"))
+                    .and_then(|v| v.as_float())
+                    && n >= 0.0 {
+                        cfg.approx_rel_tol = n;
+                    }
+                if let Some(n) = val
+                    .get("compare")
+                    .and_then(|t| t.get("abs_tol"))
+                    .and_then(|v| v.as_float())
+                    && n >= 0.0 {
+                        cfg.approx_abs_tol = n;
+                    }
                 // network.allow ([string])
                 if let Some(list) = val
                     .get("network")
@@ -125,6 +158,10 @@ pub fn load(root: Option<&Path>) -> Config {
         && n > 0 {
             cfg.run_max_depth = n;
         }
+    if let Some(n) = parse_usize_env("TAGSPEAK_MAX_DOC_DEPTH")
+        && n > 0 {
+            cfg.doc_max_depth = n;
+        }
     if let Some(b) = parse_bool_env("TAGSPEAK_NONINTERACTIVE") {
         cfg.prompts_noninteractive = b;
     }
@@ -137,6 +174,12 @@ pub fn load(root: Option<&Path>) -> Config {
     if let Some(list) = parse_list_env("TAGSPEAK_NET_ALLOW") {
         cfg.net_allow = list;
     }
+    if let Some(n) = parse_f64_env("TAGSPEAK_APPROX_REL_TOL") {
+        cfg.approx_rel_tol = n;
+    }
+    if let Some(n) = parse_f64_env("TAGSPEAK_APPROX_ABS_TOL") {
+        cfg.approx_abs_tol = n;
+    }
 
     cfg
 }
@@ -0,0 +1,251 @@
+use anyhow::{Result, bail};
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use crate::kernel::{Packet, Runtime, Value};
+use crate::packets::*;
+
+pub type PacketHandler = fn(&mut Runtime, &Packet) -> Result<Value>;
+
+// Routes a packet to its handler. Exact `(ns, op)` entries are looked up first;
+// prefix and whole-namespace routes are tried afterwards in registration order.
+#[derive(Clone, Default)]
+pub struct PacketTable {
+    exact: HashMap<String, HashMap<String, PacketHandler>>, // ns ("" for none) -> op -> handler
+    routes: Vec<Route>,
+}
+
+#[derive(Clone)]
+struct Route {
+    ns: Option<String>,
+    prefix: Option<String>, // None matches every op in `ns`
+    handler: PacketHandler,
+}
+
+impl PacketTable {
+    // [ns:op] / [op] exactly
+    pub fn exact(&mut self, ns: Option<&str>, op: &str, handler: PacketHandler) {
+        self.exact
+            .entry(ns.unwrap_or("").to_string())
+            .or_default()
+            .insert(op.to_string(), handler);
+    }
+
+    // ops starting with `prefix`, e.g. "store(" for [store(inc)@x]
+    pub fn prefix(&mut self, ns: Option<&str>, prefix: &str, handler: PacketHandler) {
+        self.routes.push(Route {
+            ns: ns.map(str::to_string),
+            prefix: Some(prefix.to_string()),
+            handler,
+        });
+    }
+
+    // every op under a namespace, e.g. [funct:anything]
+    pub fn namespace(&mut self, ns: &str, handler: PacketHandler) {
+        self.routes.push(Route {
+            ns: Some(ns.to_string()),
+            prefix: None,
+            handler,
+        });
+    }
+
+    pub fn lookup(&self, ns: Option<&str>, op: &str) -> Option<PacketHandler> {
+        if let Some(h) = self.exact.get(ns.unwrap_or("")).and_then(|ops| ops.get(op)) {
+            return Some(*h);
+        }
+        self.routes
+            .iter()
+            .find(|r| {
+                r.ns.as_deref() == ns && r.prefix.as_deref().is_none_or(|pre| op.starts_with(pre))
+            })
+            .map(|r| r.handler)
+    }
+}
+
+// The static packet table: built once on first use, then shared by every runtime and fork.
+pub fn builtin() -> Arc<PacketTable> {
+    static TABLE: OnceLock<Arc<PacketTable>> = OnceLock::new();
+    TABLE.get_or_init(|| Arc::new(builtin_table())).clone()
+}
+
+fn builtin_table() -> PacketTable {
+    let mut t = PacketTable::default();
+
+    // UI
+    t.exact(Some("ui"), "alert", ui_alert::handle);
+    t.exact(Some("ui"), "select", ui_select::handle);
+    t.exact(Some("ui"), "window", ui_window::handle);
+    t.exact(None, "app", ui_app::handle);
+    t.exact(None, "scope", ui_scope::handle);
+
+    // namespaced families: [funct:tag], [tagspeak:...], [loop:tag@N], [store:rigid@x]
+    t.namespace("funct", funct::handle);
+    t.exact(None, "funct", funct::handle);
+    t.namespace("tagspeak", tagspeak::handle);
+    t.namespace("loop", r#loop::handle);
+    t.namespace("store", store::handle);
+
+    // core
+    t.exact(None, "note", note::handle);
+    t.exact(None, "math", math::handle);
+    t.exact(None, "store", store::handle);
+    t.prefix(None, "store(", store::handle);
+    t.exact(None, "print", print::handle);
+    t.prefix(None, "print(", print::handle);
+    t.exact(None, "var", var::handle);
+    t.exact(None, "unset", store::unset);
+    t.exact(None, "coalesce", coalesce::handle);
+    t.exact(None, "changed", changed::handle);
+    t.exact(None, "dump", dump::handle);
+    t.prefix(None, "dump(", dump::handle);
+    t.exact(None, "json", json::handle);
+    t.prefix(None, "json(", json::handle);
+    t.exact(None, "call", call::handle);
+    t.prefix(None, "call(", call::handle);
+    t.exact(None, "msg", msg::handle);
+    t.exact(None, "int", int::handle);
+    t.exact(None, "bool", bool::handle);
+    t.exact(None, "env", env::handle);
+    t.prefix(None, "env(", env::handle);
+    t.exact(None, "help", help::handle);
+    t.exact(None, "lint", lint::handle);
+    t.exact(None, "cd", cd::handle);
+    t.exact(None, "now", now::handle);
+    t.prefix(None, "now(", now::handle);
+    t.exact(None, "hash", hash::handle);
+    t.prefix(None, "hash(", hash::handle);
+    t.exact(None, "base64", base64::handle);
+    t.prefix(None, "base64(", base64::handle);
+    t.prefix(None, "range(", range::handle);
+    t.exact(None, "uuid", uuid::handle);
+    t.exact(None, "len", len::handle);
+    t.prefix(None, "len(", len::handle);
+    t.exact(None, "rand", rand::handle);
+    t.prefix(None, "rand(", rand::handle);
+    t.exact(None, "array", array::handle);
+    t.prefix(None, "array(", array::handle);
+    t.prefix(None, "slice(", array::slice);
+    t.exact(None, "zip", array::zip);
+    t.prefix(None, "zip(", array::zip);
+    t.prefix(None, "group(", array::group);
+    t.exact(None, "obj", obj::handle);
+    t.prefix(None, "obj(", obj::handle);
+    t.prefix(None, "reflect(", reflect::handle);
+    t.exact(None, "template", template::handle);
+    t.exact(None, "ctx", |_, _| bail!("E_BAD_ARG: [ctx] only works inside [template]{{...}}"));
+
+    // files
+    t.exact(None, "load", load::handle);
+    t.prefix(None, "load(", load::handle);
+    t.prefix(None, "search(", search::handle);
+    t.exact(None, "readlines", readlines::handle);
+    t.prefix(None, "readlines(", readlines::handle);
+    t.prefix(None, "log", log::handle);
+    t.exact(None, "save", save::handle);
+    t.prefix(None, "save(", save::handle);
+    t.exact(None, "file", |_, p| bail!("E_BAD_ARG: [{}] only works inside [save(patch:...)]{{...}}", p.op));
+    t.exact(None, "mod", modify::handle);
+    t.prefix(None, "mod(", modify::handle);
+    t.exact(None, "merge", merge::handle);
+    t.prefix(None, "merge(", merge::handle);
+    t.prefix(None, "get(", query::handle);
+    t.prefix(None, "exists(", query::handle);
+    t.prefix(None, "count(", query::handle);
+
+    // execs
+    t.exact(None, "exec", exec::handle);
+    t.prefix(None, "exec(", exec::handle);
+    t.exact(None, "cmd", |_, p| bail!("E_BAD_ARG: [{}] only works inside [exec]{{...}}", p.op));
+    t.exact(None, "arg", |_, p| bail!("E_BAD_ARG: [{}] only works inside [exec]{{...}}", p.op));
+    t.exact(None, "run", run::handle);
+    t.exact(None, "import", import::handle);
+    t.exact(None, "tagspeak", tagspeak::handle);
+    t.prefix(None, "tagspeak ", tagspeak::handle);
+    t.exact(None, "yellow", confirm::handle);
+    t.exact(None, "confirm", confirm::handle);
+    t.prefix(None, "confirm(", confirm::handle);
+    t.exact(None, "red", red::handle);
+    t.prefix(None, "http(", http::handle);
+    t.prefix(None, "repl(", repl::handle);
+    t.prefix(None, "parse(", parse::handle);
+    t.prefix(None, "field(", |_, p| bail!("E_BAD_ARG: [{}] only works inside [parse(schema)]{{...}}", p.op));
+    t.exact(Some("yellow"), "exec", confirm::handle_exec);
+    t.prefix(Some("yellow"), "exec(", confirm::handle_exec);
+    t.exact(Some("yellow"), "run", confirm::handle_run);
+
+    // flow
+    t.exact(None, "assert", assert::handle);
+    t.exact(None, "interrupt", interrupt::handle);
+    t.exact(None, "return", r#return::handle);
+    t.exact(None, "break", r#break::handle);
+    t.prefix(None, "break(", r#break::handle);
+    t.exact(None, "with", with::handle);
+    t.prefix(None, "retry(", retry::handle);
+    t.exact(None, "once", once::handle);
+    t.exact(None, "defer", defer::handle);
+    t.exact(None, "wait_for", wait_for::handle);
+    t.prefix(None, "wait_for(", wait_for::handle);
+    t.exact(None, "try", |rt, p| r#try::handle(rt, p, None));
+    // a [catch] not consumed by a preceding [try] has nothing to handle
+    t.exact(None, "catch", |_, _| Ok(Value::Unit));
+    t.exact(None, "match", r#match::handle);
+    t.exact(None, "case", |_, p| bail!("E_BAD_ARG: [{}] only works inside [match]{{...}}", p.op));
+    t.exact(None, "default", |_, p| bail!("E_BAD_ARG: [{}] only works inside [match]{{...}}", p.op));
+    t.namespace("iter", iter::handle);
+    t.exact(None, "iter", iter::handle);
+    t.exact(Some("input"), "line", input::handle);
+    t.exact(None, "input", input::handle);
+    // loop forms: [loop3@tag] or [loop@N]{...}
+    t.prefix(None, "loop", r#loop::handle);
+
+    // comparators: [eq@rhs] and [cmp:eq@rhs]
+    for op in ["eq", "ne", "lt", "le", "gt", "ge", "approx"] {
+        t.exact(None, op, compare::handle);
+    }
+    t.namespace("cmp", compare::handle);
+
+    t
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_dispatched_form_resolves() {
+        let table = builtin();
+        let forms: &[(Option<&str>, &str)] = &[
+            (Some("ui"), "alert"), (Some("ui"), "select"), (Some("ui"), "window"), (None, "app"),
+            (None, "scope"), (Some("funct"), "step"), (None, "funct"), (Some("tagspeak"), "run"),
+            (Some("loop"), "tick"), (Some("store"), "rigid"), (None, "note"), (None, "math"),
+            (None, "store"), (None, "store(inc)"), (None, "print"), (None, "print(color:red)"),
+            (None, "var"), (None, "unset"), (None, "coalesce"), (None, "changed"), (None, "dump"), (None, "dump(vars)"), (None, "json"), (None, "json(pretty)"), (None, "call"), (None, "call(1)"),
+            (None, "msg"), (None, "int"), (None, "bool"), (None, "env"), (None, "env(loadfile)"),
+            (None, "help"), (None, "lint"), (None, "cd"), (None, "now"), (None, "now(unix)"),
+            (None, "hash"), (None, "hash(sha256)"), (None, "base64"), (None, "base64(enc)"),
+            (None, "range(1,3)"), (None, "uuid"), (None, "len"), (None, "len(items)"), (None, "rand"),
+            (None, "rand(1,6)"), (None, "array"), (None, "array(sum)"), (None, "slice(1,3)"), (None, "zip"), (None, "zip(object)"), (None, "group(kind)"), (None, "obj"), (None, "obj(has,a)"),
+            (None, "reflect(vars)"), (None, "template"), (None, "ctx"), (None, "load"),
+            (None, "load(pointer:/a)"), (None, "search(a)"), (None, "readlines"), (None, "readlines(nonempty)"), (None, "log"), (None, "log(json)"),
+            (None, "save"), (None, "save(/o.json)"), (None, "file"), (None, "mod"), (None, "mod(a)"), (None, "merge"),
+            (None, "merge(array:concat)"), (None, "get(a.b)"), (None, "exists(a)"),
+            (None, "count(items[*])"), (None, "exec"), (None, "exec(json)"), (None, "cmd"),
+            (None, "arg"), (None, "run"), (None, "import"), (None, "tagspeak"),
+            (None, "tagspeak build"), (None, "yellow"), (None, "confirm"), (None, "confirm(type:X)"), (None, "red"),
+            (None, "http(get)"), (None, "repl(line)"), (None, "parse(json)"), (None, "field(a, str)"), (None, "assert"),
+            (None, "interrupt"), (None, "return"), (None, "break"), (None, "break(2)"),
+            (None, "with"), (None, "retry(3,500)"), (None, "once"), (None, "defer"), (None, "wait_for"), (None, "wait_for(timeout:10)"), (None, "try"), (None, "catch"), (None, "match"),
+            (None, "case"), (None, "default"), (Some("iter"), "item"), (None, "iter"),
+            (Some("input"), "line"), (None, "input"), (None, "eq"), (None, "ne"), (None, "lt"),
+            (None, "le"), (None, "gt"), (None, "ge"), (None, "approx"), (None, "loop"), (None, "loop3"),
+            (Some("cmp"), "eq"), (Some("yellow"), "exec"), (Some("yellow"), "exec(shell)"),
+            (Some("yellow"), "run"),
+        ];
+        for (ns, op) in forms {
+            assert!(table.lookup(*ns, op).is_some(), "no route for {ns:?}:{op}");
+        }
+        assert!(table.lookup(None, "nope").is_none());
+        assert!(table.lookup(Some("input"), "other").is_none());
+        assert!(table.lookup(Some("yellow"), "print").is_none());
+    }
+}
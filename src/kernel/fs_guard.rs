@@ -35,3 +35,15 @@ pub fn resolve(root: &Path, requested: &Path) -> Result<PathBuf> {
     }
     Ok(normalized)
 }
+
+// Creates any missing parent directories of `path`, refusing to build outside `root`.
+pub fn ensure_parent_dirs(root: &Path, path: &Path) -> Result<()> {
+    let Some(parent) = path.parent() else {
+        return Ok(());
+    };
+    if !parent.starts_with(root) {
+        bail!("E_BOUNDARY_RED");
+    }
+    std::fs::create_dir_all(parent)?;
+    Ok(())
+}
@@ -2,11 +2,13 @@
 pub mod ast;
 pub mod boolops;
 pub mod config;
+pub mod dispatch;
 pub mod fs_guard;
 pub mod packet_catalog;
+pub mod policy;
 pub mod runtime;
 pub mod values;
 
-pub use ast::{Arg, BExpr, Comparator, Node, Packet};
+pub use ast::{Arg, BExpr, Comparator, Node, Packet, Span};
 pub use runtime::Runtime;
 pub use values::Value;
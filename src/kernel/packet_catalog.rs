@@ -1,18 +1,84 @@
 pub const KNOWN_PACKET_OPS: &[&str] = &[
     // core
     "note", "math", "store", "print", "dump", "call", "funct", "msg", "int", "bool", "env", "help", "lint",
-    "cd", "len", "rand", "array", "obj", "reflect", "var",
+    "cd", "len", "rand", "array", "obj", "reflect", "var", "unset", "zip", "coalesce", "now", "hash", "assert", "uuid", "base64",
+    "template", "ctx", "changed", "json",
     // files/data
-    "load", "search", "log", "save", "mod", "get", "exists",
+    "load", "search", "readlines", "log", "save", "file", "mod", "get", "exists",
     // execs
-    "exec", "run", "tagspeak", "yellow", "confirm", "red", "http", "repl", "parse",
+    "exec", "cmd", "arg", "run", "import", "tagspeak", "yellow", "confirm", "red", "http", "repl", "parse",
     // flow/cond
-    "iter", "input", "eq", "ne", "lt", "le", "gt", "ge", "if", "then", "else", "or", "comp",
+    "iter", "interrupt", "return", "break", "with", "wait_for", "once", "defer", "try", "catch", "match", "case", "default", "input", "eq", "ne", "lt", "le", "gt", "ge", "approx", "if", "then", "else", "or", "comp",
     "comp!", "merge", "del", "ins", "push", "set", "remove", "append", "delete",
     // UI leaf packets
     "label", "button", "textedit", "textbox", "popup", "separator", "spacer", "checkbox", "app", "scope",
 ];
 
+// Packets only reachable through a `name(...)`/`nameN` form, listed so the catalog covers them.
+const PREFIX_ONLY_OPS: &[&str] = &["loop", "count", "range", "as", "key", "sect", "layout", "retry", "slice", "group", "field"];
+
+// Packets whose main form takes a `{...}` body.
+const BODY_OPS: &[&str] = &[
+    "funct", "loop", "iter", "if", "then", "try", "catch", "match", "case", "default", "array", "obj",
+    "mod", "log", "assert", "repl", "app", "scope", "popup", "layout", "sect", "button", "yellow",
+    "confirm", "http", "with", "exec", "retry", "template", "once", "defer",
+];
+
+// `[ns:op]` namespaces each packet can be written under, e.g. [yellow:exec], [store:rigid].
+const PACKET_NAMESPACES: &[(&str, &[&str])] = &[
+    ("store", &["store"]),
+    ("loop", &["loop"]),
+    ("funct", &["funct"]),
+    ("iter", &["iter"]),
+    ("input", &["input"]),
+    ("exec", &["yellow"]),
+    ("run", &["yellow", "tagspeak"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketShape {
+    pub name: &'static str,
+    pub namespaces: &'static [&'static str],
+    pub takes_body: bool,
+}
+
+// Every known packet with its namespaces and body flag, sorted by name; backs reflect(catalog).
+pub fn catalog() -> Vec<PacketShape> {
+    let mut names: Vec<&'static str> = KNOWN_PACKET_OPS.iter().chain(PREFIX_ONLY_OPS).copied().collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+        .into_iter()
+        .map(|name| PacketShape {
+            name,
+            namespaces: PACKET_NAMESPACES
+                .iter()
+                .find(|(op, _)| *op == name)
+                .map(|(_, ns)| *ns)
+                .unwrap_or(&[]),
+            takes_body: BODY_OPS.contains(&name),
+        })
+        .collect()
+}
+
+// Whether a non-namespaced packet reads its `{...}` body; None for unknown packets.
+pub fn takes_body(op: &str) -> Option<bool> {
+    let lower = op.to_ascii_lowercase();
+    // option forms that read a body even though the bare packet doesn't
+    if lower.starts_with("store(") || lower.starts_with("save(patch") || lower.starts_with("parse(schema") {
+        return Some(true);
+    }
+    let name = lower.split('(').next().unwrap_or("").trim();
+    let name = match name.strip_prefix("loop") {
+        Some(n) if n.chars().all(|c| c.is_ascii_digit()) => "loop",
+        _ => name,
+    };
+    catalog()
+        .into_iter()
+        .find(|shape| shape.name == name)
+        .map(|shape| shape.takes_body)
+}
+
 pub fn is_known_packet(ns: Option<&str>, op: &str) -> bool {
     if let Some(namespace) = ns {
         let ns_lower = namespace.to_ascii_lowercase();
@@ -36,12 +102,35 @@ pub fn is_known_packet(ns: Option<&str>, op: &str) -> bool {
     }
 
     op_lower.starts_with("rand(")
+        || op_lower.starts_with("len(")
+        || op_lower.starts_with("now(")
+        || op_lower.starts_with("hash(")
+        || op_lower.starts_with("base64(")
+        || op_lower.starts_with("print(")
+        || op_lower.starts_with("range(")
+        || op_lower.starts_with("env(")
+        || op_lower.starts_with("obj(")
+        || op_lower.starts_with("array(")
+        || op_lower.starts_with("slice(")
+        || op_lower.starts_with("zip(")
+        || op_lower.starts_with("group(")
+        || op_lower.starts_with("break(")
+        || op_lower.starts_with("call(")
+        || op_lower.starts_with("retry(")
+        || op_lower.starts_with("wait_for(")
         || op_lower.starts_with("reflect(")
         || op_lower.starts_with("search(")
+        || op_lower.starts_with("readlines(")
+        || op_lower.starts_with("dump(")
+        || op_lower.starts_with("json(")
+        || op_lower.starts_with("load(")
+        || op_lower.starts_with("save(")
         || op_lower.starts_with("log")
         || op_lower.starts_with("exec(")
         || op_lower.starts_with("http(")
+        || op_lower.starts_with("confirm(")
         || op_lower.starts_with("parse(")
+        || op_lower.starts_with("field(")
         || op_lower.starts_with("layout(")
         || op_lower.starts_with("tagspeak ")
         || op_lower.starts_with("if(")
@@ -59,6 +148,9 @@ pub fn is_known_packet(ns: Option<&str>, op: &str) -> bool {
         || op_lower.starts_with("delete")
         || op_lower.starts_with("get(")
         || op_lower.starts_with("exists(")
+        || op_lower.starts_with("count(")
+        || op_lower.starts_with("store(")
+        || op_lower.starts_with("as(")
         || op_lower.starts_with("key(")
         || op_lower.starts_with("sect(")
 }
@@ -70,21 +162,27 @@ pub fn suggest_packet(ns: Option<&str>, op: &str) -> Option<&'static str> {
         }
     }
 
+    // compare the bare name: `stroe(x)` should still suggest `store`
     let op_norm = op.to_ascii_lowercase();
+    let op_norm = op_norm.split('(').next().unwrap_or("").trim();
     let mut best: Option<&'static str> = None;
     let mut best_score = usize::MAX;
 
-    for candidate in KNOWN_PACKET_OPS {
-        let score = edit_distance(&op_norm, candidate);
+    for candidate in KNOWN_PACKET_OPS.iter().chain(PREFIX_ONLY_OPS) {
+        let score = edit_distance(op_norm, candidate);
         if score < best_score {
             best_score = score;
             best = Some(*candidate);
         }
     }
 
-    if best_score <= 2 { best } else { None }
+    // two edits on a 1-2 letter name could turn it into almost anything
+    let limit = if op_norm.chars().count() <= 2 { 1 } else { 2 };
+    if best_score <= limit { best } else { None }
 }
 
+// Levenshtein distance where swapping two neighbouring letters counts as one edit
+// (optimal string alignment), so `stroe` is one step from `store`.
 fn edit_distance(a: &str, b: &str) -> usize {
     let a_chars: Vec<char> = a.chars().collect();
     let b_chars: Vec<char> = b.chars().collect();
@@ -107,8 +205,40 @@ fn edit_distance(a: &str, b: &str) -> usize {
             dp[i][j] = (dp[i - 1][j] + 1)
                 .min(dp[i][j - 1] + 1)
                 .min(dp[i - 1][j - 1] + cost);
+            if i > 1
+                && j > 1
+                && a_chars[i - 1] == b_chars[j - 2]
+                && a_chars[i - 2] == b_chars[j - 1]
+            {
+                dp[i][j] = dp[i][j].min(dp[i - 2][j - 2] + 1);
+            }
         }
     }
 
     dp[a_chars.len()][b_chars.len()]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suggests_close_names() {
+        assert_eq!(suggest_packet(None, "stroe"), Some("store"));
+        assert_eq!(suggest_packet(None, "prnt"), Some("print"));
+        assert_eq!(suggest_packet(None, "lodd(json)"), Some("load"));
+        assert_eq!(suggest_packet(None, "frobnicate"), None);
+    }
+
+    #[test]
+    fn catalog_lists_shapes() {
+        let shapes = catalog();
+        let find = |name: &str| shapes.iter().find(|s| s.name == name).cloned();
+        let store = find("store").expect("store missing");
+        assert_eq!(store.namespaces, &["store"]);
+        assert!(!store.takes_body);
+        assert!(find("loop").is_some_and(|s| s.takes_body && s.namespaces == ["loop"]));
+        assert!(find("mod").is_some_and(|s| s.takes_body));
+        assert!(shapes.windows(2).all(|w| w[0].name < w[1].name));
+    }
+}
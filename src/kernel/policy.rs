@@ -0,0 +1,69 @@
+use std::path::Path;
+
+// Packet policy declared in red.tgsk:
+//   deny: exec, http, run        -> these packets fail with E_PACKET_DENIED
+//   allow-only: msg, print, math -> everything else fails with E_PACKET_DENIED
+//   max-runtime-ms: 30000        -> the whole script aborts with E_TIME_BUDGET after 30s
+//   stream-threshold: 1048576    -> JSON files above this many bytes are parsed from a reader
+// Directives are plain lines; the rest of red.tgsk (comments, packets) is ignored.
+#[derive(Clone, Debug, Default)]
+pub struct PacketPolicy {
+    pub deny: Vec<String>,
+    pub allow_only: Option<Vec<String>>,
+    pub max_runtime_ms: Option<u64>,
+    pub stream_threshold: Option<u64>,
+}
+
+impl PacketPolicy {
+    pub fn load(root: Option<&Path>) -> Self {
+        root.and_then(|r| std::fs::read_to_string(r.join("red.tgsk")).ok())
+            .map(|src| Self::parse(&src))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(src: &str) -> Self {
+        let mut policy = Self::default();
+        for line in src.lines() {
+            let line = line.trim();
+            if let Some(rest) = strip_directive(line, "deny:") {
+                policy.deny.extend(names(rest));
+            } else if let Some(rest) = strip_directive(line, "allow-only:") {
+                policy.allow_only.get_or_insert_with(Vec::new).extend(names(rest));
+            } else if let Some(rest) = strip_directive(line, "max-runtime-ms:") {
+                policy.max_runtime_ms = names(rest).next().and_then(|n| n.parse().ok());
+            } else if let Some(rest) = strip_directive(line, "stream-threshold:") {
+                policy.stream_threshold = names(rest).next().and_then(|n| n.parse().ok());
+            }
+        }
+        policy
+    }
+
+    pub fn allows(&self, name: &str) -> bool {
+        let name = name.to_ascii_lowercase();
+        if self.deny.contains(&name) {
+            return false;
+        }
+        match &self.allow_only {
+            Some(list) => list.contains(&name),
+            None => true,
+        }
+    }
+}
+
+fn strip_directive<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let head = line.get(..key.len())?;
+    if head.eq_ignore_ascii_case(key) {
+        Some(&line[key.len()..])
+    } else {
+        None
+    }
+}
+
+fn names(list: &str) -> impl Iterator<Item = String> + '_ {
+    // trailing `//` or `#` comments are allowed after the list
+    let list = list.split("//").next().unwrap_or("");
+    let list = list.split('#').next().unwrap_or("");
+    list.split(',')
+        .map(|s| s.trim().to_ascii_lowercase())
+        .filter(|s| !s.is_empty())
+}
@@ -1,24 +1,211 @@
 use anyhow::{Result, bail};
-use crate::packets::core::var as pkt_var;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 
-use crate::kernel::ast::{Arg, BExpr, Node, Packet};
+use crate::kernel::ast::{Arg, BExpr, Node, Packet, Span};
+use crate::kernel::boolops::Tolerance;
+use crate::kernel::config;
+use crate::kernel::dispatch::{self, PacketTable};
 use crate::kernel::fs_guard::find_root;
 use crate::kernel::packet_catalog::suggest_packet;
+use crate::kernel::policy::PacketPolicy;
 use crate::kernel::values::Value;
 
 pub struct Runtime {
     pub vars: HashMap<String, Value>,
     pub ctx_vars: HashMap<String, Vec<(BExpr, Value)>>,
     pub rigid: HashSet<String>,
+    pub typed: HashMap<String, String>, // declared types from [store:num|str|bool@name]
     pub last: Value,
+    pub last_store_changed: bool, // read by [changed]: did the latest [store] move its variable
     pub tags: HashMap<String, Vec<Node>>, // named blocks from [funct:tag]{...}
+    pub deferred: Vec<Vec<Node>>, // [defer]{...} blocks waiting for their funct/script to exit
+    pub funct_params: HashMap<String, Vec<crate::packets::funct::Param>>, // from [funct:tag(a, b=1)]
+    pub search_cache: Arc<HashMap<PathBuf, (SystemTime, Arc<serde_json::Value>)>>, // parsed files [search] read; shared with forks
     pub effective_root: Option<PathBuf>,
     pub cwd: PathBuf,
     // safety limits
     pub call_depth: usize,
     pub max_call_depth: usize,
+    pub eval_depth: usize, // nesting of eval calls; 0 means the next eval is top-level
+    pub policy: PacketPolicy, // deny/allow-only directives from red.tgsk
+    pub packets: Arc<PacketTable>, // (ns, op) -> handler; see kernel/dispatch.rs
+    pub started: Instant,
+    pub time_budget: Option<Duration>, // TAGSPEAK_MAX_RUNTIME_MS, else red.tgsk max-runtime-ms
+    pub stream_threshold: u64, // bytes; TAGSPEAK_STREAM_THRESHOLD, else red.tgsk stream-threshold
+    pub approx_tol: Tolerance, // `~=` / [approx] tolerance, read from config once at startup
+    pub doc_max_depth: usize, // nesting [mod]/[merge] will traverse (docs.max_depth / TAGSPEAK_MAX_DOC_DEPTH)
+    pub dry_run: bool, // TAGSPEAK_DRY_RUN / --dry-run: writes, [exec] and [http] only report what they would do
+    // debugging: TAGSPEAK_TRACE=1 logs each packet, its resolved arg, and its result
+    pub trace: bool,
+    pub trace_depth: usize,
+    pub trace_capture: Option<Vec<String>>, // collect trace lines here instead of stderr
+}
+
+// A packet failure pinned to the packet that raised it. `source` is the script
+// text the span points into; whoever parsed that script attaches it, and once
+// present the error renders as a full error box.
+#[derive(Debug)]
+pub struct RuntimeError {
+    pub span: Span,
+    pub detail: String,
+    pub source: Option<String>,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(src) => write!(
+                f,
+                "{}",
+                crate::error_style::render_runtime_error(src, self.span, &self.detail)
+            ),
+            None => write!(f, "{}", self.detail),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {}
+
+// Control flow that unwinds through packets like an error until the construct that
+// owns it turns it back into a value:
+//   Interrupt -> the outermost eval (or the [run] that loaded the script)
+//   Return    -> the enclosing [call]
+//   Break(n)  -> the n-th enclosing loop ([loop], [iter]); 1 is the innermost
+// If nothing owns it, it surfaces as the error below.
+#[derive(Debug)]
+pub enum FlowSignal {
+    Interrupt(Value),
+    Return(Value),
+    Break(usize),
+}
+
+impl fmt::Display for FlowSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowSignal::Interrupt(_) => write!(f, "E_INTERRUPT: [interrupt] escaped its script"),
+            FlowSignal::Return(_) => write!(f, "E_RETURN_OUTSIDE_FUNCT: [return] used outside a funct"),
+            FlowSignal::Break(_) => write!(f, "E_BREAK_OUTSIDE_LOOP: [break] has no loop to leave"),
+        }
+    }
+}
+
+impl std::error::Error for FlowSignal {}
+
+// An interrupt ends the script it was raised in; its payload becomes the result.
+pub fn catch_interrupt(out: Result<Value>) -> Result<Value> {
+    match out {
+        Err(err) => match err.downcast::<FlowSignal>() {
+            Ok(FlowSignal::Interrupt(v)) => Ok(v),
+            Ok(other) => Err(other.into()),
+            Err(err) => Err(err),
+        },
+        ok => ok,
+    }
+}
+
+// Loops feed each iteration's result through here: Ok(true) keeps going, Ok(false)
+// means a [break] ended this loop, and a multi-level break moves on one loop outward.
+pub fn handle_loop_signal(out: Result<Value>, last: &mut Value) -> Result<bool> {
+    match out {
+        Ok(v) => {
+            *last = v;
+            Ok(true)
+        }
+        Err(err) => match err.downcast::<FlowSignal>() {
+            Ok(FlowSignal::Break(levels)) if levels <= 1 => Ok(false),
+            Ok(FlowSignal::Break(levels)) => Err(FlowSignal::Break(levels - 1).into()),
+            Ok(other) => Err(other.into()),
+            Err(err) => Err(err),
+        },
+    }
+}
+
+// Names bound for every iteration of [loop] / [iter]: 0-based index, first/last flags and
+// the total count. A nested loop puts the outer loop's values back when it finishes.
+pub const LOOP_META: [&str; 4] = ["__loop_index", "__loop_first", "__loop_last", "__loop_count"];
+
+pub fn set_loop_meta(rt: &mut Runtime, idx: usize, count: usize) -> Result<()> {
+    rt.set_var(LOOP_META[0], Value::Num(idx as f64))?;
+    rt.set_var(LOOP_META[1], Value::Bool(idx == 0))?;
+    rt.set_var(LOOP_META[2], Value::Bool(idx + 1 == count))?;
+    rt.set_var(LOOP_META[3], Value::Num(count as f64))
+}
+
+pub fn save_loop_meta(rt: &Runtime) -> Vec<Option<Value>> {
+    LOOP_META.iter().map(|name| rt.vars.get(*name).cloned()).collect()
+}
+
+pub fn restore_loop_meta(rt: &mut Runtime, saved: Vec<Option<Value>>) {
+    for (name, val) in LOOP_META.iter().zip(saved) {
+        match val {
+            Some(v) => rt.vars.insert(name.to_string(), v),
+            None => rt.vars.remove(*name),
+        };
+    }
+}
+
+// Packet errors lead with an `E_*` code (`E_PATH_MISSING: path not found`);
+// wrappers may prefix context, so the first code anywhere in the message wins.
+pub fn error_code(err: &anyhow::Error) -> Option<String> {
+    let detail = match err.downcast_ref::<RuntimeError>() {
+        Some(rt_err) => rt_err.detail.clone(),
+        None => format!("{err:#}"),
+    };
+    let is_code_char = |c: char| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_';
+    let mut from = 0;
+    while let Some(idx) = detail[from..].find("E_").map(|i| i + from) {
+        let len = detail[idx..]
+            .find(|c: char| !is_code_char(c))
+            .unwrap_or(detail.len() - idx);
+        let starts_word = !detail[..idx].ends_with(|c: char| c.is_ascii_alphanumeric() || c == '_');
+        let ends_code = matches!(detail[idx + len..].chars().next(), None | Some(':'));
+        if starts_word && ends_code && len > 2 {
+            return Some(detail[idx..idx + len].to_string());
+        }
+        from = idx + 2;
+    }
+    None
+}
+
+// strings are quoted in traces so empty values and whitespace stay visible
+fn trace_value(v: &Value) -> String {
+    match v {
+        Value::Str(s) => format!("{s:?}"),
+        other => other.to_string(),
+    }
+}
+
+// innermost packet wins; parse errors already carry their own panels
+fn attach_span(err: anyhow::Error, span: Span) -> anyhow::Error {
+    if !span.is_known()
+        || err.is::<RuntimeError>()
+        || err.is::<FlowSignal>()
+        || err.is::<crate::router::ParseError>()
+    {
+        return err;
+    }
+    RuntimeError {
+        span,
+        detail: format!("{err:#}"),
+        source: None,
+    }
+    .into()
+}
+
+pub fn attach_source(err: anyhow::Error, src: &str) -> anyhow::Error {
+    match err.downcast::<RuntimeError>() {
+        Ok(mut rt_err) => {
+            if rt_err.source.is_none() {
+                rt_err.source = Some(src.to_string());
+            }
+            rt_err.into()
+        }
+        Err(err) => err,
+    }
 }
 
 impl Runtime {
@@ -38,19 +225,88 @@ impl Runtime {
             Some(r) => start.strip_prefix(r).unwrap_or(Path::new("")).to_path_buf(),
             None => PathBuf::new(),
         };
+        let policy = PacketPolicy::load(root.as_deref());
+        let time_budget = std::env::var("TAGSPEAK_MAX_RUNTIME_MS")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .or(policy.max_runtime_ms)
+            .map(Duration::from_millis);
+        let stream_threshold = std::env::var("TAGSPEAK_STREAM_THRESHOLD")
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+            .or(policy.stream_threshold)
+            .unwrap_or(64 * 1024 * 1024);
+        let cfg = config::load(root.as_deref());
         Ok(Self {
             vars: HashMap::new(),
             ctx_vars: HashMap::new(),
             rigid: HashSet::new(),
+            typed: HashMap::new(),
             last: Value::Unit,
+            last_store_changed: false,
             tags: HashMap::new(),
+            deferred: Vec::new(),
+            funct_params: HashMap::new(),
+            search_cache: Arc::new(HashMap::new()),
             effective_root: root,
             cwd,
             call_depth: 0,
+            eval_depth: 0,
             max_call_depth: std::env::var("TAGSPEAK_MAX_CALL_DEPTH").ok().and_then(|s| s.parse::<usize>().ok()).unwrap_or(256),
+            trace: std::env::var("TAGSPEAK_TRACE")
+                .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(false),
+            trace_depth: 0,
+            trace_capture: None,
+            policy,
+            packets: dispatch::builtin(),
+            started: Instant::now(),
+            time_budget,
+            stream_threshold,
+            approx_tol: Tolerance::configured(&cfg),
+            doc_max_depth: cfg.doc_max_depth,
+            dry_run: std::env::var("TAGSPEAK_DRY_RUN")
+                .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+                .unwrap_or(false),
         })
     }
 
+    // A child runtime for side-effect-free evaluation. Everything is copied, Docs
+    // included (their serde_json trees clone deeply), so edits in the child never
+    // reach the parent; results come back only through the child's return value.
+    // The [search] parse cache is read-only data, so it is shared rather than copied.
+    // There is no async/detached execution today, so a fork always runs inline.
+    pub fn fork(&self) -> Runtime {
+        Runtime {
+            vars: self.vars.clone(),
+            ctx_vars: self.ctx_vars.clone(),
+            rigid: self.rigid.clone(),
+            typed: self.typed.clone(),
+            last: self.last.clone(),
+            last_store_changed: self.last_store_changed,
+            tags: self.tags.clone(),
+            deferred: Vec::new(), // the parent's pending blocks stay with the parent
+            funct_params: self.funct_params.clone(),
+            search_cache: self.search_cache.clone(),
+            effective_root: self.effective_root.clone(),
+            cwd: self.cwd.clone(),
+            call_depth: self.call_depth,
+            eval_depth: self.eval_depth,
+            max_call_depth: self.max_call_depth,
+            trace: self.trace,
+            trace_depth: self.trace_depth,
+            trace_capture: None,
+            policy: self.policy.clone(),
+            packets: self.packets.clone(),
+            started: self.started,
+            time_budget: self.time_budget,
+            stream_threshold: self.stream_threshold,
+            approx_tol: self.approx_tol,
+            doc_max_depth: self.doc_max_depth,
+            dry_run: self.dry_run,
+        }
+    }
+
     // ---- variables ----
     pub fn set_var(&mut self, name: &str, val: Value) -> Result<()> {
         self.vars.insert(name.to_string(), val);
@@ -73,6 +329,7 @@ impl Runtime {
                 };
                 tmp.vars = self.vars.clone();
                 tmp.tags = self.tags.clone();
+                tmp.funct_params = self.funct_params.clone();
                 if crate::packets::conditionals::eval_cond(&mut tmp, cond).unwrap_or(false) {
                     return Some(val.clone());
                 }
@@ -92,6 +349,7 @@ impl Runtime {
         Ok(match arg {
             Arg::Number(n) => Value::Num(*n),
             Arg::Str(s) => Value::Str(s.clone()),
+            Arg::Ident(id) if id == "_" => self.last.clone(), // placeholder for the piped value
             Arg::Ident(id) => self.get_var(id).unwrap_or(Value::Unit),
             _ => Value::Unit, // reserve for CondSrc/etc
         })
@@ -99,16 +357,81 @@ impl Runtime {
 
     // ---- eval ----
     pub fn eval(&mut self, n: &Node) -> Result<Value> {
+        let top = self.eval_depth == 0;
+        if top {
+            // the budget covers one top-level eval, so REPL idle time between lines is free
+            self.started = Instant::now();
+            self.hoist_functs(n)?;
+        }
+        let mark = self.deferred.len();
+        self.eval_depth += 1;
+        let mut out = self.eval_node(n);
+        if top {
+            out = self.run_deferred(mark, out);
+        }
+        self.eval_depth -= 1;
+        if self.eval_depth > 0 {
+            return out;
+        }
+        let out = catch_interrupt(out)?;
+        self.last = out.clone();
+        Ok(out)
+    }
+
+    // Runs the [defer] blocks registered since `mark`, newest first, once a funct or the
+    // whole script is done. They run whether `out` succeeded or not; the scope's own
+    // result (or error) wins over theirs, and a deferred failure only surfaces when
+    // the scope itself succeeded.
+    pub fn run_deferred(&mut self, mark: usize, out: Result<Value>) -> Result<Value> {
+        let mut failed = None;
+        while self.deferred.len() > mark {
+            let Some(block) = self.deferred.pop() else { break };
+            if let Err(err) = self.eval(&Node::Block(block))
+                && failed.is_none()
+            {
+                failed = Some(err);
+            }
+        }
+        match (out, failed) {
+            (Ok(v), None) => {
+                self.last = v.clone();
+                Ok(v)
+            }
+            (Ok(_), Some(err)) => Err(err),
+            (Err(err), _) => Err(err),
+        }
+    }
+
+    // Registers a script's top-level [funct:...] definitions up front, so a call may come
+    // before the definition. Nested definitions still register when they run.
+    pub fn hoist_functs(&mut self, n: &Node) -> Result<()> {
+        let top = match n {
+            Node::Chain(nodes) | Node::Block(nodes) => nodes.as_slice(),
+            other => std::slice::from_ref(other),
+        };
+        for node in top {
+            if let Node::Packet(def) = node
+                && def.ns.as_deref() == Some("funct")
+                && let Some(body) = &def.body
+            {
+                crate::packets::funct::define(self, &def.op, body).map_err(|e| attach_span(e, def.span))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn eval_node(&mut self, n: &Node) -> Result<Value> {
         let out = match n {
             Node::Chain(v) | Node::Block(v) => self.eval_list(v)?,
-            Node::Packet(p) => self.eval_packet(p)?,
+            Node::Packet(p) => self.eval_packet(p).map_err(|e| attach_span(e, p.span))?,
             Node::If {
                 cond,
                 then_b,
                 else_b,
+                span,
             } => {
                 // [myth] goal: runtime branching
-                if self.eval_if(cond)? {
+                if self.eval_if(cond).map_err(|e| attach_span(e, *span))? {
                     self.eval_list(then_b)?
                 } else if else_b.is_empty() {
                     Value::Unit
@@ -123,97 +446,125 @@ impl Runtime {
 
     fn eval_list(&mut self, list: &[Node]) -> Result<Value> {
         let mut last = Value::Unit;
-        for node in list {
-            last = self.eval(node)?;
+        let mut i = 0;
+        while i < list.len() {
+            // [catch] parses as its own packet; pair it with the [try] right before it
+            if let (Node::Packet(t), Some(Node::Packet(c))) = (&list[i], list.get(i + 1))
+                && t.ns.is_none()
+                && t.op == "try"
+                && c.ns.is_none()
+                && c.op == "catch"
+            {
+                last = crate::packets::r#try::handle(self, t, Some(c))
+                    .map_err(|e| attach_span(e, c.span))?;
+                self.last = last.clone();
+                i += 2;
+                continue;
+            }
+            self.check_budget()?;
+            last = self.eval(&list[i])?;
+            i += 1;
         }
         Ok(last)
     }
 
-    fn eval_packet(&mut self, p: &Packet) -> Result<Value> {
-        match (p.ns.as_deref(), p.op.as_str()) {
-            // UI namespace
-            (Some("ui"), "alert") => crate::packets::ui_alert::handle(self, p),
-            (Some("ui"), "select") => crate::packets::ui_select::handle(self, p),
-            (Some("ui"), "window") => crate::packets::ui_window::handle(self, p),
-            (None, "app") => crate::packets::ui_app::handle(self, p),
-            (None, "scope") => crate::packets::ui_scope::handle(self, p),
-            // namespaced
-            (Some("funct"), _) => crate::packets::funct::handle(self, p),
-            (None, "funct") => crate::packets::funct::handle(self, p),
-            (Some("tagspeak"), _) => crate::packets::tagspeak::handle(self, p),
-
-            // allow namespaced loop syntax: [loop:tag@N]
-            (Some("loop"), _) => crate::packets::r#loop::handle(self, p),
-            // allow namespaced store modes: [store:rigid@x], [store:context(cond)@x]
-            (Some("store"), _) => crate::packets::store::handle(self, p),
-
-            // core
-            (None, "note") => crate::packets::note::handle(self, p),
-            (None, "math") => crate::packets::math::handle(self, p),
-            (None, "store") => crate::packets::store::handle(self, p),
-            (None, "print") => crate::packets::print::handle(self, p),
-            (None, "var") => pkt_var::handle(self, p),
-            (None, "dump") => crate::packets::dump::handle(self, p),
-            (None, "call") => crate::packets::call::handle(self, p),
-            (None, "msg") => crate::packets::msg::handle(self, p),
-            (None, "int") => crate::packets::int::handle(self, p),
-            (None, "bool") => crate::packets::bool::handle(self, p),
-            (None, "env") => crate::packets::env::handle(self, p),
-            (None, "help") => crate::packets::help::handle(self, p),
-            (None, "lint") => crate::packets::lint::handle(self, p),
-            (None, "cd") => crate::packets::cd::handle(self, p),
-            (None, "len") => crate::packets::len::handle(self, p),
-            (None, "rand") => crate::packets::rand::handle(self, p),
-            (None, op) if op.starts_with("rand(") => crate::packets::rand::handle(self, p),
-            (None, "array") => crate::packets::array::handle(self, p),
-            (None, "obj") => crate::packets::obj::handle(self, p),
-            (None, op) if op.starts_with("reflect(") => crate::packets::reflect::handle(self, p),
-            (None, "load") => crate::packets::load::handle(self, p),
-            (None, op) if op.starts_with("search(") => crate::packets::search::handle(self, p),
-            (None, op) if op.starts_with("log") => crate::packets::log::handle(self, p),
-            (None, "save") => crate::packets::save::handle(self, p),
-            (None, "mod") => crate::packets::modify::handle(self, p),
-            (None, op) if op.starts_with("mod(") => crate::packets::modify::handle(self, p),
-            (None, "exec") => crate::packets::exec::handle(self, p),
-            (None, op) if op.starts_with("exec(") => crate::packets::exec::handle(self, p),
-            (None, "run") => crate::packets::run::handle(self, p),
-            (None, op) if op == "tagspeak" || op.starts_with("tagspeak ") => {
-                crate::packets::tagspeak::handle(self, p)
-            }
-            (None, "yellow") => crate::packets::confirm::handle(self, p),
-            (None, "confirm") => crate::packets::confirm::handle(self, p),
-            (None, "red") => crate::packets::red::handle(self, p),
-            (None, op) if op.starts_with("http(") => crate::packets::http::handle(self, p),
-            (None, op) if op.starts_with("repl(") => crate::packets::repl::handle(self, p),
-            (None, op) if op.starts_with("parse(") => crate::packets::parse::handle(self, p),
-            (None, op) if op.starts_with("get(") || op.starts_with("exists(") => {
-                crate::packets::query::handle(self, p)
-            }
-            (None, "iter") => crate::packets::iter::handle(self, p),
-            (Some("input"), "line") => crate::packets::input::handle(self, p),
-            (None, "input") => crate::packets::input::handle(self, p),
-            (None, op) if matches!(op, "eq" | "ne" | "lt" | "le" | "gt" | "ge") => {
-                crate::packets::compare::handle(self, p)
-            }
+    // Every block (loop and funct bodies included) runs through eval_list, so checking
+    // before each node bounds the whole script without hooks in the loop packets.
+    pub fn check_budget(&self) -> Result<()> {
+        if let Some(budget) = self.time_budget
+            && self.started.elapsed() > budget
+        {
+            bail!(
+                "E_TIME_BUDGET: script exceeded its {}ms runtime budget",
+                budget.as_millis()
+            );
+        }
+        Ok(())
+    }
 
-            // loop forms: [loop3@tag] or [loop@N]{...}
-            (None, op) if op.starts_with("loop") => crate::packets::r#loop::handle(self, p),
+    // Packets that pause ([wait_for], [retry]) sleep through here: a pause that would run
+    // past the budget fails with E_TIME_BUDGET up front instead of overshooting it.
+    pub fn sleep(&self, pause: Duration) -> Result<()> {
+        if let Some(budget) = self.time_budget
+            && self.started.elapsed() + pause > budget
+        {
+            bail!(
+                "E_TIME_BUDGET: script exceeded its {}ms runtime budget",
+                budget.as_millis()
+            );
+        }
+        std::thread::sleep(pause);
+        Ok(())
+    }
 
-            // namespaced comparators: [cmp:eq@rhs]
-            (Some("cmp"), _) => crate::packets::compare::handle(self, p),
+    fn eval_packet(&mut self, p: &Packet) -> Result<Value> {
+        self.check_policy(p)?;
+        if !self.trace {
+            return self.dispatch_packet(p);
+        }
+        // one line per packet once it finishes, indented by nesting depth
+        // (packets inside a body therefore log before their parent)
+        let arg = match &p.arg {
+            Some(Arg::Ident(id)) => format!("@{id}={}", trace_value(&self.get_var(id).unwrap_or(Value::Unit))),
+            Some(a) => format!("@{}", trace_value(&self.resolve_arg(a)?)),
+            None => String::new(),
+        };
+        self.trace_depth += 1;
+        let out = self.dispatch_packet(p);
+        self.trace_depth -= 1;
+        let result = match &out {
+            Ok(v) => trace_value(v),
+            Err(e) => format!("error: {e:#}"),
+        };
+        let name = match &p.ns {
+            Some(ns) => format!("{ns}:{}", p.op),
+            None => p.op.clone(),
+        };
+        let line = format!(
+            "{}[{name}{arg}] => {result}",
+            "  ".repeat(self.trace_depth)
+        );
+        match self.trace_capture.as_mut() {
+            Some(lines) => lines.push(line),
+            None => eprintln!("trace: {line}"),
+        }
+        out
+    }
 
-            // namespaced yellow sugar
-            (Some("yellow"), "exec") => crate::packets::confirm::handle_exec(self, p),
-            (Some("yellow"), "run") => crate::packets::confirm::handle_run(self, p),
+    // The namespace names a packet family ([store:rigid], [loop:tag]); [yellow:exec]
+    // wraps a real packet, so both halves must pass.
+    fn check_policy(&self, p: &Packet) -> Result<()> {
+        let base = p.op.split('(').next().unwrap_or("").trim();
+        // [tagspeak:run@x] and [tagspeak run x] are spellings of [run], so they answer to its policy too
+        let sub = base.strip_prefix("tagspeak ").map(str::trim_start);
+        let names: &[&str] = match (p.ns.as_deref(), sub) {
+            (Some("yellow"), _) => &["yellow", base],
+            (Some("tagspeak"), _) => &["tagspeak", base],
+            (Some(ns), _) => &[ns],
+            (None, Some(verb)) => &["tagspeak", verb],
+            (None, None) => &[base],
+        };
+        if let Some(name) = names.iter().find(|n| !self.policy.allows(n)) {
+            bail!("E_PACKET_DENIED: [{name}] is disabled by red.tgsk policy");
+        }
+        Ok(())
+    }
 
-            other => {
-                let suggestion = suggest_packet(other.0, other.1);
-                if let Some(s) = suggestion {
-                    bail!("unknown operation: {:?} (did you mean '{s}'?)", other);
-                } else {
-                    bail!("unknown operation: {:?}", other);
-                }
-            }
+    fn dispatch_packet(&mut self, p: &Packet) -> Result<Value> {
+        if let Some(handler) = self.packets.lookup(p.ns.as_deref(), &p.op) {
+            return handler(self, p);
+        }
+        let other = (p.ns.as_deref(), p.op.as_str());
+        let at = if p.span.is_known() {
+            format!(" at line {}, col {}", p.span.line, p.span.col)
+        } else {
+            String::new()
+        };
+        let suggestion = suggest_packet(other.0, other.1);
+        if let Some(s) = suggestion {
+            bail!("unknown operation: {:?}{at} (did you mean '{s}'?)", other);
+        } else {
+            bail!("unknown operation: {:?}{at}", other);
         }
     }
 
@@ -250,4 +601,151 @@ mod tests {
 
         fs::remove_dir_all(base).unwrap();
     }
+
+    #[test]
+    fn trace_lists_chain_packets_in_order() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.trace = true;
+        rt.trace_capture = Some(Vec::new());
+        let node = crate::router::parse("[math@2+3]>[store@n]>[msg@\"n=\" + n]")
+            .map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        let lines = rt.trace_capture.take().unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                "[math@\"2+3\"] => 5".to_string(),
+                "[store@n=()] => 5".to_string(),
+                "[msg@\"\\\"n=\\\" + n\"] => \"n=5\"".to_string(),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn packet_errors_carry_codes() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let node = crate::router::parse("[get(a.b)@nope]").map_err(anyhow::Error::new)?;
+        let err = rt.eval(&node).unwrap_err();
+        assert_eq!(error_code(&err).as_deref(), Some("E_HANDLE_UNKNOWN"));
+
+        let node = crate::router::parse("[int@1]>[store:rigid@x]>[store:rigid@x]")
+            .map_err(anyhow::Error::new)?;
+        let err = rt.eval(&node).unwrap_err();
+        assert_eq!(error_code(&err).as_deref(), Some("E_VAR_EXISTS"));
+        assert!(err.to_string().contains("'x' already exists"), "unexpected error: {err}");
+        Ok(())
+    }
+
+    #[test]
+    fn red_policy_denies_packets() {
+        let base = std::env::temp_dir().join(format!("tgsk_rt_policy_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "// sandbox\ndeny: exec, http\n").unwrap();
+        let script = base.join("main.tgsk");
+
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let node = crate::router::parse("[msg@\"ok\"]>[store@a]>[exec@\"echo hi\"]>[store@b]").unwrap();
+        let err = rt.eval(&node).unwrap_err();
+        assert_eq!(error_code(&err).as_deref(), Some("E_PACKET_DENIED"));
+        assert!(err.to_string().contains("[exec]"), "unexpected error: {err}");
+        assert_eq!(rt.get_var("a"), Some(Value::Str("ok".into())));
+        assert_eq!(rt.get_var("b"), None);
+
+        fs::write(base.join("red.tgsk"), "allow-only: msg, store\n").unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let node = crate::router::parse("[msg@\"ok\"]>[store@a]").unwrap();
+        assert!(rt.eval(&node).is_ok());
+        let node = crate::router::parse("[math@1+1]").unwrap();
+        let err = rt.eval(&node).unwrap_err();
+        assert_eq!(error_code(&err).as_deref(), Some("E_PACKET_DENIED"));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn red_policy_deny_run_covers_tagspeak_spellings() {
+        let base = std::env::temp_dir().join(format!("tgsk_rt_policy_run_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "deny: run\n").unwrap();
+        fs::write(base.join("child.tgsk"), "[msg@\"child\"]").unwrap();
+
+        for src in ["[run@\"/child.tgsk\"]", "[tagspeak:run@\"/child.tgsk\"]", "[tagspeak run@\"/child.tgsk\"]"] {
+            let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+            let node = crate::router::parse(src).unwrap();
+            let err = rt.eval(&node).unwrap_err();
+            assert_eq!(error_code(&err).as_deref(), Some("E_PACKET_DENIED"), "{src}: {err}");
+            assert!(err.to_string().contains("[run]"), "{src}: {err}");
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn time_budget_aborts_long_loops() {
+        let base = std::env::temp_dir().join(format!("tgsk_rt_budget_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "max-runtime-ms: 50\n").unwrap();
+
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+        assert_eq!(rt.time_budget, Some(Duration::from_millis(50)));
+        let node = crate::router::parse("[loop@1000000]{[math@1+1]}").unwrap();
+        let started = Instant::now();
+        let err = rt.eval(&node).unwrap_err();
+        assert_eq!(error_code(&err).as_deref(), Some("E_TIME_BUDGET"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // idle time between top-level evals (a REPL waiting for input) is not charged
+        std::thread::sleep(Duration::from_millis(80));
+        let node = crate::router::parse("[math@1+1]").unwrap();
+        assert_eq!(rt.eval(&node).unwrap(), Value::Num(2.0));
+
+        // pauses inside packets are checked against what is left
+        for src in ["[wait_for(timeout:5000)@(missing)]", "[retry(3,1000)]{[assert@(1==2)]}"] {
+            let started = Instant::now();
+            let err = rt.eval(&crate::router::parse(src).unwrap()).unwrap_err();
+            assert_eq!(error_code(&err).as_deref(), Some("E_TIME_BUDGET"), "{src}: {err}");
+            assert!(started.elapsed() < Duration::from_secs(1), "{src}");
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn functs_can_be_called_before_their_definition() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let src = "[int@4]>[store@n]>[call@double]\n[funct:double]{[math@n*2]>[store@n]}\n";
+        let node = crate::router::parse(src).map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("n"), Some(Value::Num(8.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn forked_child_edits_do_not_reach_parent() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let doc = crate::kernel::values::Document::new(
+            serde_json::json!({"a": 1}),
+            PathBuf::from("doc.json"),
+            "json".into(),
+            std::time::SystemTime::now(),
+            PathBuf::new(),
+        );
+        rt.set_var("doc", Value::Doc(doc))?;
+
+        let mut child = rt.fork();
+        let node = crate::router::parse("[mod@doc]{[set(a)@2]}").map_err(anyhow::Error::new)?;
+        child.eval(&node)?;
+
+        let json_of = |r: &Runtime| match r.get_var("doc") {
+            Some(Value::Doc(d)) => d.json,
+            other => panic!("doc missing: {other:?}"),
+        };
+        assert_eq!(json_of(&child), serde_json::json!({"a": 2}));
+        assert_eq!(json_of(&rt), serde_json::json!({"a": 1}));
+        Ok(())
+    }
 }
@@ -1,10 +1,14 @@
 // values.rs
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
 use serde_json::Value as JsonValue;
 
-#[derive(Clone, Debug, PartialEq)]
+// Doc is the big variant (preserve_order maps); boxing it would touch every Doc site for
+// little gain, since values are cloned and moved far less often than they are matched.
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug)]
 pub enum Value {
     Unit,
     Bool(bool),
@@ -42,6 +46,60 @@ impl Value {
     }
 }
 
+// Equality as tests, embedders and [match] see it:
+// - Num: IEEE `==`, so 0.0 == -0.0 and NaN equals nothing (use [approx] for float tolerance)
+// - Doc: deep JSON equality via `json_eq`; path, format and mtime are not compared
+// - different variants are never equal, so Num(1) != Str("1")
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Unit, Value::Unit) => true,
+            (Value::Bool(x), Value::Bool(y)) => x == y,
+            (Value::Num(x), Value::Num(y)) => x == y,
+            (Value::Str(x), Value::Str(y)) => x == y,
+            (Value::Doc(x), Value::Doc(y)) => json_eq(&x.json, &y.json),
+            _ => false,
+        }
+    }
+}
+
+// Deep equality: object key order is ignored and numbers compare by value (1 == 1.0).
+pub fn json_eq(a: &JsonValue, b: &JsonValue) -> bool {
+    match (a, b) {
+        (JsonValue::Number(x), JsonValue::Number(y)) => x.as_f64() == y.as_f64(),
+        (JsonValue::Array(x), JsonValue::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(l, r)| json_eq(l, r))
+        }
+        (JsonValue::Object(x), JsonValue::Object(y)) => {
+            x.len() == y.len() && x.iter().all(|(k, v)| y.get(k).is_some_and(|w| json_eq(v, w)))
+        }
+        _ => a == b,
+    }
+}
+
+// The one rule every JSON writer follows for numbers: whole values inside the range
+// f64 holds exactly become ints (3, not 3.0); the rest stay floats. None for NaN/inf.
+pub fn num_to_json(n: f64) -> Option<JsonValue> {
+    if n.fract() == 0.0 && n.abs() < 9.007_199_254_740_992e15 {
+        Some(JsonValue::from(n as i64))
+    } else {
+        serde_json::Number::from_f64(n).map(JsonValue::Number)
+    }
+}
+
+// Shared human-facing rendering used by [print], [msg] concatenation, and alerts
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Str(s) => write!(f, "{s}"),
+            Value::Num(n) => write!(f, "{n}"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Doc(_) => write!(f, "<doc>"),
+            Value::Unit => write!(f, "()"),
+        }
+    }
+}
+
 impl Document {
     pub fn new(
         json: JsonValue,
@@ -59,4 +117,54 @@ impl Document {
             root,
         }
     }
+
+    // A doc built in memory (a slice, a merge, a stat record, ...) is not any file on disk.
+    // It gets the scratch path `root/dir/name` so a bare [save] writes there instead of
+    // clobbering whichever file it came from.
+    pub fn scratch(root: PathBuf, dir: &Path, name: &str, json: JsonValue) -> Self {
+        let path = root.join(dir).join(name);
+        Self::new(json, path, "json".into(), SystemTime::now(), root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(json: JsonValue, path: &str) -> Value {
+        Value::Doc(Document::new(json, PathBuf::from(path), "json".into(), SystemTime::now(), PathBuf::new()))
+    }
+
+    #[test]
+    fn equality_per_variant() {
+        assert_eq!(Value::Unit, Value::Unit);
+        assert_eq!(Value::Bool(true), Value::Bool(true));
+        assert_ne!(Value::Bool(true), Value::Bool(false));
+        assert_eq!(Value::Num(0.0), Value::Num(-0.0));
+        assert_ne!(Value::Num(f64::NAN), Value::Num(f64::NAN));
+        assert_ne!(Value::Num(0.1 + 0.2), Value::Num(0.3));
+        assert_eq!(Value::Str("a".into()), Value::Str("a".into()));
+        // docs compare by content only: key order, int/float spelling and path don't matter
+        assert_eq!(
+            doc(serde_json::json!({"a": 1, "b": [1.0, "x"]}), "one.json"),
+            doc(serde_json::json!({"b": [1, "x"], "a": 1.0}), "two.yaml")
+        );
+        assert_ne!(doc(serde_json::json!([1, 2]), "a.json"), doc(serde_json::json!([2, 1]), "a.json"));
+    }
+
+    #[test]
+    fn mixed_variants_are_unequal() {
+        let values = [
+            Value::Unit,
+            Value::Bool(true),
+            Value::Num(1.0),
+            Value::Str("1".into()),
+            doc(serde_json::json!(1), "a.json"),
+        ];
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                assert_eq!(a == b, i == j, "{a:?} vs {b:?}");
+            }
+        }
+    }
 }
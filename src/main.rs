@@ -1,4 +1,5 @@
 mod error_style;
+mod format;
 mod interpreter;
 mod kernel;
 mod packets;
@@ -7,10 +8,11 @@ mod router;
 
 use anyhow::{Result, anyhow};
 use kernel::Runtime;
-use kernel::ast::{Arg, Packet as AstPacket};
+use kernel::ast::{Arg, Packet as AstPacket, Span};
 use kernel::values::Value;
 use std::env;
 use std::fs;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::process;
 use std::time::{Duration, Instant};
@@ -18,13 +20,31 @@ use std::time::{Duration, Instant};
 fn main() {
     if let Err(err) = run_cli() {
         eprintln!("\n{err}");
-        process::exit(1);
+        process::exit(exit_code(&err));
+    }
+}
+
+// 2 = parse error, 3 = sandbox/consent refusal, 4 = failed [assert], 1 = anything else
+fn exit_code(err: &anyhow::Error) -> i32 {
+    if err.is::<router::ParseError>() {
+        return 2;
+    }
+    match kernel::runtime::error_code(err).as_deref() {
+        Some("E_ASSERT_FAILED") => 4,
+        Some(
+            "E_BOUNDARY_RED" | "E_BOX_REQUIRED" | "E_PACKET_DENIED" | "E_YELLOW_REQUIRED"
+            | "E_RED_REQUIRED",
+        ) => 3,
+        _ => 1,
     }
 }
 
 fn run_cli() -> Result<()> {
     // Simple CLI: `tagspeak init [dir]` or `tagspeak <file.tgsk>`
-    let mut args = env::args();
+    // --dry-run may appear anywhere; it applies to the script being run
+    let raw: Vec<String> = env::args().collect();
+    let dry_run = raw.iter().any(|a| a == "--dry-run");
+    let mut args = raw.into_iter().filter(|a| a != "--dry-run");
     let _exe = args.next();
     match args.next() {
         Some(cmd) if cmd == "init" => {
@@ -34,8 +54,8 @@ fn run_cli() -> Result<()> {
         Some(cmd) if cmd == "run" => {
             let path = args
                 .next()
-                .ok_or_else(|| anyhow!("`tagspeak run` expects a <file.tgsk> argument"))?;
-            run_script(&path)
+                .ok_or_else(|| anyhow!("`tagspeak run` expects a <file.tgsk> argument (or `-` for stdin)"))?;
+            run_script(&path, dry_run)
         }
         Some(cmd) if cmd == "build" => {
             let path = args
@@ -59,11 +79,20 @@ fn run_cli() -> Result<()> {
                 .ok_or_else(|| anyhow!("`tagspeak lint` expects a <file.tgsk> argument"))?;
             lint_script(&path)
         }
-        Some(path) => run_script(&path),
+        Some(cmd) if cmd == "fmt" => {
+            let rest: Vec<String> = args.collect();
+            let check = rest.iter().any(|a| a == "--check");
+            let path = rest
+                .iter()
+                .find(|a| a.as_str() != "--check")
+                .ok_or_else(|| anyhow!("`tagspeak fmt` expects a <file.tgsk> argument"))?;
+            fmt_script(path, check)
+        }
+        Some(path) => run_script(&path, dry_run),
         None => {
             // no args: guide the user
             eprintln!(
-                "No input file provided. Usage:\n  tagspeak init [dir]\n  tagspeak run <file.tgsk>\n  tagspeak build <file.tgsk>\n  tagspeak help [packet]\n  tagspeak lint <file.tgsk>\n  tagspeak <file.tgsk>"
+                "No input file provided. Usage:\n  tagspeak init [dir]\n  tagspeak run <file.tgsk|-> [--dry-run]\n  tagspeak build <file.tgsk>\n  tagspeak help [packet]\n  tagspeak lint <file.tgsk>\n  tagspeak fmt <file.tgsk> [--check]\n  tagspeak <file.tgsk>"
             );
             Err(anyhow!("no_input"))
         }
@@ -89,18 +118,35 @@ fn init_red(dir: Option<&str>) -> Result<()> {
     Ok(())
 }
 
-fn run_script(path: &str) -> Result<()> {
+fn run_script(path: &str, dry_run: bool) -> Result<()> {
+    let (src, mut rt) = load_script(path, &mut io::stdin(), &env::current_dir()?)?;
+    rt.dry_run |= dry_run;
+    let _ = run_source(&src, rt)?;
+    Ok(())
+}
+
+// `-` reads the program from `stdin`, with red.tgsk located from `cwd`; anything else
+// is a file path and the root is found from the file's directory
+fn load_script(path: &str, stdin: &mut dyn Read, cwd: &Path) -> Result<(String, Runtime)> {
+    if path == "-" {
+        let mut src = String::new();
+        stdin.read_to_string(&mut src)?;
+        return Ok((src, Runtime::from_entry(&cwd.join("-"))?));
+    }
     println!("Running file: {}", &path);
-    let src = fs::read_to_string(&path)?;
-    let ast = router::parse(&src)?;
-    let mut rt = Runtime::from_entry(Path::new(&path))?;
+    let src = fs::read_to_string(path)?;
+    Ok((src, Runtime::from_entry(Path::new(path))?))
+}
+
+fn run_source(src: &str, mut rt: Runtime) -> Result<Value> {
+    let ast = router::parse(src)?;
     if rt.effective_root.is_none() {
         return Err(anyhow!(
             "No red.tgsk root detected.\nCreate one via `tagspeak init` in your project root."
         ));
     }
-    let _ = rt.eval(&ast)?;
-    Ok(())
+    rt.eval(&ast)
+        .map_err(|e| kernel::runtime::attach_source(e, src))
 }
 
 fn build_script(path: &str) -> Result<()> {
@@ -117,6 +163,7 @@ fn build_script(path: &str) -> Result<()> {
         anyhow!("No red.tgsk root detected.\nCreate one via `tagspeak init` in your project root.")
     })?;
     let root_abs = fs::canonicalize(root)?;
+    packets::tagspeak::check_includes(&root_abs, &abs)?;
     let pretty = root_relative_path(&root_abs, &abs);
     println!("build_ok {}", pretty);
     Ok(())
@@ -153,6 +200,7 @@ fn run_help(topic: Option<&str>) -> Result<()> {
         op: "help".to_string(),
         arg,
         body: None,
+        span: Span::default(),
     };
     match packets::core::help::handle(&mut rt, &packet)? {
         Value::Str(s) => {
@@ -180,6 +228,7 @@ fn lint_script(path: &str) -> Result<()> {
         op: "lint".to_string(),
         arg: None,
         body: None,
+        span: Span::default(),
     };
     match packets::core::lint::handle(&mut rt, &packet)? {
         Value::Str(s) => {
@@ -193,6 +242,21 @@ fn lint_script(path: &str) -> Result<()> {
     }
 }
 
+fn fmt_script(path: &str, check: bool) -> Result<()> {
+    let src = fs::read_to_string(path)?;
+    let formatted = format::format_source(&src)?;
+    if check {
+        // --check leaves the file untouched and prints the canonical form
+        print!("{formatted}");
+        return Ok(());
+    }
+    if formatted != src {
+        fs::write(path, &formatted)?;
+    }
+    println!("fmt_ok {}", path);
+    Ok(())
+}
+
 fn burn_5s(path: &str) -> Result<()> {
     // Validate and anchor to the Box root
     let src = fs::read_to_string(&path)?;
@@ -232,3 +296,101 @@ fn burn_5s(path: &str) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_source_evaluates_stdin_program() -> Result<()> {
+        let base = env::temp_dir().join(format!("tgsk_stdin_test_{}", process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+
+        let mut stdin = "[math@2+3]>[store@x]".as_bytes();
+        let (src, rt) = load_script("-", &mut stdin, &base)?;
+        assert_eq!(src, "[math@2+3]>[store@x]");
+        assert!(rt.effective_root.is_some());
+        let out = run_source(&src, rt)?;
+        assert_eq!(out, Value::Num(5.0));
+
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
+    #[test]
+    fn build_reports_include_cycle_chain() -> Result<()> {
+        let base = env::temp_dir().join(format!("tgsk_build_cycle_test_{}", process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("lib"))?;
+        fs::write(base.join("red.tgsk"), "")?;
+        fs::write(base.join("a.tgsk"), "[msg@\"a\"]>[run@/lib/b.tgsk]")?;
+        fs::write(base.join("lib/b.tgsk"), "[yellow@\"again?\"]{[run@\"../a.tgsk\"]}")?;
+
+        let err = build_script(base.join("a.tgsk").to_str().unwrap()).unwrap_err();
+        assert!(
+            err.to_string().contains("E_RUN_CYCLE: include cycle /a.tgsk -> /lib/b.tgsk -> /a.tgsk"),
+            "unexpected error: {err}"
+        );
+
+        fs::write(base.join("lib/b.tgsk"), "[msg@\"leaf\"]")?;
+        build_script(base.join("a.tgsk").to_str().unwrap())?;
+
+        // the tagspeak spellings of [run] are includes too
+        fs::write(base.join("lib/b.tgsk"), "[tagspeak run@\"../a.tgsk\"]")?;
+        let err = build_script(base.join("a.tgsk").to_str().unwrap()).unwrap_err();
+        assert!(err.to_string().contains("E_RUN_CYCLE"), "unexpected error: {err}");
+        fs::write(base.join("lib/b.tgsk"), "[tagspeak:run@\"../a.tgsk\"]")?;
+        assert!(build_script(base.join("a.tgsk").to_str().unwrap()).is_err());
+
+        // guarded recursion is allowed (warned about, not rejected)
+        fs::write(base.join("self.tgsk"), "[if@(__run_depth<3)]>[then]{[run@/self.tgsk]}")?;
+        build_script(base.join("self.tgsk").to_str().unwrap())?;
+
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
+    #[test]
+    fn exit_codes_follow_error_kind() -> Result<()> {
+        let base = env::temp_dir().join(format!("tgsk_exit_code_test_{}", process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+
+        let cases = [
+            ("[math@1+1", 2),
+            ("[load@/../outside.json]", 3),
+            ("[assert@(1 == 2)]", 4),
+            ("[call@missing]", 1),
+        ];
+        for (src, want) in cases {
+            let rt = Runtime::from_entry(&base.join("-"))?;
+            let err = run_source(src, rt).expect_err("expected failure");
+            assert_eq!(exit_code(&err), want, "wrong code for {src}:\n{err}");
+        }
+
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
+    #[test]
+    fn run_source_renders_runtime_error_box() -> Result<()> {
+        let base = env::temp_dir().join(format!("tgsk_rt_err_test_{}", process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+
+        let rt = Runtime::from_entry(&base.join("-"))?;
+        let err = run_source("[math@1+1]\n[call@missing]\n", rt)
+            .expect_err("expected runtime failure");
+        let rendered = err.to_string();
+        assert!(rendered.contains('╭') && rendered.contains('╯'), "no box:\n{rendered}");
+        assert!(rendered.contains("unknown funct 'missing'"), "no detail:\n{rendered}");
+        assert!(rendered.contains("Line 2, Column 1"), "no location:\n{rendered}");
+
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+}
@@ -1,10 +1,15 @@
 use anyhow::Result;
 use std::time::SystemTime;
 
-use crate::kernel::values::Document;
+use crate::kernel::values::{Document, num_to_json};
 use crate::kernel::{Node, Packet, Runtime, Value};
 
+// [array]{...} / [array@[1,2,3]] -> build an array Doc
+// [array(sum)@nums] / array(avg|min|max) -> aggregate an array of numbers (var or piped Doc)
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if let Some(mode) = crate::router::extract_paren(&p.op) {
+        return aggregate(rt, p, mode.trim());
+    }
     let root_path = rt
         .effective_root
         .as_ref()
@@ -28,14 +33,14 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         if trimmed.starts_with('[') && trimmed.ends_with(']') {
             let val: serde_json::Value = serde_json::from_str(trimmed)?;
             if !val.is_array() {
-                anyhow::bail!("array_sugar_not_array");
+                anyhow::bail!("E_NOT_ARRAY: @[...] sugar did not parse to an array");
             }
             val
         } else {
-            anyhow::bail!("array needs body or @[...] sugar");
+            anyhow::bail!("E_BAD_ARG: array needs body or @[...] sugar");
         }
     } else {
-        anyhow::bail!("array needs body or @[...] sugar");
+        anyhow::bail!("E_BAD_ARG: array needs body or @[...] sugar");
     };
 
     let path = root_path.join(&rt.cwd).join("_array.json");
@@ -53,10 +58,282 @@ fn value_to_json(v: Value) -> Result<serde_json::Value> {
     Ok(match v {
         Value::Unit => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(b),
-        Value::Num(n) => serde_json::Value::Number(
-            serde_json::Number::from_f64(n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-        ),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Value::Str(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
         Value::Doc(d) => d.json,
     })
 }
+
+// [slice(1,3)@items] -> new array Doc of items[1..3] (half-open); the input is untouched
+// [slice(-2)@items]   -> last two items; negative bounds count from the end
+// Bounds are whole numbers or variables; out-of-range bounds clamp, so a page past the end is [].
+pub fn slice(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let raw = crate::router::extract_paren(&p.op).unwrap_or("");
+    let mut bounds = raw.split(',').map(str::trim);
+    let start = match bounds.next() {
+        Some(tok) if !tok.is_empty() => Some(slice_bound(rt, tok)?),
+        _ => None,
+    };
+    let end = match bounds.next() {
+        Some(tok) if !tok.is_empty() => Some(slice_bound(rt, tok)?),
+        _ => None,
+    };
+    if bounds.next().is_some() {
+        anyhow::bail!("E_BAD_ARG: slice takes (start,end)");
+    }
+    let v = match p.arg.as_ref() {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    let doc = match v {
+        Value::Doc(d) if d.json.is_array() => d,
+        _ => anyhow::bail!("E_NOT_ARRAY: slice needs an array"),
+    };
+    let items = doc.json.as_array().map(Vec::as_slice).unwrap_or_default();
+    let (from, to) = clamp_range(items.len(), start, end);
+    let json = serde_json::Value::Array(items[from..to].to_vec());
+    Ok(Value::Doc(Document::scratch(doc.root, &rt.cwd, "_slice.json", json)))
+}
+
+// [zip@keys,values]         -> array Doc of [k, v] pairs
+// [zip(object)@keys,values] -> object Doc mapping each key to its value; later duplicates win
+// Both sides are array variables; the result stops at the shorter one.
+pub fn zip(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let object = match crate::router::extract_paren(&p.op).map(str::trim) {
+        None => false,
+        Some("object") => true,
+        Some(other) => anyhow::bail!("E_MODE_UNKNOWN: zip mode unsupported: {other} (object)"),
+    };
+    let list = match p.arg.as_ref() {
+        Some(crate::kernel::ast::Arg::Str(s)) => s.as_str(),
+        _ => anyhow::bail!("E_BAD_ARG: zip needs @keys,values"),
+    };
+    let (left, right) = match list.split_once(',') {
+        Some((a, b)) if !a.trim().is_empty() && !b.trim().is_empty() && !b.contains(',') => {
+            (zip_side(rt, a.trim())?, zip_side(rt, b.trim())?)
+        }
+        _ => anyhow::bail!("E_BAD_ARG: zip needs @keys,values"),
+    };
+    let pairs = left.json.as_array().into_iter().flatten().zip(right.json.as_array().into_iter().flatten());
+    let json = if object {
+        let mut map = serde_json::Map::new();
+        for (k, v) in pairs {
+            let key = match k {
+                serde_json::Value::String(s) => s.clone(),
+                serde_json::Value::Number(_) | serde_json::Value::Bool(_) => k.to_string(),
+                other => anyhow::bail!("E_BAD_ARG: zip(object) key must be a scalar, got {other}"),
+            };
+            map.insert(key, v.clone());
+        }
+        serde_json::Value::Object(map)
+    } else {
+        serde_json::Value::Array(pairs.map(|(k, v)| serde_json::json!([k, v])).collect())
+    };
+    Ok(Value::Doc(Document::scratch(left.root, &rt.cwd, "_zip.json", json)))
+}
+
+// [group(category)@items]     -> object Doc mapping each distinct `category` to its items
+// [group(meta.region)@items]  -> the key is any [get] path, read from each item
+// Buckets keep first-seen order; items missing the key (or holding null) go under "null".
+pub fn group(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let path = match crate::router::extract_paren(&p.op).map(str::trim) {
+        Some(path) if !path.is_empty() => path,
+        _ => anyhow::bail!("E_BAD_ARG: group needs a key path: [group(category)@items]"),
+    };
+    let v = match p.arg.as_ref() {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    let (mut one, items) = match v {
+        Value::Doc(mut d) => match std::mem::take(&mut d.json) {
+            serde_json::Value::Array(items) => (d, items),
+            _ => anyhow::bail!("E_NOT_ARRAY: group needs an array"),
+        },
+        _ => anyhow::bail!("E_NOT_ARRAY: group needs an array"),
+    };
+    // one doc shell is reused for every item: only its json is swapped in and out
+    one.last_json = serde_json::Value::Null;
+    let mut buckets = serde_json::Map::new();
+    for item in items {
+        one.json = item;
+        let key = match crate::packets::query::read_value(rt, &one, path)? {
+            None | Some(Value::Unit) => String::from("null"),
+            Some(Value::Doc(d)) => d.json.to_string(),
+            Some(other) => other.to_string(),
+        };
+        let bucket = buckets.entry(key).or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if let serde_json::Value::Array(list) = bucket {
+            list.push(std::mem::take(&mut one.json));
+        }
+    }
+    let json = serde_json::Value::Object(buckets);
+    Ok(Value::Doc(Document::scratch(one.root, &rt.cwd, "_group.json", json)))
+}
+
+fn zip_side(rt: &Runtime, name: &str) -> Result<Document> {
+    match rt.get_var(name) {
+        Some(Value::Doc(d)) if d.json.is_array() => Ok(d),
+        _ => anyhow::bail!("E_NOT_ARRAY: zip needs '{name}' to be an array"),
+    }
+}
+
+fn slice_bound(rt: &Runtime, token: &str) -> Result<i64> {
+    match crate::packets::coalesce::resolve_token(rt, token)? {
+        Value::Num(n) if n.fract() == 0.0 => Ok(n as i64),
+        other => anyhow::bail!("E_BAD_ARG: slice bound '{token}' must be a whole number, got {other}"),
+    }
+}
+
+// Python-style: negatives count from the end, everything clamps to 0..=len, start <= end
+fn clamp_range(len: usize, start: Option<i64>, end: Option<i64>) -> (usize, usize) {
+    let len_i = len as i64;
+    let fix = |i: i64| if i < 0 { (len_i + i).max(0) } else { i.min(len_i) } as usize;
+    let from = start.map(fix).unwrap_or(0);
+    let to = end.map(fix).unwrap_or(len);
+    (from, to.max(from))
+}
+
+// sum of [] is 0; avg/min/max of [] fail with E_ARRAY_EMPTY
+fn aggregate(rt: &Runtime, p: &Packet, mode: &str) -> Result<Value> {
+    if !matches!(mode, "sum" | "avg" | "min" | "max") {
+        anyhow::bail!("E_MODE_UNKNOWN: array mode unsupported: {mode} (sum|avg|min|max)");
+    }
+    let v = match p.arg.as_ref() {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    let items = match &v {
+        Value::Doc(d) => match d.json.as_array() {
+            Some(items) => items,
+            None => anyhow::bail!("E_NOT_ARRAY: array({mode}) needs an array"),
+        },
+        _ => anyhow::bail!("E_NOT_ARRAY: array({mode}) needs an array"),
+    };
+    let mut nums = Vec::with_capacity(items.len());
+    for (i, item) in items.iter().enumerate() {
+        match item.as_f64() {
+            Some(n) => nums.push(n),
+            None => anyhow::bail!("E_NOT_NUMERIC: array({mode}) element {i} is {item}"),
+        }
+    }
+    if nums.is_empty() && mode != "sum" {
+        anyhow::bail!("E_ARRAY_EMPTY: array({mode}) of an empty array");
+    }
+    let sum: f64 = nums.iter().sum();
+    Ok(Value::Num(match mode {
+        "sum" => sum,
+        "avg" => sum / nums.len() as f64,
+        "min" => nums.iter().copied().fold(f64::INFINITY, f64::min),
+        _ => nums.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+    use std::path::PathBuf;
+
+    fn with_array(json: serde_json::Value) -> Result<Runtime> {
+        let mut rt = Runtime::new()?;
+        let doc = Document::new(json, PathBuf::from("a.json"), "json".into(), SystemTime::now(), PathBuf::new());
+        rt.set_var("nums", Value::Doc(doc))?;
+        Ok(rt)
+    }
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn aggregates_numbers() -> Result<()> {
+        let mut rt = with_array(serde_json::json!([4, -2, 7.5, 0.5]))?;
+        assert_eq!(eval(&mut rt, "[array(sum)@nums]")?, Value::Num(10.0));
+        assert_eq!(eval(&mut rt, "[array(avg)@nums]")?, Value::Num(2.5));
+        assert_eq!(eval(&mut rt, "[array(min)@nums]")?, Value::Num(-2.0));
+        assert_eq!(eval(&mut rt, "[array(max)@nums]")?, Value::Num(7.5));
+        Ok(())
+    }
+
+    #[test]
+    fn slices_half_open_ranges() -> Result<()> {
+        let mut rt = with_array(serde_json::json!([10, 20, 30, 40, 50]))?;
+        let cases = [
+            ("[slice(1,3)@nums]", serde_json::json!([20, 30])),
+            ("[slice(-2)@nums]", serde_json::json!([40, 50])),
+            ("[slice(1,-1)@nums]", serde_json::json!([20, 30, 40])),
+            ("[slice(3,99)@nums]", serde_json::json!([40, 50])),
+            ("[slice(-99,1)@nums]", serde_json::json!([10])),
+            ("[slice(7,9)@nums]", serde_json::json!([])),
+            ("[slice(4,2)@nums]", serde_json::json!([])),
+        ];
+        for (script, expected) in cases {
+            match eval(&mut rt, script)? {
+                Value::Doc(d) => assert_eq!(d.json, expected, "{script}"),
+                other => panic!("{script}: expected doc, got {other:?}"),
+            }
+        }
+        match rt.get_var("nums") {
+            Some(Value::Doc(d)) => assert_eq!(d.json, serde_json::json!([10, 20, 30, 40, 50])),
+            other => panic!("nums changed: {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn zips_pairs_and_objects() -> Result<()> {
+        let mut rt = with_array(serde_json::json!([1, 2, 3]))?;
+        let keys = Document::new(serde_json::json!(["a", "b"]), PathBuf::from("k.json"), "json".into(), SystemTime::now(), PathBuf::new());
+        rt.set_var("keys", Value::Doc(keys))?;
+        match eval(&mut rt, "[zip@keys,nums]")? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!([["a", 1], ["b", 2]])),
+            other => panic!("expected doc, got {other:?}"),
+        }
+        match eval(&mut rt, "[zip(object)@keys,nums]")? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!({"a": 1, "b": 2})),
+            other => panic!("expected doc, got {other:?}"),
+        }
+        match eval(&mut rt, "[zip(object)@nums,keys]")? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!({"1": "a", "2": "b"})),
+            other => panic!("expected doc, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn groups_records_by_key() -> Result<()> {
+        let mut rt = with_array(serde_json::json!([
+            {"id": 1, "category": "fruit"},
+            {"id": 2, "category": "veg"},
+            {"id": 3, "category": "fruit"},
+            {"id": 4},
+        ]))?;
+        match eval(&mut rt, "[group(category)@nums]")? {
+            Value::Doc(d) => assert_eq!(
+                d.json,
+                serde_json::json!({
+                    "fruit": [{"id": 1, "category": "fruit"}, {"id": 3, "category": "fruit"}],
+                    "veg": [{"id": 2, "category": "veg"}],
+                    "null": [{"id": 4}],
+                })
+            ),
+            other => panic!("expected doc, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn empty_and_non_numeric_arrays_fail() -> Result<()> {
+        let mut rt = with_array(serde_json::json!([]))?;
+        assert_eq!(eval(&mut rt, "[array(sum)@nums]")?, Value::Num(0.0));
+        for mode in ["avg", "min", "max"] {
+            let err = eval(&mut rt, &format!("[array({mode})@nums]")).unwrap_err();
+            assert!(err.to_string().contains("E_ARRAY_EMPTY"), "{mode}: {err}");
+        }
+        let mut rt = with_array(serde_json::json!([1, "two"]))?;
+        let err = eval(&mut rt, "[array(sum)@nums]").unwrap_err();
+        assert!(err.to_string().contains("E_NOT_NUMERIC"), "unexpected error: {err}");
+        Ok(())
+    }
+}
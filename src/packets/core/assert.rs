@@ -0,0 +1,54 @@
+use anyhow::{Result, bail};
+
+use crate::kernel::ast::{Arg, Node};
+use crate::kernel::{Packet, Runtime, Value};
+use crate::packets::conditionals::{eval_cond, parse_cond};
+
+// [assert@(count > 0)]                     -> passes the piped value through, or fails with E_ASSERT_FAILED
+// [assert@(count > 0)]{[msg@"no rows"]}    -> body supplies the failure message
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let src = match p.arg.as_ref() {
+        Some(Arg::CondSrc(s)) | Some(Arg::Ident(s)) => s.clone(),
+        _ => bail!("E_BAD_ARG: assert needs @(cond)"),
+    };
+    let last = rt.last.clone();
+    if eval_cond(rt, &parse_cond(&src))? {
+        rt.last = last.clone();
+        return Ok(last);
+    }
+    let detail = match &p.body {
+        Some(body) => rt.eval(&Node::Block(body.clone()))?.to_string(),
+        None => src.trim().trim_start_matches('(').trim_end_matches(')').trim().to_string(),
+    };
+    bail!("E_ASSERT_FAILED: {detail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn passing_assert_keeps_piped_value() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("count", Value::Num(3.0))?;
+        assert_eq!(eval(&mut rt, "[msg@\"rows\"]>[assert@(count > 0)]")?, Value::Str("rows".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn failing_assert_reports_condition_or_message() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("count", Value::Num(0.0))?;
+        let err = eval(&mut rt, "[assert@(count > 0)]").unwrap_err();
+        assert!(err.to_string().contains("E_ASSERT_FAILED: count > 0"), "unexpected error: {err}");
+        let err = eval(&mut rt, "[assert@(count > 0)]{[msg@\"no rows\"]}").unwrap_err();
+        assert!(err.to_string().contains("E_ASSERT_FAILED: no rows"), "unexpected error: {err}");
+        Ok(())
+    }
+}
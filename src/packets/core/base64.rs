@@ -0,0 +1,62 @@
+use anyhow::{Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use crate::kernel::{Packet, Runtime, Value};
+
+// [base64(enc)@value] -> standard (padded) base64 of the value's text; docs encode their JSON
+// [base64(dec)@value] -> decoded UTF-8 text; bad input or non-UTF-8 bytes fail with E_BASE64_DECODE
+// Without an arg the piped value is used.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let v = match p.arg.as_ref() {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    let text = match &v {
+        Value::Doc(d) => serde_json::to_string(&d.json)?,
+        other => other.to_string(),
+    };
+    match crate::router::extract_paren(&p.op).map(str::trim) {
+        Some("enc") | None => Ok(Value::Str(STANDARD.encode(text.as_bytes()))),
+        Some("dec") => {
+            let bytes = match STANDARD.decode(text.trim()) {
+                Ok(bytes) => bytes,
+                Err(e) => bail!("E_BASE64_DECODE: {e}"),
+            };
+            match String::from_utf8(bytes) {
+                Ok(s) => Ok(Value::Str(s)),
+                Err(_) => bail!("E_BASE64_DECODE: decoded bytes are not UTF-8 text"),
+            }
+        }
+        Some(other) => bail!("E_MODE_UNKNOWN: base64 mode unsupported: {other}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn round_trips_text() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let enc = eval(&mut rt, "[base64(enc)@\"héllo, world\"]")?;
+        assert_eq!(enc, Value::Str("aMOpbGxvLCB3b3JsZA==".into()));
+        let dec = eval(&mut rt, "[base64(enc)@\"héllo, world\"]>[base64(dec)]")?;
+        assert_eq!(dec, Value::Str("héllo, world".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn malformed_input_fails_to_decode() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let err = eval(&mut rt, "[base64(dec)@\"not*base64\"]").unwrap_err();
+        assert!(err.to_string().contains("E_BASE64_DECODE"), "unexpected error: {err}");
+        Ok(())
+    }
+}
@@ -10,7 +10,7 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         Some(Arg::Str(s)) => s.clone(),
         Some(Arg::CondSrc(s)) => s.clone(),
         Some(Arg::Number(n)) => return Ok(Value::Bool(*n != 0.0)),
-        None => bail!("bool needs @<value>"),
+        None => bail!("E_BAD_ARG: bool needs @<value>"),
     };
 
     let cond = parse_cond(&raw);
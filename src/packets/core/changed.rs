@@ -0,0 +1,12 @@
+use anyhow::{Result, bail};
+
+use crate::kernel::{Packet, Runtime, Value};
+
+// [changed] -> true when the most recent [store] gave its variable a different value,
+// false when it stored what was already there (or nothing has been stored yet)
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if p.arg.is_some() || p.body.is_some() {
+        bail!("E_BAD_ARG: changed takes no arguments");
+    }
+    Ok(Value::Bool(rt.last_store_changed))
+}
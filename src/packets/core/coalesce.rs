@@ -0,0 +1,66 @@
+use anyhow::{Result, bail};
+
+use crate::kernel::ast::Arg;
+use crate::kernel::{Packet, Runtime, Value};
+
+// [coalesce@a,b,"fallback"] -> first token that resolves to something other than Unit/null
+// Tokens are quoted strings, numbers, true/false, or variable names (missing vars are Unit).
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let list = match p.arg.as_ref() {
+        Some(Arg::Str(s)) => s.as_str(),
+        Some(Arg::Ident(id)) => id.as_str(),
+        _ => bail!("E_BAD_ARG: coalesce needs @a,b,..."),
+    };
+    for token in list.split(',') {
+        let token = token.trim();
+        if token.is_empty() {
+            continue;
+        }
+        let v = resolve_token(rt, token)?;
+        if !is_empty(&v) {
+            return Ok(v);
+        }
+    }
+    Ok(Value::Unit)
+}
+
+// "text", 12, true/false, or a variable name (Unit when unset)
+pub fn resolve_token(rt: &Runtime, token: &str) -> Result<Value> {
+    if token.len() >= 2 && token.starts_with('"') && token.ends_with('"') {
+        let s: String = serde_json::from_str(token)?;
+        return Ok(Value::Str(s));
+    }
+    if let Ok(n) = token.parse::<f64>() {
+        return Ok(Value::Num(n));
+    }
+    Ok(match token {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => rt.get_var(token).unwrap_or(Value::Unit),
+    })
+}
+
+fn is_empty(v: &Value) -> bool {
+    match v {
+        Value::Unit => true,
+        Value::Doc(d) => d.json.is_null(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    #[test]
+    fn skips_unit_values() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("a", Value::Unit)?;
+        rt.set_var("c", Value::Str("found".into()))?;
+        let node = router::parse("[coalesce@a,b,c]").map_err(anyhow::Error::new)?;
+        let out = rt.eval(&node)?;
+        assert_eq!(out, Value::Str("found".into()));
+        Ok(())
+    }
+}
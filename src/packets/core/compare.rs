@@ -1,18 +1,19 @@
 use anyhow::{Result, bail};
 
 use crate::kernel::ast::{CmpBase, Comparator};
-use crate::kernel::boolops::cmp_eval;
+use crate::kernel::boolops::{cmp_eval, cmp_eval_with};
 use crate::kernel::{Arg, Packet, Runtime, Value};
 
 // Canonical comparator packets:
 // [eq@rhs], [ne@rhs], [lt@rhs], [le@rhs], [gt@rhs], [ge@rhs]
 // Compares last value against rhs and returns a bool.
+// [approx@rhs] / [cmp:approx@rhs] -> equality with float tolerance (rt.approx_tol, see Tolerance)
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let rhs = match p.arg.as_ref() {
         Some(Arg::Number(n)) => Value::Num(*n),
         Some(Arg::Str(s)) => Value::Str(s.clone()),
         Some(Arg::Ident(id)) => rt.get_var(id).unwrap_or(Value::Unit),
-        _ => bail!("comparator needs @<rhs> (number|string|ident)"),
+        _ => bail!("E_BAD_ARG: comparator needs @<rhs> (number|string|ident)"),
     };
 
     let lhs = rt.last.clone();
@@ -24,13 +25,17 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         "le" => (CmpBase::Lt, true, false),
         "gt" => (CmpBase::Gt, false, false),
         "ge" => (CmpBase::Gt, true, false),
-        _ => bail!("unknown_comparator"),
+        "approx" => (CmpBase::Approx, false, false),
+        _ => bail!("E_MODE_UNKNOWN: unknown comparator"),
     };
     let cmp = Comparator {
         base,
         include_eq,
         negate,
     };
-    let out = cmp_eval(&cmp, &lhs, &rhs)?;
+    let out = match cmp.base {
+        CmpBase::Approx => cmp_eval_with(&cmp, &lhs, &rhs, rt.approx_tol)?,
+        _ => cmp_eval(&cmp, &lhs, &rhs)?,
+    };
     Ok(Value::Bool(out))
 }
@@ -1,10 +1,30 @@
 use crate::kernel::{Packet, Runtime, Value};
-use anyhow::Result;
+use anyhow::{Result, bail};
 
 // [dump] -> pretty-print last value
 // [dump@var] -> pretty-print value of variable/arg
+// [dump(vars)] -> pretty-print every runtime variable as JSON; passes last through
+// [dump(at:/users/0)@doc] -> pretty-print only the subtree at that JSON Pointer; passes last through
 // Documents are rendered as pretty JSON to stdout.
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if let Some(mode) = p.op.strip_prefix("dump(") {
+        match mode.trim_end_matches(')').trim() {
+            "vars" => {
+                println!("{}", render_vars(rt)?);
+                return Ok(rt.last.clone());
+            }
+            m if m.starts_with("at:") => {
+                let v = match p.arg.as_ref() {
+                    Some(arg) => rt.resolve_arg(arg)?,
+                    None => rt.last.clone(),
+                };
+                println!("{}", render_at(&v, m["at:".len()..].trim())?);
+                return Ok(rt.last.clone());
+            }
+            other => bail!("E_MODE_UNKNOWN: dump mode unsupported: {other}"),
+        }
+    }
+
     let v = match p.arg.as_ref() {
         Some(arg) => rt.resolve_arg(arg)?,
         None => rt.last.clone(),
@@ -23,3 +43,59 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
 
     Ok(v)
 }
+
+fn render_at(v: &Value, pointer: &str) -> Result<String> {
+    let doc = match v {
+        Value::Doc(d) => d,
+        _ => bail!("E_NO_DOC: dump(at:...) needs a document"),
+    };
+    if !pointer.is_empty() && !pointer.starts_with('/') {
+        bail!("E_BAD_ARG: dump(at:{pointer}) needs a JSON Pointer like /users/0");
+    }
+    match doc.json.pointer(pointer) {
+        Some(sub) => Ok(serde_json::to_string_pretty(sub)?),
+        None => bail!("E_PATH_MISSING: nothing at {pointer}"),
+    }
+}
+
+fn render_vars(rt: &Runtime) -> Result<String> {
+    let json = crate::packets::reflect::vars_to_json(rt)?;
+    Ok(serde_json::to_string_pretty(&json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    #[test]
+    fn dump_vars_lists_stored_values() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let node = router::parse("[math@40+2]>[store@answer]>[dump(vars)]")
+            .map_err(anyhow::Error::new)?;
+        let out = rt.eval(&node)?;
+        assert_eq!(out, Value::Num(42.0));
+        let rendered = render_vars(&rt)?;
+        assert!(rendered.contains("\"answer\": 42"), "missing var:\n{rendered}");
+        Ok(())
+    }
+
+    #[test]
+    fn dump_at_prints_only_the_subtree() -> Result<()> {
+        let doc = crate::kernel::values::Document::new(
+            serde_json::json!({"users": [{"name": "ana", "tags": ["a"]}, {"name": "bo"}], "meta": 1}),
+            std::path::PathBuf::from("users.json"),
+            "json".into(),
+            std::time::SystemTime::now(),
+            std::path::PathBuf::new(),
+        );
+        let mut rt = Runtime::new()?;
+        rt.set_var("doc", Value::Doc(doc.clone()))?;
+        rt.last = Value::Num(7.0);
+        let rendered = render_at(&Value::Doc(doc), "/users/0")?;
+        assert_eq!(rendered, "{\n  \"name\": \"ana\",\n  \"tags\": [\n    \"a\"\n  ]\n}");
+        let node = router::parse("[dump(at:/users/1)@doc]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Num(7.0));
+        Ok(())
+    }
+}
@@ -1,10 +1,22 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
+use std::path::Path;
 
+use crate::kernel::fs_guard::resolve;
 use crate::kernel::{Arg, Packet, Runtime, Value};
+use crate::packets::store::check_type;
 
 // [env@NAME] -> returns environment variable as string, or Unit if missing
 // Accepts @"NAME" or @NAME ident. Does not mutate state beyond last value.
-pub fn handle(_rt: &mut Runtime, p: &Packet) -> Result<Value> {
+// [env(loadfile)@/.env] -> binds each KEY=value of a dotenv file (inside the red box) as a
+// string var; returns how many were bound
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if p.op.starts_with("env(") {
+        return match crate::router::extract_paren(&p.op).map(str::trim) {
+            Some("loadfile") => load_file(rt, p),
+            Some(other) => bail!("E_MODE_UNKNOWN: unknown env mode '{other}'"),
+            None => bail!("E_BAD_ARG: use [env(loadfile)@/path]"),
+        };
+    }
     let key = match &p.arg {
         Some(Arg::Str(s)) => s.clone(),
         Some(Arg::Ident(id)) => id.clone(),
@@ -18,3 +30,137 @@ pub fn handle(_rt: &mut Runtime, p: &Packet) -> Result<Value> {
         Err(_) => Ok(Value::Unit),
     }
 }
+
+fn load_file(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let raw = match &p.arg {
+        Some(Arg::Str(s)) => s,
+        _ => bail!("E_BAD_ARG: env(loadfile) needs @<path>"),
+    };
+    let root = rt
+        .effective_root
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+    let candidate = match raw.strip_prefix('/') {
+        Some(rel) => Path::new(rel).to_path_buf(),
+        None => rt.cwd.join(raw),
+    };
+    let path = resolve(root, &candidate)?;
+    let src = std::fs::read_to_string(&path)?;
+
+    let pairs: Vec<(String, Value)> =
+        parse_dotenv(&src)?.into_iter().map(|(k, v)| (k, Value::Str(v))).collect();
+    // a typed var that can't take a string fails the whole file before anything binds
+    for (key, val) in &pairs {
+        check_type(rt, key, val)?;
+    }
+    let count = pairs.len();
+    for (key, val) in pairs {
+        rt.set_var(&key, val)?;
+    }
+    Ok(Value::Num(count as f64))
+}
+
+// KEY=value per line; blank lines and `#` comments are skipped, `export ` is allowed.
+// "double quoted" values understand \n \t \" \; 'single quoted' values are literal;
+// bare values end at ` #`.
+fn parse_dotenv(src: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = Vec::new();
+    for (idx, line) in src.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, rest)) = line.split_once('=') else {
+            bail!("E_BAD_ARG: .env line {} has no '='", idx + 1);
+        };
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!("E_BAD_ARG: .env line {} has invalid key '{key}'", idx + 1);
+        }
+        let rest = rest.trim();
+        let val = if let Some(body) = rest.strip_prefix('"') {
+            let Some(end) = closing_quote(body) else {
+                bail!("E_BAD_ARG: .env line {} has an unterminated quote", idx + 1);
+            };
+            unescape(&body[..end])
+        } else if let Some(body) = rest.strip_prefix('\'') {
+            let Some(end) = body.find('\'') else {
+                bail!("E_BAD_ARG: .env line {} has an unterminated quote", idx + 1);
+            };
+            body[..end].to_string()
+        } else {
+            rest.split(" #").next().unwrap_or("").trim_end().to_string()
+        };
+        pairs.push((key.to_string(), val));
+    }
+    Ok(pairs)
+}
+
+fn closing_quote(body: &str) -> Option<usize> {
+    let mut escape = false;
+    for (idx, ch) in body.char_indices() {
+        match ch {
+            _ if escape => escape = false,
+            '\\' => escape = true,
+            '"' => return Some(idx),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn loadfile_binds_string_vars() {
+        let base = std::env::temp_dir().join(format!("tgsk_env_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(
+            base.join(".env"),
+            "# secrets\nAPI_KEY=abc123 # inline note\nexport PORT=8080\nGREETING=\"hi \\\"there\\\"\"\nRAW='a#b'\n",
+        )
+        .unwrap();
+        let script = base.join("main.tgsk");
+        fs::write(&script, "[env(loadfile)@/.env]").unwrap();
+
+        let ast = crate::router::parse(&fs::read_to_string(&script).unwrap()).unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        assert_eq!(rt.eval(&ast).unwrap(), Value::Num(4.0));
+        assert_eq!(rt.get_var("API_KEY"), Some(Value::Str("abc123".into())));
+        assert_eq!(rt.get_var("PORT"), Some(Value::Str("8080".into())));
+        assert_eq!(rt.get_var("GREETING"), Some(Value::Str("hi \"there\"".into())));
+        assert_eq!(rt.get_var("RAW"), Some(Value::Str("a#b".into())));
+
+        fs::write(base.join("typed.env"), "A=1\nPORT=8080\n").unwrap();
+        let ast = crate::router::parse("[int@1]>[store:num@PORT]>[env(loadfile)@/typed.env]").unwrap();
+        let err = rt.eval(&ast).unwrap_err();
+        assert!(err.to_string().contains("E_TYPE_MISMATCH"), "unexpected error: {err}");
+        assert_eq!(rt.get_var("A"), None);
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
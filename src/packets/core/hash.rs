@@ -0,0 +1,109 @@
+use anyhow::{Result, bail};
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+use crate::kernel::ast::Arg;
+use crate::kernel::fs_guard::resolve;
+use crate::kernel::{Packet, Runtime, Value};
+
+// [hash@value]       -> hex SHA-256 of the value (last value when no arg)
+// [hash(file)@/path] -> hex SHA-256 of a file's bytes inside the red box
+// Strings hash their UTF-8 bytes (matches `sha256sum`); docs hash their JSON with
+// object keys sorted, so equal docs hash equally whatever their key order.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let bytes = match crate::router::extract_paren(&p.op).map(str::trim) {
+        None => {
+            let v = match p.arg.as_ref() {
+                Some(arg) => rt.resolve_arg(arg)?,
+                None => rt.last.clone(),
+            };
+            canonical_bytes(&v)?
+        }
+        Some("file") => {
+            let raw = match p.arg.as_ref() {
+                Some(Arg::Str(s)) | Some(Arg::Ident(s)) => s.as_str(),
+                _ => bail!("E_BAD_ARG: hash(file) needs @<path>"),
+            };
+            let root = rt
+                .effective_root
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+            let candidate = match raw.strip_prefix('/') {
+                Some(rel) => PathBuf::from(rel),
+                None => rt.cwd.join(raw),
+            };
+            std::fs::read(resolve(root, &candidate)?)?
+        }
+        Some(other) => bail!("E_MODE_UNKNOWN: hash mode unsupported: {other}"),
+    };
+    let digest = Sha256::digest(&bytes);
+    Ok(Value::Str(digest.iter().map(|b| format!("{b:02x}")).collect()))
+}
+
+fn canonical_bytes(v: &Value) -> Result<Vec<u8>> {
+    Ok(match v {
+        Value::Doc(d) => serde_json::to_vec(&sorted(&d.json))?,
+        other => other.to_string().into_bytes(),
+    })
+}
+
+fn sorted(v: &serde_json::Value) -> serde_json::Value {
+    match v {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            serde_json::Value::Object(keys.into_iter().map(|k| (k.clone(), sorted(&map[k]))).collect())
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(sorted).collect()),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::values::Document;
+    use crate::router;
+    use std::fs;
+    use std::time::SystemTime;
+
+    fn doc(json: serde_json::Value) -> Value {
+        Value::Doc(Document::new(
+            json,
+            PathBuf::from("doc.json"),
+            "json".into(),
+            SystemTime::now(),
+            PathBuf::new(),
+        ))
+    }
+
+    #[test]
+    fn equal_docs_hash_equally() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("a", doc(serde_json::json!({"x": 1, "y": {"p": true, "q": [1, 2]}})))?;
+        rt.set_var("b", doc(serde_json::json!({"y": {"q": [1, 2], "p": true}, "x": 1})))?;
+        let ha = rt.eval(&router::parse("[hash@a]").map_err(anyhow::Error::new)?)?;
+        let hb = rt.eval(&router::parse("[hash@b]").map_err(anyhow::Error::new)?)?;
+        assert_eq!(ha, hb);
+        Ok(())
+    }
+
+    #[test]
+    fn file_hash_matches_sha256() {
+        let base = std::env::temp_dir().join(format!("tgsk_hash_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(base.join("abc.txt"), "abc").unwrap();
+        let script = base.join("main.tgsk");
+
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let out = rt.eval(&router::parse("[hash(file)@/abc.txt]").unwrap()).unwrap();
+        assert_eq!(
+            out,
+            Value::Str("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".into())
+        );
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
@@ -71,12 +71,12 @@ pub fn handle(_rt: &mut Runtime, p: &Packet) -> Result<Value> {
         Some(Arg::Ident(id)) => id.trim(),
         Some(Arg::Number(_)) => {
             return Err(anyhow!(
-                "[help] expects a packet name like [help@print] or [help@*]"
+                "E_BAD_ARG: [help] expects a packet name like [help@print] or [help@*]"
             ));
         }
         Some(Arg::CondSrc(_)) => {
             return Err(anyhow!(
-                "[help] expects a packet name, not a conditional expression"
+                "E_BAD_ARG: [help] expects a packet name, not a conditional expression"
             ));
         }
         None => "",
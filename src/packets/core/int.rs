@@ -11,13 +11,13 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
                 return Ok(match v {
                     Value::Num(n) => Value::Num(n.trunc()),
                     Value::Str(s) => Value::Num(s.parse::<f64>()?.trunc()),
-                    _ => bail!("non_numeric_var"),
+                    _ => bail!("E_NOT_NUMERIC: variable is not a number"),
                 });
             }
             id.clone()
         }
         Some(Arg::Str(s)) => s.clone(),
-        _ => bail!("int needs @<number|expr>"),
+        _ => bail!("E_BAD_ARG: int needs @<number|expr>"),
     };
 
     let mut buf = String::new();
@@ -32,10 +32,10 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             match v {
                 Value::Num(n) => buf.push_str(&format!("{}", n.trunc() as i64)),
                 Value::Str(s) => buf.push_str(&s),
-                _ => bail!("non_numeric_var"),
+                _ => bail!("E_NOT_NUMERIC: variable is not a number"),
             }
         } else {
-            bail!("unknown_segment");
+            bail!("E_BAD_ARG: unknown expression segment");
         }
     }
     let n: i64 = buf.parse()?;
@@ -0,0 +1,83 @@
+use anyhow::{Result, bail};
+
+use crate::kernel::values::num_to_json;
+use crate::kernel::{Packet, Runtime, Value};
+
+// [json@value]         -> compact JSON text of a var/literal (or the piped value) as a string
+// [json(pretty)@value] -> same, indented two spaces
+// Strings stay strings ("hi" -> "\"hi\""), whole numbers print without a fraction,
+// Unit becomes null. Unlike [dump], nothing is printed.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let pretty = match crate::router::extract_paren(&p.op).map(str::trim) {
+        None => false,
+        Some("pretty") => true,
+        Some(other) => bail!("E_MODE_UNKNOWN: json mode unsupported: {other}"),
+    };
+    let v = match p.arg.as_ref() {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    let json = to_json(&v)?;
+    let text = if pretty {
+        serde_json::to_string_pretty(&json)?
+    } else {
+        serde_json::to_string(&json)?
+    };
+    Ok(Value::Str(text))
+}
+
+fn to_json(v: &Value) -> Result<serde_json::Value> {
+    Ok(match v {
+        Value::Unit => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(*b),
+        Value::Num(n) => num_to_json(*n)
+            .ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: {n} has no JSON form"))?,
+        Value::Str(s) => serde_json::Value::String(s.clone()),
+        Value::Doc(d) => d.json.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kernel::values::Document;
+    use crate::router;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn doc_compact_and_pretty() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let doc = Document::new(
+            serde_json::json!({"name": "ana", "tags": [1, 2]}),
+            PathBuf::from("d.json"),
+            "json".into(),
+            SystemTime::now(),
+            PathBuf::new(),
+        );
+        rt.set_var("doc", Value::Doc(doc))?;
+        assert_eq!(
+            eval(&mut rt, "[json@doc]")?,
+            Value::Str(r#"{"name":"ana","tags":[1,2]}"#.into())
+        );
+        assert_eq!(
+            eval(&mut rt, "[json(pretty)@doc]")?,
+            Value::Str("{\n  \"name\": \"ana\",\n  \"tags\": [\n    1,\n    2\n  ]\n}".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scalars_keep_their_json_type() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        assert_eq!(eval(&mut rt, "[int@3]>[json]")?, Value::Str("3".into()));
+        assert_eq!(eval(&mut rt, "[math@1.5]>[json(pretty)]")?, Value::Str("1.5".into()));
+        assert_eq!(eval(&mut rt, "[json@\"say \\\"hi\\\"\"]")?, Value::Str(r#""say \"hi\"""#.into()));
+        Ok(())
+    }
+}
@@ -1,24 +1,38 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::kernel::{Packet, Runtime, Value};
 
 // [len] -> length of last value (string/doc)
 // [len@var|"text"] -> length of the provided arg
+// [len(graphemes)@"🇺🇸"] -> counts user-perceived characters instead of chars (1 vs 2 here)
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let graphemes = match crate::router::extract_paren(&p.op).map(str::trim) {
+        None | Some("chars") => false,
+        Some("graphemes") => true,
+        Some(other) => bail!("E_MODE_UNKNOWN: len mode unsupported: {other}"),
+    };
+    let count = |s: &str| {
+        if graphemes {
+            s.graphemes(true).count()
+        } else {
+            s.chars().count()
+        }
+    };
     let v = match p.arg.as_ref() {
         Some(arg) => rt.resolve_arg(arg)?,
         None => rt.last.clone(),
     };
 
     let n = match v {
-        Value::Str(s) => s.chars().count() as f64,
+        Value::Str(s) => count(&s) as f64,
         Value::Doc(d) => {
             if d.json.is_array() {
                 d.json.as_array().map(|a| a.len()).unwrap_or(0) as f64
             } else if d.json.is_object() {
                 d.json.as_object().map(|o| o.len()).unwrap_or(0) as f64
             } else if d.json.is_string() {
-                d.json.as_str().map(|s| s.chars().count()).unwrap_or(0) as f64
+                d.json.as_str().map(count).unwrap_or(0) as f64
             } else {
                 0.0
             }
@@ -27,3 +41,20 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     };
     Ok(Value::Num(n))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    #[test]
+    fn graphemes_count_flag_as_one() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("flag", Value::Str("\u{1F1FA}\u{1F1F8}".into()))?;
+        let node = router::parse("[len@flag]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Num(2.0));
+        let node = router::parse("[len(graphemes)@flag]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Num(1.0));
+        Ok(())
+    }
+}
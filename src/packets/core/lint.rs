@@ -20,26 +20,26 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             Some(Value::Str(s)) => s,
             Some(_) => {
                 return Err(anyhow!(
-                    "[lint@{name}] expects the variable to hold script text."
+                    "E_BAD_ARG: [lint@{name}] expects the variable to hold script text."
                 ));
             }
-            None => return Err(anyhow!("[lint@{name}] variable not found.")),
+            None => return Err(anyhow!("E_VAR_MISSING: [lint@{name}] variable not found.")),
         },
         Some(Arg::Number(_)) => {
             return Err(anyhow!(
-                "[lint] expects script text, a variable name, or a path inside the red box."
+                "E_BAD_ARG: [lint] expects script text, a variable name, or a path inside the red box."
             ));
         }
         Some(Arg::CondSrc(_)) => {
             return Err(anyhow!(
-                "[lint] expects script text, a variable name, or a path inside the red box."
+                "E_BAD_ARG: [lint] expects script text, a variable name, or a path inside the red box."
             ));
         }
         None => match &rt.last {
             Value::Str(s) => s.clone(),
             _ => {
                 return Err(anyhow!(
-                    "[lint] needs script text. Pass it via [lint@\"...\"] or store it in a variable."
+                    "E_BAD_ARG: [lint] needs script text. Pass it via [lint@\"...\"] or store it in a variable."
                 ));
             }
         },
@@ -81,7 +81,7 @@ fn resolve_source(rt: &Runtime, raw: &str) -> Result<String> {
     let root = rt
         .effective_root
         .as_ref()
-        .ok_or_else(|| anyhow!("No red.tgsk root detected; cannot lint files."))?;
+        .ok_or_else(|| anyhow!("E_BOX_REQUIRED: No red.tgsk root detected; cannot lint files."))?;
 
     let trimmed = raw.trim();
     let rel = if trimmed.starts_with('/') {
@@ -200,6 +200,20 @@ fn lint_packet(
         _ => {}
     }
 
+    if pkt.body.is_some()
+        && pkt.ns.is_none()
+        && crate::kernel::packet_catalog::takes_body(&pkt.op) == Some(false)
+    {
+        push_warning(
+            seen,
+            warnings,
+            format!(
+                "Line {}, Column {}: Packet [{}] ignores its block.",
+                pkt.span.line, pkt.span.col, pkt.op
+            ),
+        );
+    }
+
     if let Some(body) = &pkt.body {
         let body_ctx = LintContext { inside_yellow };
         for child in body {
@@ -231,6 +245,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lint_flags_ignored_block() {
+        let mut rt = Runtime::new().unwrap();
+        rt.last = Value::Str("[msg@\"ok\"]\n[print@\"x\"]{[msg@\"lost\"]}\n[loop@2]{[print]}".into());
+        let packet = router::parse_single_packet("[lint]").unwrap();
+        match handle(&mut rt, &packet).unwrap() {
+            Value::Str(s) => {
+                assert!(s.contains("Line 2, Column 1: Packet [print] ignores its block."), "got: {s}");
+                assert!(!s.contains("[loop]"), "loop body is used: {s}");
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+    }
+
     #[test]
     fn lint_reports_clean_script() {
         let mut rt = Runtime::new().unwrap();
@@ -1,6 +1,5 @@
-use anyhow::Result;
-use meval::Expr;
-use std::str::FromStr;
+use anyhow::{Result, bail};
+use meval::ContextProvider;
 
 use crate::kernel::ast::Arg;
 use crate::kernel::{Packet, Runtime, Value};
@@ -9,6 +8,10 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     // Accept @<number>  -> return number
     // Accept @<ident>   -> if numeric var exists, return it; else treat ident text as expr
     // Accept @"a+b"     -> evaluate as expression with current numeric vars
+    // Functions: abs(x), sqrt(x), min(a,b,..), max(a,b,..), pow(b,e), plus meval's
+    // builtins (floor, ceil, round, ln, exp, sin, ...)
+    // Bitwise on 64-bit integers: a|b, a^b (xor; use pow(b,e) for powers), a&b, a<<n, a>>n
+    // Comparisons: ==, !=, <, <=, >, >= give 1 or 0
     let expr_text = match p.arg.as_ref() {
         Some(Arg::Number(n)) => return Ok(Value::Num(*n)),
         Some(Arg::Ident(id)) => {
@@ -17,19 +20,322 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             }
             id.clone() // treat as expression string: allows [math@counter+1]
         }
-        Some(Arg::Str(s)) => s.clone(),
-        _ => anyhow::bail!("math needs @<number|ident|expr>"),
+        Some(Arg::Str(s)) | Some(Arg::CondSrc(s)) => s.clone(),
+        _ => anyhow::bail!("E_BAD_ARG: math needs @<number|ident|expr>"),
     };
 
     // bind numeric vars into math context
     let mut ctx = meval::Context::new();
+    ctx.func2("pow", f64::powf);
     for (k, v) in &rt.vars {
         if let Value::Num(n) = v {
             ctx.var(k.clone(), *n);
         }
     }
 
-    let expr = Expr::from_str(&expr_text)?;
-    let val = expr.eval_with_context(ctx)?;
+    let val = eval_expr(expr_text.trim(), &ctx)?;
+    // NaN/inf come from domain errors (sqrt(-1), 1/0); report them instead of storing them
+    if !val.is_finite() {
+        anyhow::bail!("E_MATH_DOMAIN: '{expr_text}' has no finite result");
+    }
     Ok(Value::Num(val))
 }
+
+// Binary levels, loosest first, in C order: bitwise sits below comparison, so `a&b==c`
+// reads `a&(b==c)`, and shifts sit between comparison and arithmetic. Comparisons give
+// 1 or 0. `^` is xor here; powers go through pow(b,e).
+const LEVELS: &[&[&str]] = &[
+    &["|"],
+    &["^"],
+    &["&"],
+    &["==", "!="],
+    &["<", "<=", ">", ">="],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+// two-char operators first so `<<` never reads as `<` `<`
+const OPS: &[&str] = &[
+    "<<", ">>", "<=", ">=", "==", "!=", "|", "^", "&", "<", ">", "+", "-", "*", "/", "%",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Num(f64),
+    Ident(String),
+    Op(&'static str),
+    Open,
+    Close,
+    Comma,
+}
+
+fn eval_expr(text: &str, ctx: &meval::Context) -> Result<f64> {
+    let mut parser = Parser { toks: tokenize(text)?, pos: 0, ctx };
+    let val = parser.binary(0)?;
+    if let Some(tok) = parser.toks.get(parser.pos) {
+        bail!("E_BAD_ARG: math: unexpected {tok:?} in '{text}'");
+    }
+    Ok(val)
+}
+
+fn tokenize(text: &str) -> Result<Vec<Tok>> {
+    let mut toks = Vec::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len());
+            // exponent: 1e3, 2.5E-4
+            let tail = &rest[end..];
+            if let Some(exp) = tail.strip_prefix(['e', 'E']) {
+                let digits = exp.strip_prefix(['+', '-']).unwrap_or(exp);
+                if digits.starts_with(|c: char| c.is_ascii_digit()) {
+                    let skip = tail.len() - digits.len();
+                    end += skip + digits.find(|c: char| !c.is_ascii_digit()).unwrap_or(digits.len());
+                }
+            }
+            let num = rest[..end]
+                .parse::<f64>()
+                .map_err(|_| anyhow::anyhow!("E_BAD_ARG: math: bad number '{}'", &rest[..end]))?;
+            toks.push(Tok::Num(num));
+            rest = &rest[end..];
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            toks.push(Tok::Ident(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) {
+            toks.push(Tok::Op(op));
+            rest = &rest[op.len()..];
+        } else {
+            toks.push(match c {
+                '(' => Tok::Open,
+                ')' => Tok::Close,
+                ',' => Tok::Comma,
+                _ => bail!("E_BAD_ARG: math: unexpected '{c}' in '{text}'"),
+            });
+            rest = &rest[1..];
+        }
+    }
+    Ok(toks)
+}
+
+struct Parser<'a> {
+    toks: Vec<Tok>,
+    pos: usize,
+    ctx: &'a meval::Context<'a>,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<Tok> {
+        let tok = self.toks.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn expect(&mut self, want: Tok) -> Result<()> {
+        match self.next() {
+            Some(tok) if tok == want => Ok(()),
+            other => bail!("E_BAD_ARG: math: expected {want:?}, found {other:?}"),
+        }
+    }
+
+    // one precedence level; loops so chains stay left-associative
+    fn binary(&mut self, level: usize) -> Result<f64> {
+        let Some(ops) = LEVELS.get(level) else {
+            return self.unary();
+        };
+        let mut lhs = self.binary(level + 1)?;
+        while let Some(Tok::Op(op)) = self.toks.get(self.pos)
+            && ops.contains(op)
+        {
+            let op = *op;
+            self.pos += 1;
+            let rhs = self.binary(level + 1)?;
+            lhs = apply(op, lhs, rhs)?;
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<f64> {
+        match self.toks.get(self.pos) {
+            Some(Tok::Op("-")) => {
+                self.pos += 1;
+                Ok(-self.unary()?)
+            }
+            Some(Tok::Op("+")) => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<f64> {
+        match self.next() {
+            Some(Tok::Num(n)) => Ok(n),
+            Some(Tok::Open) => {
+                let val = self.binary(0)?;
+                self.expect(Tok::Close)?;
+                Ok(val)
+            }
+            Some(Tok::Ident(name)) if self.toks.get(self.pos) == Some(&Tok::Open) => {
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.toks.get(self.pos) == Some(&Tok::Close) {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        args.push(self.binary(0)?);
+                        match self.next() {
+                            Some(Tok::Comma) => {}
+                            Some(Tok::Close) => break,
+                            other => bail!("E_BAD_ARG: math: expected ',' or ')', found {other:?}"),
+                        }
+                    }
+                }
+                self.ctx
+                    .eval_func(&name, &args)
+                    .map_err(|e| anyhow::anyhow!("E_BAD_ARG: math: {name}(): {e}"))
+            }
+            Some(Tok::Ident(name)) => self
+                .ctx
+                .get_var(&name)
+                .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: math: unknown variable '{name}'")),
+            other => bail!("E_BAD_ARG: math: expected a value, found {other:?}"),
+        }
+    }
+}
+
+fn apply(op: &str, lhs: f64, rhs: f64) -> Result<f64> {
+    let flag = |b: bool| if b { 1.0 } else { 0.0 };
+    Ok(match op {
+        "+" => lhs + rhs,
+        "-" => lhs - rhs,
+        "*" => lhs * rhs,
+        "/" => lhs / rhs,
+        "%" => lhs % rhs,
+        "==" => flag(lhs == rhs),
+        "!=" => flag(lhs != rhs),
+        "<" => flag(lhs < rhs),
+        "<=" => flag(lhs <= rhs),
+        ">" => flag(lhs > rhs),
+        ">=" => flag(lhs >= rhs),
+        _ => {
+            let (a, b) = (to_int(lhs, op)?, to_int(rhs, op)?);
+            let out = match op {
+                "|" => a | b,
+                "^" => a ^ b,
+                "&" => a & b,
+                _ => {
+                    let shifted = u32::try_from(b).ok().and_then(|n| match op {
+                        "<<" => a.checked_shl(n),
+                        _ => a.checked_shr(n),
+                    });
+                    match shifted {
+                        Some(v) => v,
+                        None => bail!("E_MATH_DOMAIN: shift by {b} is outside 0..63"),
+                    }
+                }
+            };
+            out as f64
+        }
+    })
+}
+
+fn to_int(v: f64, op: &str) -> Result<i64> {
+    if v.fract() != 0.0 || !v.is_finite() || v < i64::MIN as f64 || v > i64::MAX as f64 {
+        bail!("E_NOT_INTEGER: '{op}' needs whole numbers, got {v}");
+    }
+    Ok(v as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn functions_use_numeric_vars() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("x", Value::Num(-9.0))?;
+        rt.set_var("y", Value::Num(4.0))?;
+        assert_eq!(eval(&mut rt, "[math@abs(x)]")?, Value::Num(9.0));
+        assert_eq!(eval(&mut rt, "[math@sqrt(y)]")?, Value::Num(2.0));
+        assert_eq!(eval(&mut rt, "[math@min(x,y)]")?, Value::Num(-9.0));
+        assert_eq!(eval(&mut rt, "[math@max(x,y)]")?, Value::Num(4.0));
+        assert_eq!(eval(&mut rt, "[math@pow(y,3)]")?, Value::Num(64.0));
+        assert_eq!(eval(&mut rt, "[math@floor(2.7)+1e1]")?, Value::Num(12.0));
+        Ok(())
+    }
+
+    #[test]
+    fn domain_errors_are_reported() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("x", Value::Num(-9.0))?;
+        let err = eval(&mut rt, "[math@sqrt(x)]").unwrap_err();
+        assert!(err.to_string().contains("E_MATH_DOMAIN"), "unexpected error: {err}");
+        Ok(())
+    }
+
+    #[test]
+    fn bitwise_ops_on_integers() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("flags", Value::Num(12.0))?;
+        assert_eq!(eval(&mut rt, "[math@flags&10]")?, Value::Num(8.0));
+        assert_eq!(eval(&mut rt, "[math@flags|3]")?, Value::Num(15.0));
+        assert_eq!(eval(&mut rt, "[math@flags^5]")?, Value::Num(9.0));
+        assert_eq!(eval(&mut rt, "[math@1<<4]")?, Value::Num(16.0));
+        // shifts bind tighter than &, arithmetic tighter than shifts
+        assert_eq!(eval(&mut rt, "[math@(flags|1)&1<<1+1]")?, Value::Num(4.0));
+        Ok(())
+    }
+
+    #[test]
+    fn bitwise_inside_parentheses_and_calls() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        assert_eq!(eval(&mut rt, "[math@2*(1<<3)]")?, Value::Num(16.0));
+        assert_eq!(eval(&mut rt, "[math@(6|1)+1]")?, Value::Num(8.0));
+        assert_eq!(eval(&mut rt, "[math@max((5&3)<<2, 1)]")?, Value::Num(4.0));
+        assert_eq!(eval(&mut rt, "[math@-(2|1)*2]")?, Value::Num(-6.0));
+        Ok(())
+    }
+
+    #[test]
+    fn bitwise_binds_below_comparison() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        // 6 & (3 == 3) -> 6 & 1
+        assert_eq!(eval(&mut rt, "[math@6&3==3]")?, Value::Num(0.0));
+        assert_eq!(eval(&mut rt, "[math@(6&3)==2]")?, Value::Num(1.0));
+        // shifts bind tighter than comparison
+        assert_eq!(eval(&mut rt, "[math@1<<2==4]")?, Value::Num(1.0));
+        assert_eq!(eval(&mut rt, "[math@4|1<2]")?, Value::Num(5.0));
+        Ok(())
+    }
+
+    #[test]
+    fn caret_is_xor_not_power() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        assert_eq!(eval(&mut rt, "[math@2^3]")?, Value::Num(1.0));
+        assert_eq!(eval(&mut rt, "[math@pow(2,3)]")?, Value::Num(8.0));
+        Ok(())
+    }
+
+    #[test]
+    fn bitwise_rejects_fractions() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let err = eval(&mut rt, "[math@1.5&1]").unwrap_err();
+        assert!(err.to_string().contains("E_NOT_INTEGER"), "unexpected error: {err}");
+        Ok(())
+    }
+}
@@ -3,10 +3,12 @@ use anyhow::Result;
 use crate::kernel::ast::Arg;
 use crate::kernel::{Packet, Runtime, Value};
 
+// [msg@"count: " + count + "!"] -> joins string literals, numbers, and variables into one string
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let expr = match p.arg.as_ref() {
         Some(Arg::Str(s)) => s.as_str(),
         Some(Arg::Ident(id)) => id.as_str(),
+        Some(Arg::Number(n)) => return Ok(Value::Str(n.to_string())),
         _ => "",
     };
     let out = eval_concat(rt, expr)?;
@@ -15,25 +17,92 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
 
 fn eval_concat(rt: &Runtime, expr: &str) -> Result<String> {
     let mut out = String::new();
-    for part in expr.split('+') {
+    for part in split_plus(expr) {
         let part = part.trim();
         if part.is_empty() {
             continue;
         }
-        if part.starts_with('"') && part.ends_with('"') {
+        if part.len() >= 2 && part.starts_with('"') && part.ends_with('"') {
             let s: String = serde_json::from_str(part)?;
             out.push_str(&s);
         } else if let Some(v) = rt.get_var(part) {
-            match v {
-                Value::Str(s) => out.push_str(&s),
-                Value::Num(n) => out.push_str(&format!("{}", n)),
-                Value::Bool(b) => out.push_str(if b { "true" } else { "false" }),
-                Value::Unit => {}
-                Value::Doc(_) => out.push_str("[doc]"),
+            // unset-but-declared values contribute nothing
+            if v != Value::Unit {
+                out.push_str(&v.to_string());
             }
+        } else if let Ok(n) = part.parse::<f64>() {
+            out.push_str(&Value::Num(n).to_string());
         } else {
             out.push_str(part);
         }
     }
     Ok(out)
 }
+
+// split on '+' outside of quoted segments
+fn split_plus(expr: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0usize;
+    let mut in_string = false;
+    let mut escape = false;
+    for (idx, ch) in expr.char_indices() {
+        if in_string {
+            if escape {
+                escape = false;
+            } else if ch == '\\' {
+                escape = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '+' => {
+                parts.push(&expr[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&expr[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn concatenates_literal_and_number_var() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("count", Value::Num(3.0))?;
+        let out = eval(&mut rt, "[msg@\"count: \" + count]")?;
+        assert_eq!(out, Value::Str("count: 3".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn concatenates_vars_and_trailing_literal() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("a", Value::Str("hi".into()))?;
+        rt.set_var("b", Value::Num(2.5))?;
+        let out = eval(&mut rt, "[msg@a + b + \"!\"]")?;
+        assert_eq!(out, Value::Str("hi2.5!".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_plus_inside_quotes() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let out = eval(&mut rt, "[msg@\"1 + 1 = \" + 2]")?;
+        assert_eq!(out, Value::Str("1 + 1 = 2".into()));
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use std::time::SystemTime;
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Datelike, Local, TimeZone, Timelike, Utc};
+
+use crate::kernel::values::Document;
+use crate::kernel::{Packet, Runtime, Value};
+
+// [now]      -> doc {year, month, day, hour, minute, second, epoch, tz} in local time
+// [now(utc)] -> same fields in UTC (tz is "UTC")
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let json = match crate::router::extract_paren(&p.op).map(str::trim) {
+        None | Some("local") => fields(&Local::now()),
+        Some("utc") => fields(&Utc::now()),
+        Some(other) => bail!("E_MODE_UNKNOWN: now mode unsupported: {other}"),
+    };
+    let root = rt.effective_root.clone().unwrap_or_default();
+    let path = root.join(&rt.cwd).join("_now.json");
+    let doc = Document::new(json, path, String::from("json"), SystemTime::now(), root);
+    Ok(Value::Doc(doc))
+}
+
+fn fields<Tz: TimeZone>(t: &DateTime<Tz>) -> serde_json::Value
+where
+    Tz::Offset: std::fmt::Display,
+{
+    serde_json::json!({
+        "year": t.year(),
+        "month": t.month(),
+        "day": t.day(),
+        "hour": t.hour(),
+        "minute": t.minute(),
+        "second": t.second(),
+        "epoch": t.timestamp(),
+        "tz": t.offset().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    #[test]
+    fn now_has_numeric_date_fields() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let node = router::parse("[now(utc)]>[store@now]>[get(year)@now]").map_err(anyhow::Error::new)?;
+        let year = rt.eval(&node)?;
+        assert!(matches!(year, Value::Num(y) if y >= 2024.0), "unexpected year: {year:?}");
+        let Some(Value::Doc(doc)) = rt.get_var("now") else {
+            panic!("now not stored");
+        };
+        for key in ["month", "day", "epoch"] {
+            assert!(doc.json[key].is_number(), "{key} not numeric: {}", doc.json);
+        }
+        assert_eq!(doc.json["tz"], "UTC");
+        Ok(())
+    }
+}
@@ -1,14 +1,17 @@
 use anyhow::{Result, bail};
 use std::time::SystemTime;
 
-use crate::kernel::values::Document;
+use crate::kernel::values::{Document, num_to_json};
 use crate::kernel::{Arg, Node, Packet, Runtime, Value};
 
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if p.op.starts_with("obj(") {
+        return accessor(rt, p);
+    }
     let body = p
         .body
         .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("obj needs body"))?;
+        .ok_or_else(|| anyhow::anyhow!("E_MISSING_BODY: obj needs body"))?;
     let root = rt
         .effective_root
         .as_ref()
@@ -26,6 +29,58 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     Ok(Value::Doc(doc))
 }
 
+// [obj(has,key)@handle]          -> Bool: the top-level key is present
+// [obj(get,key,default)@handle]  -> value under the top-level key, else default (Unit if omitted)
+// Keys are taken literally (no path grammar), so `a.b` is one key.
+fn accessor(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let opts = crate::router::extract_paren(&p.op).unwrap_or("");
+    let mut parts = opts.splitn(3, ',').map(str::trim);
+    let mode = parts.next().unwrap_or("");
+    let key = match parts.next() {
+        Some(k) if !k.is_empty() => k.trim_matches('"'),
+        _ => bail!("E_BAD_ARG: obj({mode}, key) needs a key"),
+    };
+    let default = parts.next();
+    let handle = match p.arg.as_ref() {
+        Some(Arg::Ident(id)) => id.as_str(),
+        _ => bail!("E_BAD_ARG: obj({mode}, ...) needs @<handle>"),
+    };
+    let doc = match rt.get_var(handle) {
+        Some(Value::Doc(d)) => d,
+        _ => bail!("E_HANDLE_UNKNOWN: '{handle}' is not a loaded document"),
+    };
+    let map = match &doc.json {
+        serde_json::Value::Object(map) => map,
+        _ => bail!("E_TYPE_MISMATCH: '{handle}' is not an object"),
+    };
+    match mode {
+        "has" if default.is_none() => Ok(Value::Bool(map.contains_key(key))),
+        "get" => match map.get(key) {
+            Some(v) => Ok(json_to_value(v, &doc)),
+            None => match default {
+                Some(tok) => crate::packets::coalesce::resolve_token(rt, tok),
+                None => Ok(Value::Unit),
+            },
+        },
+        "has" => bail!("E_BAD_ARG: obj(has, key) takes no default"),
+        other => bail!("E_MODE_UNKNOWN: unknown obj mode '{other}'"),
+    }
+}
+
+fn json_to_value(v: &serde_json::Value, meta: &Document) -> Value {
+    match v {
+        serde_json::Value::Null => Value::Unit,
+        serde_json::Value::Bool(b) => Value::Bool(*b),
+        serde_json::Value::Number(n) => n.as_f64().map(Value::Num).unwrap_or(Value::Unit),
+        serde_json::Value::String(s) => Value::Str(s.clone()),
+        serde_json::Value::Array(_) | serde_json::Value::Object(_) => {
+            let mut d = meta.clone();
+            d.json = v.clone();
+            Value::Doc(d)
+        }
+    }
+}
+
 fn build_object_from_body(rt: &Runtime, body: &Vec<Node>) -> Result<serde_json::Value> {
     use serde_json::Value as J;
     let mut root = serde_json::Map::new();
@@ -48,16 +103,16 @@ fn build_object_from_body(rt: &Runtime, body: &Vec<Node>) -> Result<serde_json::
                 } else if let Some(n) = parse_paren_name(&pkt.op) {
                     n.to_string()
                 } else {
-                    bail!("sect needs @<name> or (name)");
+                    bail!("E_BAD_ARG: sect needs @<name> or (name)");
                 };
                 let inner = pkt
                     .body
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("sect missing body"))?;
+                    .ok_or_else(|| anyhow::anyhow!("E_MISSING_BODY: sect missing body"))?;
                 let obj = build_object_from_body(rt, inner)?;
                 root.insert(name, obj);
             } else {
-                bail!(format!("unsupported packet in [obj] body: [{}]", pkt.op));
+                bail!("E_UNSUPPORTED_PACKET: [{}] is not allowed in an [obj] body", pkt.op);
             }
         }
     }
@@ -84,9 +139,7 @@ fn value_to_json(v: Value) -> Result<serde_json::Value> {
     Ok(match v {
         Value::Unit => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(b),
-        Value::Num(n) => serde_json::Value::Number(
-            serde_json::Number::from_f64(n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-        ),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Value::Str(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
         Value::Doc(d) => d.json,
     })
@@ -94,21 +147,13 @@ fn value_to_json(v: Value) -> Result<serde_json::Value> {
 
 fn arg_to_json(rt: &Runtime, arg: &Arg) -> Result<serde_json::Value> {
     Ok(match arg {
-        Arg::Number(n) => {
-            if n.fract() == 0.0 && *n >= (i64::MIN as f64) && *n <= (i64::MAX as f64) {
-                serde_json::Value::Number(serde_json::Number::from(*n as i64))
-            } else {
-                serde_json::Value::Number(
-                    serde_json::Number::from_f64(*n)
-                        .ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-                )
-            }
-        }
+        Arg::Number(n) => num_to_json(*n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Arg::Str(s) => serde_json::from_str(s).unwrap_or(serde_json::Value::String(s.clone())),
         Arg::Ident(id) => match id.as_str() {
             "true" => serde_json::Value::Bool(true),
             "false" => serde_json::Value::Bool(false),
             "null" => serde_json::Value::Null,
+            "_" => value_to_json(rt.last.clone())?,
             other => {
                 if let Some(v) = rt.get_var(other) {
                     value_to_json(v)?
@@ -120,3 +165,41 @@ fn arg_to_json(rt: &Runtime, arg: &Arg) -> Result<serde_json::Value> {
         _ => serde_json::Value::Null,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    fn flat_doc(rt: &mut Runtime) -> Result<()> {
+        let json = serde_json::json!({"name": "ada", "a.b": 1});
+        let doc = Document::new(json, "cfg.json".into(), "json".into(), SystemTime::now(), Default::default());
+        rt.set_var("cfg", Value::Doc(doc))
+    }
+
+    #[test]
+    fn has_checks_top_level_keys() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        flat_doc(&mut rt)?;
+        assert_eq!(eval(&mut rt, "[obj(has,name)@cfg]")?, Value::Bool(true));
+        assert_eq!(eval(&mut rt, "[obj(has,a.b)@cfg]")?, Value::Bool(true));
+        assert_eq!(eval(&mut rt, "[obj(has,age)@cfg]")?, Value::Bool(false));
+        Ok(())
+    }
+
+    #[test]
+    fn get_falls_back_to_default() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        flat_doc(&mut rt)?;
+        assert_eq!(eval(&mut rt, "[obj(get,name,\"anon\")@cfg]")?, Value::Str("ada".into()));
+        assert_eq!(eval(&mut rt, "[obj(get,age,\"anon\")@cfg]")?, Value::Str("anon".into()));
+        assert_eq!(eval(&mut rt, "[obj(get,age,30)@cfg]")?, Value::Num(30.0));
+        assert_eq!(eval(&mut rt, "[obj(get,age)@cfg]")?, Value::Unit);
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::{Result, bail};
 use std::time::SystemTime;
 
 use crate::kernel::values::Document;
-use crate::kernel::{Arg, Packet, Runtime, Value};
+use crate::kernel::{Arg, Node, Packet, Runtime, Value};
 
 fn detect_mode(op: &str) -> Option<&str> {
     if let Some(rest) = op.strip_prefix("parse(")
@@ -14,7 +14,10 @@ fn detect_mode(op: &str) -> Option<&str> {
 
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let mode = detect_mode(&p.op)
-        .ok_or_else(|| anyhow::anyhow!("parse needs mode: parse(json|yaml|toml)"))?;
+        .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: parse needs mode: parse(json|yaml|toml)"))?;
+    if mode.trim().eq_ignore_ascii_case("schema") {
+        return schema(rt, p);
+    }
     let s = match &p.arg {
         Some(Arg::Str(s)) => s.clone(),
         Some(Arg::Ident(id)) => id.clone(),
@@ -36,7 +39,7 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             let tv: toml::Value = toml::from_str(&s)?;
             serde_json::to_value(tv)?
         }
-        other => bail!(format!("parse_mode_unsupported:{other}")),
+        other => bail!("E_FORMAT_UNKNOWN: unsupported parse mode '{other}'"),
     };
 
     // Build a memory-backed Document so users can [mod] and [dump]; [save] is optional if they set a file later
@@ -55,3 +58,96 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     );
     Ok(Value::Doc(doc))
 }
+
+// [parse(schema)@cfg]{[field(user.name, str)][field(user.age, num)]}
+// -> array Doc of problems, one {path, expected, found} per field that is missing
+//    ("found": "missing") or of another type; [] when the doc matches.
+// Types: str, num, bool, null, array, object, doc (array or object), any (just present).
+fn schema(rt: &Runtime, p: &Packet) -> Result<Value> {
+    let doc = match p.arg.as_ref().map(|a| rt.resolve_arg(a)).transpose()? {
+        Some(Value::Doc(d)) => d,
+        None => match &rt.last {
+            Value::Doc(d) => d.clone(),
+            _ => bail!("E_NO_DOC: parse(schema) needs @<doc> or a piped document"),
+        },
+        Some(_) => bail!("E_NO_DOC: parse(schema) needs @<doc> or a piped document"),
+    };
+    let body = match &p.body {
+        Some(body) => body,
+        None => bail!("E_MISSING_BODY: parse(schema) needs {{[field(path, type)]...}}"),
+    };
+    let mut errors = Vec::new();
+    for node in body {
+        let (path, want) = match node {
+            Node::Packet(f) if f.ns.is_none() && f.op.starts_with("field(") => {
+                match crate::router::extract_paren(&f.op).and_then(|o| o.rsplit_once(',')) {
+                    Some((path, want)) if !path.trim().is_empty() => (path.trim(), want.trim()),
+                    _ => bail!("E_BAD_ARG: use [field(path, type)], got [{}]", f.op),
+                }
+            }
+            _ => bail!("E_BAD_ARG: parse(schema) body only takes [field(path, type)]"),
+        };
+        if !matches!(want, "str" | "num" | "bool" | "null" | "array" | "object" | "doc" | "any") {
+            bail!("E_BAD_ARG: unknown field type '{want}' (str|num|bool|null|array|object|doc|any)");
+        }
+        let found = match crate::packets::query::read_value(rt, &doc, path)? {
+            None => "missing",
+            Some(Value::Unit) => "null",
+            Some(Value::Bool(_)) => "bool",
+            Some(Value::Num(_)) => "num",
+            Some(Value::Str(_)) => "str",
+            Some(Value::Doc(d)) if d.json.is_array() => "array",
+            Some(Value::Doc(_)) => "object",
+        };
+        let ok = match want {
+            "any" => found != "missing",
+            "doc" => matches!(found, "array" | "object"),
+            _ => found == want,
+        };
+        if !ok {
+            errors.push(serde_json::json!({"path": path, "expected": want, "found": found}));
+        }
+    }
+    let path = doc.root.join(&rt.cwd).join("_schema.json");
+    Ok(Value::Doc(Document::new(
+        serde_json::Value::Array(errors),
+        path,
+        String::from("json"),
+        SystemTime::now(),
+        doc.root,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    #[test]
+    fn schema_reports_missing_and_mistyped_fields() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let check = |rt: &mut Runtime, json: serde_json::Value| -> Result<serde_json::Value> {
+            let doc = Document::new(json, "cfg.json".into(), "json".into(), SystemTime::now(), "".into());
+            rt.set_var("cfg", Value::Doc(doc))?;
+            let node = router::parse("[parse(schema)@cfg]{[field(user.name, str)][field(user.age, num)][field(tags, array)]}")
+                .map_err(anyhow::Error::new)?;
+            match rt.eval(&node)? {
+                Value::Doc(d) => Ok(d.json),
+                other => bail!("expected doc, got {other:?}"),
+            }
+        };
+
+        let valid = serde_json::json!({"user": {"name": "Ana", "age": 31}, "tags": []});
+        assert_eq!(check(&mut rt, valid)?, serde_json::json!([]));
+
+        let broken = serde_json::json!({"user": {"age": "31"}, "tags": []});
+        assert_eq!(
+            check(&mut rt, broken)?,
+            serde_json::json!([
+                {"path": "user.name", "expected": "str", "found": "missing"},
+                {"path": "user.age", "expected": "num", "found": "str"},
+            ])
+        );
+        Ok(())
+    }
+}
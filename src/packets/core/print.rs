@@ -1,22 +1,136 @@
 use crate::kernel::{Packet, Runtime, Value};
-use anyhow::Result;
+use anyhow::{Result, bail};
+use std::io::{IsTerminal, Write};
 
+const COLOR_RESET: &str = "\x1b[0m";
+const COLORS: &[(&str, &str)] = &[
+    ("red", "\x1b[31m"),
+    ("green", "\x1b[32m"),
+    ("yellow", "\x1b[33m"),
+    ("blue", "\x1b[34m"),
+    ("magenta", "\x1b[35m"),
+    ("cyan", "\x1b[36m"),
+    ("gray", "\x1b[90m"),
+];
+
+// [print@value] -> prints the value (last value when no arg); pass-through
+// [print(table)] -> prints an array of objects as an aligned table
+// [print(color:green)@"ok"] -> ANSI-colored line; plain when NO_COLOR is set or stdout isn't a TTY
+// [print(inline)@"."] / [print(nonl)@"."] -> no trailing newline, stdout flushed right away;
+// combines with color: [print(inline,color:green)@"."]
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let v = match p.arg.as_ref() {
         Some(arg) => rt.resolve_arg(arg)?,
         None => rt.last.clone(),
     };
-    println!("{}", pretty(&v));
+    let mut inline = false;
+    let mut mode = None;
+    for opt in crate::router::extract_paren(&p.op).unwrap_or("").split(',').map(str::trim) {
+        match opt {
+            "" => {}
+            "inline" | "nonl" => inline = true,
+            _ if mode.is_none() => mode = Some(opt),
+            _ => bail!("E_BAD_ARG: print takes one mode besides inline, got '{opt}'"),
+        }
+    }
+    match mode {
+        None => emit(&mut std::io::stdout(), &v.to_string(), inline)?,
+        Some("table") if inline => bail!("E_BAD_ARG: print(table) can't be inline"),
+        Some("table") => match &v {
+            Value::Doc(d) => print!("{}", render_table(&d.json)?),
+            _ => bail!("E_NO_DOC: print(table) needs an array document"),
+        },
+        Some(opt) if opt.starts_with("color:") => {
+            let name = opt["color:".len()..].trim();
+            let Some((_, code)) = COLORS.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)) else {
+                let names: Vec<&str> = COLORS.iter().map(|(n, _)| *n).collect();
+                bail!("E_BAD_ARG: unknown print color '{name}' (use {})", names.join(", "));
+            };
+            let enabled = use_color(std::env::var("NO_COLOR").ok(), std::io::stdout().is_terminal());
+            emit(&mut std::io::stdout(), &paint(&v.to_string(), code, enabled), inline)?;
+        }
+        Some(other) => bail!("E_MODE_UNKNOWN: print mode unsupported: {other}"),
+    }
     Ok(v.clone())
 }
 
-fn pretty(v: &Value) -> String {
+// a line, or for inline output the bare text flushed so it shows up before the next newline
+fn emit(out: &mut impl Write, text: &str, inline: bool) -> Result<()> {
+    if inline {
+        write!(out, "{text}")?;
+        out.flush()?;
+    } else {
+        writeln!(out, "{text}")?;
+    }
+    Ok(())
+}
+
+// https://no-color.org: any non-empty NO_COLOR turns color off
+fn use_color(no_color: Option<String>, tty: bool) -> bool {
+    tty && no_color.is_none_or(|v| v.is_empty())
+}
+
+fn paint(text: &str, code: &str, enabled: bool) -> String {
+    if enabled {
+        format!("{code}{text}{COLOR_RESET}")
+    } else {
+        text.to_string()
+    }
+}
+
+// Header is the union of keys in first-seen order; nested cells are JSON-encoded.
+fn render_table(json: &serde_json::Value) -> Result<String> {
+    let Some(rows) = json.as_array() else {
+        bail!("E_NOT_ARRAY: print(table) needs an array of objects");
+    };
+    let mut header: Vec<&str> = Vec::new();
+    for row in rows {
+        let Some(obj) = row.as_object() else {
+            bail!("E_NOT_OBJECT: print(table) rows must be objects");
+        };
+        for key in obj.keys() {
+            if !header.contains(&key.as_str()) {
+                header.push(key);
+            }
+        }
+    }
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| header.iter().map(|k| cell(row.get(*k))).collect())
+        .collect();
+    let widths: Vec<usize> = header
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            cells
+                .iter()
+                .map(|r| r[i].chars().count())
+                .fold(h.chars().count(), usize::max)
+        })
+        .collect();
+
+    let line = |vals: Vec<&str>| {
+        let padded: Vec<String> = vals
+            .iter()
+            .zip(&widths)
+            .map(|(v, w)| format!("{v:<w$}"))
+            .collect();
+        format!("{}\n", padded.join(" | ").trim_end())
+    };
+    let mut out = line(header.clone());
+    let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    out.push_str(&format!("{}\n", rule.join("-+-")));
+    for row in &cells {
+        out.push_str(&line(row.iter().map(String::as_str).collect()));
+    }
+    Ok(out)
+}
+
+fn cell(v: Option<&serde_json::Value>) -> String {
     match v {
-        Value::Str(s) => s.clone(),
-        Value::Num(n) => format!("{}", n),
-        Value::Bool(b) => format!("{}", b),
-        Value::Doc(_) => String::from("<doc>"),
-        Value::Unit => String::from("()"),
+        None | Some(serde_json::Value::Null) => String::new(),
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(other) => other.to_string(),
     }
 }
 
@@ -74,7 +188,7 @@ fn format_composite(rt: &Runtime, raw: &str) -> Option<String> {
                     }
                 }
                 let val = rt.get_var(&ident).unwrap_or(Value::Unit);
-                out.push_str(&pretty(&val));
+                out.push_str(&val.to_string());
                 saw = true;
             }
             _ => {
@@ -86,3 +200,42 @@ fn format_composite(rt: &Runtime, raw: &str) -> Option<String> {
 
     saw.then_some(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_color_strips_ansi_codes() {
+        let enabled = use_color(Some("1".into()), true);
+        assert_eq!(paint("ok", COLORS[1].1, enabled), "ok");
+        assert!(!use_color(None, false), "non-TTY output stays plain");
+        assert_eq!(paint("ok", COLORS[1].1, use_color(None, true)), "\x1b[32mok\x1b[0m");
+    }
+
+    #[test]
+    fn inline_output_has_no_newline() -> Result<()> {
+        let mut out = Vec::new();
+        emit(&mut out, "[##", true)?;
+        emit(&mut out, "#]", true)?;
+        assert_eq!(String::from_utf8(out.clone())?, "[###]");
+        emit(&mut out, " done", false)?;
+        assert_eq!(String::from_utf8(out)?, "[###] done\n");
+        Ok(())
+    }
+
+    #[test]
+    fn table_has_header_and_rows() -> Result<()> {
+        let json = serde_json::json!([
+            {"name": "Ana", "age": 31},
+            {"name": "Bo", "tags": ["x"]}
+        ]);
+        let out = render_table(&json)?;
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "name | age | tags");
+        assert_eq!(lines[1], "-----+-----+------");
+        assert_eq!(lines[2], "Ana  | 31  |");
+        assert_eq!(lines[3], "Bo   |     | [\"x\"]");
+        Ok(())
+    }
+}
@@ -1,29 +1,59 @@
 use anyhow::{Result, anyhow, bail};
 
+use crate::kernel::values::Document;
 use crate::kernel::{Packet, Runtime, Value};
+use crate::packets::query::json_to_value;
 
 // [rand] -> uniform float in [0,1)
 // [rand(min,max)] -> random number between evaluated bounds (ints yield ints)
+// [rand(choice)@items] -> one random element of an array Doc
+// [rand(shuffle)@items] -> shuffled copy of an array Doc; the input is untouched
+// All forms draw from fastrand's thread-local generator, so fastrand::seed makes runs repeat.
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     if let Some(ns) = &p.ns {
-        bail!("rand does not support namespace '{}'.", ns);
+        bail!("E_BAD_ARG: rand does not support namespace '{}'.", ns);
     }
 
     if p.op == "rand" {
         if p.arg.is_some() {
-            bail!("rand expects either no args or parentheses (min,max)");
+            bail!("E_BAD_ARG: rand expects either no args or parentheses (min,max)");
         }
         return Ok(Value::Num(fastrand::f64()));
     }
 
     if p.op.starts_with("rand(") {
         let inner =
-            crate::router::extract_paren(&p.op).ok_or_else(|| anyhow!("rand needs (min,max)"))?;
+            crate::router::extract_paren(&p.op).ok_or_else(|| anyhow!("E_BAD_ARG: rand needs (min,max)"))?;
+        if matches!(inner.trim(), "choice" | "shuffle") {
+            return pick(rt, p, inner.trim());
+        }
         let (min, max) = parse_bounds(rt, inner)?;
         return Ok(Value::Num(sample_between(min, max)?));
     }
 
-    bail!("unknown rand form")
+    bail!("E_BAD_ARG: unknown rand form")
+}
+
+fn pick(rt: &Runtime, p: &Packet, mode: &str) -> Result<Value> {
+    let v = match p.arg.as_ref() {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    let doc = match v {
+        Value::Doc(d) if d.json.is_array() => d,
+        _ => bail!("E_NOT_ARRAY: rand({mode}) needs an array"),
+    };
+    let items = doc.json.as_array().cloned().unwrap_or_default();
+    if mode == "choice" {
+        if items.is_empty() {
+            bail!("E_ARRAY_EMPTY: rand(choice) needs a non-empty array");
+        }
+        return Ok(json_to_value(&items[fastrand::usize(..items.len())], &doc));
+    }
+    let mut items = items;
+    fastrand::shuffle(&mut items);
+    let json = serde_json::Value::Array(items);
+    Ok(Value::Doc(Document::scratch(doc.root, &rt.cwd, "_shuffle.json", json)))
 }
 
 fn parse_bounds(rt: &mut Runtime, inner: &str) -> Result<(f64, f64)> {
@@ -33,12 +63,12 @@ fn parse_bounds(rt: &mut Runtime, inner: &str) -> Result<(f64, f64)> {
         .filter(|s| !s.is_empty())
         .collect();
     if parts.len() != 2 {
-        bail!("rand expects exactly two bounds");
+        bail!("E_BAD_ARG: rand expects exactly two bounds");
     }
     let min = eval_expr(rt, parts[0])?;
     let max = eval_expr(rt, parts[1])?;
     if min > max {
-        bail!("rand bounds inverted");
+        bail!("E_BAD_RANGE: rand bounds inverted");
     }
     Ok((min, max))
 }
@@ -46,7 +76,7 @@ fn parse_bounds(rt: &mut Runtime, inner: &str) -> Result<(f64, f64)> {
 fn eval_expr(rt: &mut Runtime, expr: &str) -> Result<f64> {
     let trimmed = expr.trim();
     if trimmed.is_empty() {
-        bail!("empty bound");
+        bail!("E_BAD_ARG: empty bound");
     }
 
     if let Ok(n) = trimmed.parse::<f64>() {
@@ -54,7 +84,7 @@ fn eval_expr(rt: &mut Runtime, expr: &str) -> Result<f64> {
     }
 
     if let Some(val) = rt.get_var(trimmed) {
-        return value_to_num(&val).ok_or_else(|| anyhow!("non-numeric bound"));
+        return value_to_num(&val).ok_or_else(|| anyhow!("E_NOT_NUMERIC: non-numeric bound"));
     }
 
     if trimmed.starts_with('[') {
@@ -69,10 +99,10 @@ fn eval_expr(rt: &mut Runtime, expr: &str) -> Result<f64> {
             }
         };
         rt.last = prev_last;
-        return value_to_num(&out).ok_or_else(|| anyhow!("non-numeric bound"));
+        return value_to_num(&out).ok_or_else(|| anyhow!("E_NOT_NUMERIC: non-numeric bound"));
     }
 
-    bail!("unsupported bound expression")
+    bail!("E_BAD_ARG: unsupported bound expression")
 }
 
 fn value_to_num(v: &Value) -> Option<f64> {
@@ -94,14 +124,14 @@ fn sample_between(min: f64, max: f64) -> Result<f64> {
         let a = min as i64;
         let b = max as i64;
         if a > b {
-            bail!("rand bounds inverted");
+            bail!("E_BAD_RANGE: rand bounds inverted");
         }
         return Ok(fastrand::i64(a..=b) as f64);
     }
 
     let span = max - min;
     if span <= 0.0 {
-        bail!("rand bounds inverted");
+        bail!("E_BAD_RANGE: rand bounds inverted");
     }
     Ok(min + fastrand::f64() * span)
 }
@@ -138,6 +168,45 @@ mod tests {
         Ok(())
     }
 
+    fn with_items(rt: &mut Runtime, json: serde_json::Value) -> Result<()> {
+        let doc = Document::new(
+            json,
+            std::path::PathBuf::from("items.json"),
+            "json".into(),
+            std::time::SystemTime::now(),
+            std::path::PathBuf::new(),
+        );
+        rt.set_var("items", Value::Doc(doc))
+    }
+
+    #[test]
+    fn choice_and_shuffle_draw_from_the_array() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        with_items(&mut rt, serde_json::json!([10, 20, 30, 40, 50]))?;
+        let choice = crate::router::parse("[rand(choice)@items]").map_err(anyhow::Error::new)?;
+        for _ in 0..20 {
+            match rt.eval(&choice)? {
+                Value::Num(n) => assert!([10.0, 20.0, 30.0, 40.0, 50.0].contains(&n)),
+                other => bail!("expected a number, got {other:?}"),
+            }
+        }
+
+        let shuffle = crate::router::parse("[rand(shuffle)@items]").map_err(anyhow::Error::new)?;
+        fastrand::seed(11);
+        let first = rt.eval(&shuffle)?;
+        fastrand::seed(11);
+        assert_eq!(rt.eval(&shuffle)?, first);
+        let Value::Doc(d) = first else { bail!("expected doc") };
+        let mut nums: Vec<i64> = d.json.as_array().unwrap().iter().filter_map(|v| v.as_i64()).collect();
+        nums.sort();
+        assert_eq!(nums, [10, 20, 30, 40, 50]);
+
+        with_items(&mut rt, serde_json::json!([]))?;
+        let err = rt.eval(&choice).unwrap_err();
+        assert!(err.to_string().contains("E_ARRAY_EMPTY"), "{err}");
+        Ok(())
+    }
+
     #[test]
     fn rand_with_packet_bounds() -> Result<()> {
         let mut rt = Runtime::new()?;
@@ -0,0 +1,116 @@
+use std::time::SystemTime;
+
+use anyhow::{Result, bail};
+
+use crate::kernel::values::Document;
+use crate::kernel::{Packet, Runtime, Value};
+
+// [range(1,5)]     -> [1,2,3,4,5] (bounds inclusive)
+// [range(0,10,2)]  -> [0,2,4,6,8,10]
+// [range(5,1)]     -> [5,4,3,2,1] (step defaults to -1 when counting down)
+// Bounds and step are integer literals or variables holding integers.
+const MAX_ITEMS: i64 = 1_000_000;
+
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let inner = crate::router::extract_paren(&p.op)
+        .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: range needs (start,end[,step])"))?;
+    let parts: Vec<&str> = inner.split(',').map(str::trim).collect();
+    let (start, end, step) = match parts.as_slice() {
+        [a, b] => {
+            let (a, b) = (bound(rt, a)?, bound(rt, b)?);
+            (a, b, if a <= b { 1 } else { -1 })
+        }
+        [a, b, s] => (bound(rt, a)?, bound(rt, b)?, bound(rt, s)?),
+        _ => bail!("E_BAD_ARG: range needs (start,end[,step])"),
+    };
+    if step == 0 {
+        bail!("E_BAD_RANGE: range step cannot be 0");
+    }
+    let overflow = || anyhow::anyhow!("E_BAD_ARG: range({start},{end},{step}) overflows a 64-bit integer");
+    let span = end.checked_sub(start).ok_or_else(overflow)?;
+    if span.signum() * step.signum() < 0 {
+        bail!("E_BAD_RANGE: step {step} never reaches {end} from {start}");
+    }
+    let last = span.checked_div(step).ok_or_else(overflow)?;
+    if last >= MAX_ITEMS {
+        bail!("E_BAD_RANGE: range exceeds {MAX_ITEMS} items");
+    }
+
+    // start + i*step stays between start and end, so only the setup above can overflow
+    let items: Vec<serde_json::Value> = (0..=last).map(|i| serde_json::Value::from(start + i * step)).collect();
+
+    let root = rt.effective_root.clone().unwrap_or_default();
+    let path = root.join(&rt.cwd).join("_range.json");
+    let doc = Document::new(
+        serde_json::Value::Array(items),
+        path,
+        String::from("json"),
+        SystemTime::now(),
+        root,
+    );
+    Ok(Value::Doc(doc))
+}
+
+fn bound(rt: &Runtime, token: &str) -> Result<i64> {
+    let n = match token.parse::<f64>() {
+        Ok(n) => n,
+        Err(_) => match rt.get_var(token) {
+            Some(v) => v
+                .try_num()
+                .ok_or_else(|| anyhow::anyhow!("E_NOT_NUMERIC: range bound '{token}' is not a number"))?,
+            None => bail!("E_BAD_ARG: unknown range bound '{token}'"),
+        },
+    };
+    if n.fract() != 0.0 || !n.is_finite() {
+        bail!("E_NOT_NUMERIC: range bound '{token}' must be an integer");
+    }
+    if n < i64::MIN as f64 || n >= i64::MAX as f64 {
+        bail!("E_BAD_ARG: range bound '{token}' is outside the 64-bit integer range");
+    }
+    Ok(n as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval_json(rt: &mut Runtime, script: &str) -> Result<serde_json::Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        match rt.eval(&node)? {
+            Value::Doc(d) => Ok(d.json),
+            other => bail!("expected doc, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn bounds_are_inclusive() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        assert_eq!(eval_json(&mut rt, "[range(1,5)]")?, serde_json::json!([1, 2, 3, 4, 5]));
+        assert_eq!(eval_json(&mut rt, "[range(3,1)]")?, serde_json::json!([3, 2, 1]));
+        Ok(())
+    }
+
+    #[test]
+    fn step_and_var_bounds() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("top", Value::Num(10.0))?;
+        assert_eq!(eval_json(&mut rt, "[range(0,top,2)]")?, serde_json::json!([0, 2, 4, 6, 8, 10]));
+        assert!(eval_json(&mut rt, "[range(0,10,-1)]").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn overflowing_bounds_are_bad_args() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        for script in [
+            "[range(-9000000000000000000,9000000000000000000)]",
+            "[range(0,-9223372036854775808,-1)]",
+            "[range(0,1e19)]",
+        ] {
+            let err = eval_json(&mut rt, script).unwrap_err();
+            assert_eq!(crate::kernel::runtime::error_code(&err).as_deref(), Some("E_BAD_ARG"), "{script}: {err:#}");
+        }
+        Ok(())
+    }
+}
@@ -4,7 +4,7 @@ use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-use crate::kernel::values::Document;
+use crate::kernel::values::{Document, num_to_json};
 use crate::kernel::{Packet, Runtime, Value};
 
 fn extract_group_block(content: &str, group: &str) -> Vec<String> {
@@ -310,11 +310,15 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     //   reflect(vars)
     //   reflect(runtime)
     //   reflect(doc)[@handle]
+    //   reflect(catalog)
     let mode = if let Some(rest) = p.op.strip_prefix("reflect(") {
         rest.trim_end_matches(')')
     } else {
         ""
     };
+    if mode == "catalog" {
+        return reflect_catalog(rt);
+    }
 
     let root = rt
         .effective_root
@@ -435,12 +439,7 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             Ok(Value::Doc(doc))
         }
         "vars" => {
-            use serde_json::Value as J;
-            let mut obj = serde_json::Map::new();
-            for (k, v) in rt.vars.clone() {
-                obj.insert(k, value_to_json_reflect(v)?);
-            }
-            let json = J::Object(obj);
+            let json = vars_to_json(rt)?;
             let doc = Document::new(
                 json,
                 root.join("_reflect_vars.json"),
@@ -475,12 +474,12 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             let d = if let Some(crate::kernel::ast::Arg::Ident(id)) = p.arg.as_ref() {
                 match rt.get_var(id) {
                     Some(Value::Doc(d)) => d,
-                    _ => bail!("handle_unknown"),
+                    _ => bail!("E_HANDLE_UNKNOWN: '{id}' is not a loaded document"),
                 }
             } else {
                 match &rt.last {
                     Value::Doc(d) => d.clone(),
-                    _ => bail!("no_doc_last"),
+                    _ => bail!("E_NO_DOC: last value is not a document"),
                 }
             };
             use serde_json::json;
@@ -499,17 +498,50 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             );
             Ok(Value::Doc(doc))
         }
-        _ => bail!("reflect mode unsupported"),
+        _ => bail!("E_MODE_UNKNOWN: reflect mode unsupported"),
     }
 }
 
+// Snapshot of every runtime variable; shared with [dump(vars)]
+pub fn vars_to_json(rt: &Runtime) -> Result<serde_json::Value> {
+    // maps keep insertion order, so sort the HashMap keys for stable output
+    let mut vars: Vec<(String, Value)> = rt.vars.clone().into_iter().collect();
+    vars.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut obj = serde_json::Map::new();
+    for (k, v) in vars {
+        obj.insert(k, value_to_json_reflect(v)?);
+    }
+    Ok(serde_json::Value::Object(obj))
+}
+
+// [{name, namespaces, body}] for every known packet; needs no red box
+fn reflect_catalog(rt: &Runtime) -> Result<Value> {
+    let items = crate::kernel::packet_catalog::catalog()
+        .into_iter()
+        .map(|shape| {
+            serde_json::json!({
+                "name": shape.name,
+                "namespaces": shape.namespaces,
+                "body": shape.takes_body,
+            })
+        })
+        .collect();
+    let root = rt.effective_root.clone().unwrap_or_default();
+    let doc = Document::new(
+        serde_json::Value::Array(items),
+        root.join(&rt.cwd).join("_catalog.json"),
+        "json".into(),
+        SystemTime::now(),
+        root,
+    );
+    Ok(Value::Doc(doc))
+}
+
 fn value_to_json_reflect(v: Value) -> Result<serde_json::Value> {
     Ok(match v {
         Value::Unit => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(b),
-        Value::Num(n) => serde_json::Value::Number(
-            serde_json::Number::from_f64(n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-        ),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Value::Str(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
         Value::Doc(d) => d.json,
     })
@@ -1,26 +1,50 @@
 use crate::kernel::ast::Arg;
-use crate::kernel::{Packet, Runtime, Value};
+use crate::kernel::{Node, Packet, Runtime, Value};
 use crate::packets::conditionals::parse_cond;
 use anyhow::{Result, bail};
 
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if p.ns.is_none() && crate::router::extract_paren(&p.op).map(str::trim) == Some("swap") {
+        return swap(rt, p);
+    }
+    if p.ns.is_none()
+        && let Some(opts) = crate::router::extract_paren(&p.op)
+        && matches!(opts.split(',').next().map(str::trim), Some("inc" | "dec"))
+    {
+        return step(rt, p, opts);
+    }
+    if p.ns.is_none() && p.op.starts_with("store(") {
+        return destructure(rt, p);
+    }
     let name = match p.arg.as_ref() {
         Some(Arg::Ident(id)) => id.as_str(),
-        _ => bail!("store needs @<ident>"),
+        _ => bail!("E_BAD_ARG: store needs @<ident>"),
     };
     let val = rt.last.clone();
+    let prev = rt.vars.get(name).cloned();
 
     match p.ns.as_deref() {
         Some("store") => {
             let mode = p.op.as_str();
             if mode == "rigid" {
                 if rt.vars.contains_key(name) {
-                    bail!("var_exists");
+                    bail!("E_VAR_EXISTS: '{name}' already exists");
                 }
                 rt.set_var(name, val.clone())?;
                 rt.rigid.insert(name.to_string());
             } else if mode == "fluid" {
+                check_type(rt, name, &val)?;
                 rt.set_var(name, val.clone())?;
+            } else if matches!(mode, "num" | "str" | "bool") {
+                // declaring may leave the var unset; every later store must match
+                if val != Value::Unit && type_name(&val) != mode {
+                    bail!("E_TYPE_MISMATCH: '{name}' declared {mode}, got {}", type_name(&val));
+                }
+                rt.typed.insert(name.to_string(), mode.to_string());
+                rt.set_var(name, val.clone())?;
+            } else if mode.replace(' ', "") == "context(clear)" {
+                // drop every conditional binding; a direct binding stays
+                rt.ctx_vars.remove(name);
             } else if mode.starts_with("context") {
                 let mut src = mode.trim_start_matches("context").trim();
                 if src.starts_with('(') && src.ends_with(')') {
@@ -32,7 +56,7 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
                     .or_default()
                     .push((cond, val.clone()));
             } else {
-                bail!("unknown_store_mode");
+                bail!("E_MODE_UNKNOWN: unknown store mode '{mode}'");
             }
         }
         _ => {
@@ -45,18 +69,184 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
                     .or_default()
                     .push((cond, val.clone()));
             } else {
+                check_type(rt, name, &val)?;
                 rt.set_var(name, val.clone())?;
             }
         }
     }
 
+    let changed = rt.vars.get(name) != prev.as_ref();
+    mark_changed(rt, changed);
+    Ok(val)
+}
+
+// read back by [changed]; kept off the variable table so dump/reflect don't show it
+fn mark_changed(rt: &mut Runtime, changed: bool) {
+    rt.last_store_changed = changed;
+}
+
+// every packet that binds a user variable goes through this, not just [store]
+pub fn check_type(rt: &Runtime, name: &str, val: &Value) -> Result<()> {
+    if let Some(want) = rt.typed.get(name)
+        && type_name(val) != want
+    {
+        bail!("E_TYPE_MISMATCH: '{name}' declared {want}, got {}", type_name(val));
+    }
+    Ok(())
+}
+
+fn type_name(v: &Value) -> &'static str {
+    match v {
+        Value::Unit => "unit",
+        Value::Bool(_) => "bool",
+        Value::Num(_) => "num",
+        Value::Str(_) => "str",
+        Value::Doc(_) => "doc",
+    }
+}
+
+// [store(swap)@a,b] -> a and b trade values; both must be set. Nothing is written unless
+// both sides pass the rigid/typed checks. The piped value passes through.
+fn swap(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let list = match p.arg.as_ref() {
+        Some(Arg::Str(s)) => s.as_str(),
+        _ => bail!("E_BAD_ARG: store(swap) needs @a,b"),
+    };
+    let (a, b) = match list.split_once(',') {
+        Some((a, b)) if !a.trim().is_empty() && !b.trim().is_empty() && !b.contains(',') => {
+            (a.trim(), b.trim())
+        }
+        _ => bail!("E_BAD_ARG: store(swap) needs @a,b"),
+    };
+    let (va, vb) = match (rt.vars.get(a), rt.vars.get(b)) {
+        (Some(va), Some(vb)) => (va.clone(), vb.clone()),
+        (None, _) => bail!("E_VAR_MISSING: '{a}' is not set"),
+        (_, None) => bail!("E_VAR_MISSING: '{b}' is not set"),
+    };
+    for name in [a, b] {
+        if rt.rigid.contains(name) {
+            bail!("E_VAR_EXISTS: '{name}' is rigid");
+        }
+    }
+    check_type(rt, a, &vb)?;
+    check_type(rt, b, &va)?;
+    rt.set_var(a, vb)?;
+    rt.set_var(b, va)?;
+    Ok(rt.last.clone())
+}
+
+// [store(inc)@c] / [store(inc,5)@c] / [store(dec)@c] / [store(dec,2)@c]
+// -> adds or subtracts in place (default 1) and returns the new value; unset vars start at 0
+fn step(rt: &mut Runtime, p: &Packet, opts: &str) -> Result<Value> {
+    let mut parts = opts.split(',').map(str::trim);
+    let sign = if parts.next() == Some("dec") { -1.0 } else { 1.0 };
+    let by = match parts.next() {
+        None => 1.0,
+        Some(n) => match n.parse::<f64>() {
+            Ok(n) if n.is_finite() => n,
+            _ => bail!("E_NOT_NUMERIC: store step '{n}' is not a number"),
+        },
+    };
+    if parts.next().is_some() {
+        bail!("E_BAD_ARG: use [store(inc)@name] or [store(inc, n)@name]");
+    }
+    let name = match p.arg.as_ref() {
+        Some(Arg::Ident(id)) => id.as_str(),
+        _ => bail!("E_BAD_ARG: store(inc|dec) needs @<ident>"),
+    };
+    if rt.rigid.contains(name) {
+        bail!("E_VAR_EXISTS: '{name}' is rigid");
+    }
+    let cur = match rt.vars.get(name) {
+        None | Some(Value::Unit) => 0.0,
+        Some(Value::Num(n)) => *n,
+        Some(other) => bail!("E_NOT_NUMERIC: '{name}' holds {}, not a number", type_name(other)),
+    };
+    let val = Value::Num(cur + sign * by);
+    check_type(rt, name, &val)?;
+    let changed = rt.vars.get(name) != Some(&val);
+    rt.set_var(name, val.clone())?;
+    mark_changed(rt, changed);
     Ok(val)
 }
 
+// [unset@name] -> removes the variable and every [store:context(...)] binding for it; the
+// piped value passes through. Rigid vars refuse; a typed declaration stays in force.
+pub fn unset(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let name = match p.arg.as_ref() {
+        Some(Arg::Ident(id)) => id.as_str(),
+        _ => bail!("E_BAD_ARG: unset needs @<ident>"),
+    };
+    if rt.rigid.contains(name) {
+        bail!("E_VAR_EXISTS: '{name}' is rigid");
+    }
+    let had = rt.vars.remove(name).is_some();
+    rt.ctx_vars.remove(name);
+    mark_changed(rt, had);
+    Ok(rt.last.clone())
+}
+
+// [store(destructure)@doc]{[as(user.name)@fullName][as(user.age)@age]}
+// Missing paths bind Unit; store(destructure, strict) errors instead.
+fn destructure(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let opts = crate::router::extract_paren(&p.op).unwrap_or("");
+    let mut strict = false;
+    let mut seen_mode = false;
+    for opt in opts.split(',').map(str::trim) {
+        match opt {
+            "destructure" => seen_mode = true,
+            "strict" => strict = true,
+            other => bail!("E_MODE_UNKNOWN: unknown store mode '{other}'"),
+        }
+    }
+    if !seen_mode {
+        bail!("E_BAD_ARG: use [store(destructure)@handle]{{[as(path)@name]...}}");
+    }
+    let handle = match p.arg.as_ref() {
+        Some(Arg::Ident(id)) => id.as_str(),
+        _ => bail!("E_BAD_ARG: store(destructure) needs @<handle>"),
+    };
+    let doc = match rt.get_var(handle) {
+        Some(Value::Doc(d)) => d,
+        _ => bail!("E_HANDLE_UNKNOWN: '{handle}' is not a loaded document"),
+    };
+    let body = p
+        .body
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("E_MISSING_BODY: store(destructure) needs body"))?;
+
+    // resolve every binding first so a strict miss leaves no partial assignments
+    let mut bindings = Vec::new();
+    for node in body {
+        let Node::Packet(pkt) = node else {
+            bail!("E_UNSUPPORTED_PACKET: store(destructure) body only takes [as(path)@name]");
+        };
+        let path = match crate::router::extract_paren(&pkt.op) {
+            Some(path) if pkt.op.starts_with("as(") => path.trim(),
+            _ => bail!("E_UNSUPPORTED_PACKET: [{}] is not allowed in a store(destructure) body", pkt.op),
+        };
+        let name = match pkt.arg.as_ref() {
+            Some(Arg::Ident(id)) => id.as_str(),
+            _ => bail!("E_BAD_ARG: as({path}) needs @<ident>"),
+        };
+        let val = match crate::packets::query::read_value(rt, &doc, path)? {
+            Some(v) => v,
+            None if strict => bail!("E_PATH_MISSING: '{path}' not found in '{handle}'"),
+            None => Value::Unit,
+        };
+        check_type(rt, name, &val)?;
+        bindings.push((name, val));
+    }
+    for (name, val) in bindings {
+        rt.set_var(name, val)?;
+    }
+    Ok(Value::Doc(doc))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{kernel::Runtime, kernel::values::Value, router};
+    use crate::{kernel::Runtime, kernel::Span, kernel::values::Value, router};
 
     #[test]
     fn rigid_rejects_overwrite() {
@@ -76,6 +266,105 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn context_bindings_can_be_cleared() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let eval = |rt: &mut Runtime, src: &str| -> Result<Value> {
+            rt.eval(&router::parse(src).map_err(anyhow::Error::new)?)
+        };
+        eval(&mut rt, "[msg@\"dev\"]>[store@phase]>[msg@\"debug\"]>[store:context(phase==\"dev\")@level]")?;
+        assert_eq!(rt.get_var("level"), Some(Value::Str("debug".into())));
+        eval(&mut rt, "[store:context(clear)@level]")?;
+        assert_eq!(rt.get_var("level"), None);
+
+        eval(&mut rt, "[msg@\"info\"]>[store:context(phase==\"dev\")@level]>[store@level]")?;
+        eval(&mut rt, "[unset@level]")?;
+        assert_eq!(rt.get_var("level"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn inc_and_dec_adjust_in_place() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let node = router::parse("[store(inc)@c]>[store(inc)@c]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Num(2.0));
+        let node = router::parse("[store(inc, 5)@c]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Num(7.0));
+        let node = router::parse("[store(dec)@c]>[store(dec,2)@c]").map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("c"), Some(Value::Num(4.0)));
+
+        rt.set_var("s", Value::Str("x".into()))?;
+        let node = router::parse("[store(inc)@s]").map_err(anyhow::Error::new)?;
+        assert!(rt.eval(&node).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn changed_tracks_whether_the_value_moved() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let node = router::parse("[int@5]>[store@x]>[changed]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Bool(true));
+        assert_eq!(rt.eval(&node)?, Value::Bool(false));
+        // [changed] bookkeeping must not show up in dump(vars)/reflect
+        assert!(rt.vars.keys().all(|k| k == "x"), "{:?}", rt.vars.keys());
+        Ok(())
+    }
+
+    #[test]
+    fn swap_trades_values() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("a", Value::Num(1.0))?;
+        rt.set_var("b", Value::Str("two".into()))?;
+        let node = router::parse("[store(swap)@a,b]").map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("a"), Some(Value::Str("two".into())));
+        assert_eq!(rt.get_var("b"), Some(Value::Num(1.0)));
+
+        let node = router::parse("[store(swap)@a,missing]").map_err(anyhow::Error::new)?;
+        let err = rt.eval(&node).unwrap_err();
+        assert!(err.to_string().contains("E_VAR_MISSING"), "unexpected error: {err}");
+        Ok(())
+    }
+
+    #[test]
+    fn typed_declaration_rejects_other_types() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let node = router::parse("[int@1]>[store:num@count]>[int@2]>[store@count]").map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("count"), Some(Value::Num(2.0)));
+
+        let node = router::parse("[msg@\"three\"]>[store@count]").map_err(anyhow::Error::new)?;
+        let err = rt.eval(&node).unwrap_err();
+        assert!(err.to_string().contains("E_TYPE_MISMATCH"), "unexpected error: {err}");
+        assert_eq!(rt.get_var("count"), Some(Value::Num(2.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn other_binders_respect_typed_declarations() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let json = serde_json::json!({"name": "Ana"});
+        let doc = crate::kernel::values::Document::new(
+            json,
+            std::path::PathBuf::from("user.json"),
+            "json".into(),
+            std::time::SystemTime::now(),
+            std::path::PathBuf::new(),
+        );
+        rt.set_var("doc", Value::Doc(doc))?;
+        for script in [
+            "[int@1]>[store:num@count]>[store(destructure)@doc]{[as(name)@count]}",
+            "[with@{count:\"two\"}]{[print@count]}",
+        ] {
+            let node = router::parse(script).map_err(anyhow::Error::new)?;
+            let err = rt.eval(&node).unwrap_err();
+            assert!(err.to_string().contains("E_TYPE_MISMATCH"), "{script}: {err}");
+            assert_eq!(rt.get_var("count"), Some(Value::Num(1.0)));
+        }
+        Ok(())
+    }
+
     #[test]
     fn context_matches_conditions() -> Result<()> {
         let mut rt = Runtime::new()?;
@@ -87,6 +376,7 @@ mod tests {
                 op: "context(x==1)".into(),
                 arg: Some(Arg::Ident("tone".into())),
                 body: None,
+                span: Span::default(),
             },
         )?;
         rt.last = Value::Str("neutral".into());
@@ -97,6 +387,7 @@ mod tests {
                 op: "context(1==1)".into(),
                 arg: Some(Arg::Ident("tone".into())),
                 body: None,
+                span: Span::default(),
             },
         )?;
         rt.set_var("x", Value::Num(1.0))?;
@@ -105,4 +396,30 @@ mod tests {
         assert_eq!(rt.get_var("tone"), Some(Value::Str("neutral".into())));
         Ok(())
     }
+
+    #[test]
+    fn destructure_binds_paths() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let json = serde_json::json!({"user": {"name": "Ana", "age": 31}});
+        let doc = crate::kernel::values::Document::new(
+            json,
+            std::path::PathBuf::from("user.json"),
+            "json".into(),
+            std::time::SystemTime::now(),
+            std::path::PathBuf::new(),
+        );
+        rt.set_var("doc", Value::Doc(doc))?;
+        let script = "[store(destructure)@doc]{[as(user.name)@fullName][as(user.age)@age][as(user.email)@email]}";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("fullName"), Some(Value::Str("Ana".into())));
+        assert_eq!(rt.get_var("age"), Some(Value::Num(31.0)));
+        assert_eq!(rt.get_var("email"), Some(Value::Unit));
+
+        let strict = script.replace("destructure)", "destructure, strict)");
+        let node = router::parse(&strict).map_err(anyhow::Error::new)?;
+        let err = rt.eval(&node).unwrap_err();
+        assert!(err.to_string().contains("E_PATH_MISSING"), "unexpected error: {err}");
+        Ok(())
+    }
 }
@@ -0,0 +1,131 @@
+use crate::kernel::ast::{Arg, Node};
+use crate::kernel::values::Document;
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::{Result, bail};
+
+// [template@"Hello {{user.name}}"]{[ctx@doc]} -> placeholders read paths from the Doc `doc`
+// [template@"Hi {{user.name}}, item {{items[0]}}"] -> without [ctx], the first segment names a variable
+// Paths use the [get] grammar (dot keys, [idx]). A missing path renders empty and warns on stderr.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let src = match p.arg.as_ref() {
+        Some(Arg::Str(s)) => s.clone(),
+        Some(Arg::Ident(id)) => match rt.get_var(id) {
+            Some(Value::Str(s)) => s,
+            _ => bail!("E_TYPE_MISMATCH: template@{id} needs a string"),
+        },
+        _ => bail!("E_BAD_ARG: template needs @\"text with {{{{path}}}}\""),
+    };
+    let ctx = match &p.body {
+        Some(body) => Some(context(rt, body)?),
+        None => None,
+    };
+    let (out, missing) = render(rt, &src, ctx.as_ref())?;
+    for path in missing {
+        eprintln!("[template] warning: nothing at {{{{{path}}}}}, rendered empty");
+    }
+    Ok(Value::Str(out))
+}
+
+fn context(rt: &Runtime, body: &[Node]) -> Result<Document> {
+    let pkt = match body.first() {
+        Some(Node::Packet(pkt)) if pkt.ns.is_none() && pkt.op == "ctx" => pkt,
+        Some(_) => bail!("E_UNSUPPORTED_PACKET: template body only takes [ctx@doc]"),
+        None => bail!("E_BAD_ARG: template body needs [ctx@doc]"),
+    };
+    match pkt.arg.as_ref() {
+        Some(Arg::Ident(id)) => match rt.get_var(id) {
+            Some(Value::Doc(d)) => Ok(d),
+            _ => bail!("E_HANDLE_UNKNOWN: '{id}' is not a loaded document"),
+        },
+        _ => bail!("E_BAD_ARG: ctx needs @<doc>"),
+    }
+}
+
+// Returns the rendered text and the placeholder paths that resolved to nothing.
+fn render(rt: &Runtime, src: &str, ctx: Option<&Document>) -> Result<(String, Vec<String>)> {
+    let mut out = String::with_capacity(src.len());
+    let mut missing = Vec::new();
+    let mut rest = src;
+    while let Some(open) = rest.find("{{") {
+        let Some(close) = rest[open + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        let path = rest[open + 2..open + 2 + close].trim();
+        match lookup(rt, ctx, path)? {
+            Some(v) => out.push_str(&text(&v)),
+            None => missing.push(path.to_string()),
+        }
+        rest = &rest[open + 2 + close + 2..];
+    }
+    out.push_str(rest);
+    Ok((out, missing))
+}
+
+fn lookup(rt: &Runtime, ctx: Option<&Document>, path: &str) -> Result<Option<Value>> {
+    if let Some(doc) = ctx {
+        return crate::packets::query::read_value(rt, doc, path);
+    }
+    let split = path.find(['.', '[']).unwrap_or(path.len());
+    let (name, rest) = path.split_at(split);
+    let rest = rest.strip_prefix('.').unwrap_or(rest);
+    match rt.get_var(name) {
+        Some(Value::Doc(d)) if !rest.is_empty() => crate::packets::query::read_value(rt, &d, rest),
+        Some(_) if !rest.is_empty() => Ok(None),
+        other => Ok(other),
+    }
+}
+
+// Containers render as compact JSON; null renders empty.
+fn text(v: &Value) -> String {
+    match v {
+        Value::Doc(d) => serde_json::to_string(&d.json).unwrap_or_default(),
+        Value::Unit => String::new(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    fn runtime_with_doc() -> Result<Runtime> {
+        let mut rt = Runtime::new()?;
+        let doc = Document::new(
+            serde_json::json!({"user": {"name": "Ana", "langs": ["rust", "go"]}}),
+            PathBuf::from("ctx.json"),
+            "json".into(),
+            SystemTime::now(),
+            PathBuf::new(),
+        );
+        rt.set_var("data", Value::Doc(doc))?;
+        Ok(rt)
+    }
+
+    #[test]
+    fn renders_nested_paths_from_ctx() -> Result<()> {
+        let mut rt = runtime_with_doc()?;
+        let node = router::parse("[template@\"Hello {{user.name}}, you like {{ user.langs[1] }}\"]{[ctx@data]}")
+            .map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Str("Hello Ana, you like go".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn missing_keys_render_empty() -> Result<()> {
+        let rt = runtime_with_doc()?;
+        let doc = match rt.get_var("data") {
+            Some(Value::Doc(d)) => d,
+            other => panic!("doc not bound: {other:?}"),
+        };
+        let (out, missing) = render(&rt, "[{{user.email}}] {{data.user.name}}", Some(&doc))?;
+        assert_eq!(out, "[] ");
+        assert_eq!(missing, vec!["user.email", "data.user.name"]);
+        let (out, missing) = render(&rt, "{{data.user.name}}", None)?;
+        assert_eq!((out.as_str(), missing.len()), ("Ana", 0));
+        Ok(())
+    }
+}
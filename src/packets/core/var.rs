@@ -3,12 +3,67 @@ use crate::kernel::ast::Arg;
 use anyhow::{Result, bail};
 
 // [var@name] -> returns the current value of runtime variable `name` (or Unit if missing)
+// [var@a||b||"default"] -> first variable that is set, else the quoted literal
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let name = match p.arg.as_ref() {
         Some(Arg::Ident(id)) => id.as_str(),
         Some(Arg::Str(s)) => s.as_str(),
-        _ => bail!("var needs @<ident|\"name\">")
+        _ => bail!("E_BAD_ARG: var needs @<ident|\"name\">")
     };
+    if name.contains("||") {
+        return fallback(rt, name);
+    }
     Ok(rt.get_var(name).unwrap_or(Value::Unit))
 }
 
+fn fallback(rt: &Runtime, chain: &str) -> Result<Value> {
+    for part in chain.split("||") {
+        let part = part.trim();
+        if part.len() >= 2 && part.starts_with('"') && part.ends_with('"') {
+            let s: String = serde_json::from_str(part)?;
+            return Ok(Value::Str(s));
+        }
+        if let Some(v) = rt.get_var(part) {
+            return Ok(v);
+        }
+    }
+    Ok(Value::Unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn fallback_prefers_first_set() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("a", Value::Str("first".into()))?;
+        rt.set_var("b", Value::Str("second".into()))?;
+        let out = eval(&mut rt, "[var@a||b||\"default\"]")?;
+        assert_eq!(out, Value::Str("first".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn fallback_uses_literal_default() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let out = eval(&mut rt, "[var@a||b||\"default\"]")?;
+        assert_eq!(out, Value::Str("default".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn fallback_skips_to_middle() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("b", Value::Num(2.0))?;
+        let out = eval(&mut rt, "[var@a||b||\"default\"]")?;
+        assert_eq!(out, Value::Num(2.0));
+        Ok(())
+    }
+}
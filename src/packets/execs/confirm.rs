@@ -1,17 +1,22 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use std::collections::HashSet;
-use std::io::{self, Write};
+use std::io::{self, BufRead, Write};
 use std::sync::{Mutex, OnceLock};
 
 use crate::kernel::{Packet, Runtime, Value};
 
 static ALLOW: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
 
-fn is_allowed(key: &str) -> bool {
-    if std::env::var(key)
+fn env_flag(key: &str) -> bool {
+    std::env::var(key)
         .map(|v| matches!(v.as_str(), "1" | "true" | "yes" | "y"))
         .unwrap_or(false)
-    {
+}
+
+// TAGSPEAK_ASSUME_YES answers plain [confirm] prompts (and typed ones, see typed_gate);
+// exec/run consent still needs its own TAGSPEAK_ALLOW_* key or an answer
+fn is_allowed(key: &str) -> bool {
+    if env_flag(key) || (key == "TAGSPEAK_ALLOW_YELLOW" && env_flag("TAGSPEAK_ASSUME_YES")) {
         return true;
     }
     let set = ALLOW.get_or_init(|| Mutex::new(HashSet::new()));
@@ -55,6 +60,54 @@ pub fn prompt_yes_no(msg: &str, env_allow_key: &str) -> Result<bool> {
     Ok(ans == "y" || ans == "yes")
 }
 
+// The answer must be `word` exactly (case-sensitive); "always" doesn't apply here.
+fn typed_confirm(msg: &str, word: &str, input: &mut impl BufRead, out: &mut impl Write) -> Result<bool> {
+    writeln!(out, "[confirm] {msg}")?;
+    write!(out, "Type {word} to proceed: ")?;
+    out.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line).is_err() {
+        return Ok(false);
+    }
+    Ok(line.trim_end_matches(['\r', '\n']) == word)
+}
+
+// Only TAGSPEAK_ASSUME_YES skips a typed confirm: an earlier "always" answer or
+// TAGSPEAK_ALLOW_YELLOW must not pass it on the user's behalf.
+fn typed_gate(msg: &str, word: &str, input: &mut impl BufRead, out: &mut impl Write) -> Result<bool> {
+    if env_flag("TAGSPEAK_ASSUME_YES") {
+        return Ok(true);
+    }
+    if env_flag("TAGSPEAK_NONINTERACTIVE") {
+        return Ok(false);
+    }
+    typed_confirm(msg, word, input, out)
+}
+
+// [confirm(type:"DELETE")@"This will wipe data"]{...} -> runs the body only if the user
+// types DELETE; without a body the packet yields true/false. A mismatch yields false.
+fn handle_typed(rt: &mut Runtime, p: &Packet, message: &str, opts: &str) -> Result<Value> {
+    let word = match opts.trim().strip_prefix("type:") {
+        Some(w) => w.trim().trim_matches('"'),
+        None => bail!("E_MODE_UNKNOWN: confirm mode unsupported: {opts}"),
+    };
+    if word.is_empty() {
+        bail!("E_BAD_ARG: confirm(type:...) needs a word to type");
+    }
+    let ok = typed_gate(message, word, &mut io::stdin().lock(), &mut io::stdout())?;
+    match (&p.body, ok) {
+        (_, false) => Ok(Value::Bool(false)),
+        (None, true) => Ok(Value::Bool(true)),
+        (Some(body), true) => {
+            let cur = rt.get_num("__yellow_depth").unwrap_or(0.0);
+            rt.set_num("__yellow_depth", cur + 1.0)?;
+            let out = rt.eval(&crate::kernel::Node::Block(body.clone()))?;
+            rt.set_num("__yellow_depth", cur)?;
+            Ok(out)
+        }
+    }
+}
+
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let message = p
         .arg
@@ -65,6 +118,9 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
             _ => None,
         })
         .unwrap_or_else(|| "Are you sure you want to continue?".to_string());
+    if let Some(opts) = crate::router::extract_paren(&p.op) {
+        return handle_typed(rt, p, &message, opts);
+    }
 
     let body = p
         .body
@@ -95,7 +151,8 @@ pub fn handle_exec(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     } else {
         format!("Execute external command?\n  cmd: {}", cmd)
     };
-    if !prompt_yes_no(&msg, "TAGSPEAK_ALLOW_EXEC")? {
+    // a dry run never spawns the command, so there is nothing to consent to
+    if !rt.dry_run && !prompt_yes_no(&msg, "TAGSPEAK_ALLOW_EXEC")? {
         return Ok(Value::Unit);
     }
     let cur = rt.get_num("__yellow_depth").unwrap_or(0.0);
@@ -122,3 +179,30 @@ pub fn handle_run(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     rt.set_num("__yellow_depth", cur)?;
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_confirm_needs_the_exact_word() -> Result<()> {
+        let mut out = Vec::new();
+        assert!(typed_confirm("wipe?", "DELETE", &mut "DELETE\n".as_bytes(), &mut out)?);
+        assert!(String::from_utf8_lossy(&out).contains("Type DELETE to proceed"));
+        for wrong in ["delete\n", "DELETE \n", "y\n", ""] {
+            assert!(!typed_confirm("wipe?", "DELETE", &mut wrong.as_bytes(), &mut Vec::new())?, "{wrong:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn always_answer_does_not_skip_typed_confirm() -> Result<()> {
+        mark_allowed("TAGSPEAK_ALLOW_YELLOW");
+        assert!(is_allowed("TAGSPEAK_ALLOW_YELLOW"));
+        let mut out = Vec::new();
+        assert!(!typed_gate("wipe?", "DELETE", &mut "y\n".as_bytes(), &mut out)?);
+        assert!(String::from_utf8_lossy(&out).contains("Type DELETE to proceed"));
+        assert!(typed_gate("wipe?", "DELETE", &mut "DELETE\n".as_bytes(), &mut Vec::new())?);
+        Ok(())
+    }
+}
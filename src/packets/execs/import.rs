@@ -0,0 +1,80 @@
+use anyhow::{Result, bail};
+use std::fs;
+use std::path::Path;
+
+use crate::kernel::ast::{Arg, Node};
+use crate::kernel::fs_guard::resolve;
+use crate::kernel::{Packet, Runtime, Value};
+
+// [import@/lib.tgsk] -> register the file's top-level [funct:name]{...} definitions and
+// run nothing else; returns how many were registered. Paths resolve like [run].
+// Replacing a funct that already exists warns on stderr; the imported body wins.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let raw = match &p.arg {
+        Some(Arg::Str(s)) => s.trim(),
+        _ => bail!("E_BAD_ARG: import needs @<path.tgsk>"),
+    };
+    let root = rt
+        .effective_root
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: [import] needs a red.tgsk root"))?;
+    let candidate = match raw.strip_prefix('/') {
+        Some(rel) => Path::new(rel).to_path_buf(),
+        None => rt.cwd.join(raw),
+    };
+    let path = resolve(root, &candidate)?;
+    if path.extension().and_then(|e| e.to_str()).unwrap_or("") != "tgsk" {
+        bail!("E_BAD_ARG: import expects a .tgsk file");
+    }
+    let src = fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("E_PATH_MISSING: import {raw}: {e}"))?;
+    let ast = crate::router::parse(&src).map_err(anyhow::Error::new)?;
+
+    let top: &[Node] = match &ast {
+        Node::Chain(nodes) => nodes,
+        other => std::slice::from_ref(other),
+    };
+    let mut count = 0;
+    for node in top {
+        if let Node::Packet(def) = node
+            && def.ns.as_deref() == Some("funct")
+            && let Some(body) = &def.body
+        {
+            let tag = def.op.split('(').next().unwrap_or("").trim();
+            if rt.tags.contains_key(tag) {
+                eprintln!("[import] warning: funct '{tag}' from {raw} replaces an existing definition");
+            }
+            crate::packets::funct::define(rt, &def.op, body)?;
+            count += 1;
+        }
+    }
+    Ok(Value::Num(count as f64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_functs_without_running_the_library() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_import_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("lib"))?;
+        fs::write(base.join("red.tgsk"), "")?;
+        fs::write(
+            base.join("lib").join("util.tgsk"),
+            "[msg@\"side effect\"]>[store@ran]\n[funct:double]{[math@n*2]>[store@n]}\n",
+        )?;
+
+        let script = base.join("main.tgsk");
+        let node = crate::router::parse("[import@/lib/util.tgsk]>[int@21]>[store@n]>[call@double]>[var@n]")
+            .map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::from_entry(&script)?;
+        let out = rt.eval(&node)?;
+        assert_eq!(out, Value::Num(42.0));
+        assert_eq!(rt.get_var("ran"), None);
+
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+}
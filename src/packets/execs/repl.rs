@@ -1,8 +1,94 @@
 use anyhow::{Result, bail};
 use std::io::{self, Write};
+use std::path::Path;
 use std::sync::{Mutex, OnceLock};
 
-use crate::kernel::{Packet, Runtime, Value};
+use crate::kernel::fs_guard::resolve;
+use crate::kernel::{Node, Packet, Runtime, Value};
+
+// history lives at the red root and keeps the most recent entries
+const HISTORY_FILE: &str = ".tagspeak_history";
+const HISTORY_MAX: usize = 500;
+
+fn load_history(root: &Path) -> Result<Vec<String>> {
+    let path = resolve(root, Path::new(HISTORY_FILE))?;
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(text.lines().filter(|l| !l.trim().is_empty()).map(str::to_string).collect())
+}
+
+fn save_history(root: &Path, history: &[String]) -> Result<()> {
+    let path = resolve(root, Path::new(HISTORY_FILE))?;
+    let keep = &history[history.len().saturating_sub(HISTORY_MAX)..];
+    let mut text = keep.join("\n");
+    text.push('\n');
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+// One REPL session: the runtime, the optional per-line body and the input history.
+// The stdin loop in `handle` only does I/O, so embedders can drive the same session
+// a line at a time.
+pub struct Repl<'a> {
+    rt: &'a mut Runtime,
+    body: Option<Vec<Node>>,
+    history: Vec<String>,
+}
+
+impl<'a> Repl<'a> {
+    // picks up the saved history when there is a red root
+    pub fn new(rt: &'a mut Runtime, body: Option<Vec<Node>>) -> Result<Self> {
+        let history = match &rt.effective_root {
+            Some(root) => load_history(root)?,
+            None => Vec::new(),
+        };
+        Ok(Self { rt, body, history })
+    }
+
+    // one input line -> the text to print. `:vars` / `:history` inspect the session;
+    // anything else is bound to `q` and runs the body, or is echoed when there is none
+    pub fn line(&mut self, q: &str) -> Result<String> {
+        self.history.push(q.to_string());
+        match q {
+            ":vars" => return Ok(render_vars(self.rt)),
+            ":history" => return Ok(self.history.join("\n")),
+            _ => {}
+        }
+        self.rt.set_var("q", Value::Str(q.to_string()))?;
+        let out = match &self.body {
+            Some(b) => self.rt.eval(&Node::Block(b.clone()))?,
+            None => Value::Str(q.to_string()),
+        };
+        let text = match &out {
+            Value::Unit => "(ok)".to_string(),
+            other => other.to_string(),
+        };
+        self.rt.last = out;
+        Ok(text)
+    }
+
+    // writes the history back to the red root
+    pub fn finish(self) -> Result<()> {
+        match &self.rt.effective_root {
+            Some(root) => save_history(root, &self.history),
+            None => Ok(()),
+        }
+    }
+}
+
+// `:vars` -> one `name = value` line per user variable, sorted by name
+fn render_vars(rt: &Runtime) -> String {
+    let mut names: Vec<&String> = rt.vars.keys().filter(|k| !k.starts_with("__")).collect();
+    names.sort();
+    names
+        .into_iter()
+        .map(|k| format!("{k} = {}", rt.vars[k]))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
 fn parse_model(op: &str) -> Option<String> {
     if let Some(rest) = op.strip_prefix("repl(")
@@ -54,10 +140,11 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let model = parse_model(&p.op).unwrap_or_else(|| "repl".to_string());
     let prompt_symbol = format!("{}> ", model);
 
+    let mut repl = Repl::new(rt, p.body.clone())?;
     let mut stdout = io::stdout();
     writeln!(
         stdout,
-        "[repl] starting (model: {}) — type 'exit' to quit",
+        "[repl] starting (model: {}) — type 'exit' to quit, ':vars' or ':history' to inspect",
         model
     )?;
     stdout.flush()?;
@@ -77,41 +164,72 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         if q.is_empty() {
             continue;
         }
-
-        // Expose input as variable 'q'
-        rt.set_var("q", Value::Str(q.clone()))?;
-
-        // Evaluate body if present, else just echo
-        let out = if let Some(b) = &p.body {
-            rt.eval(&crate::kernel::Node::Block(b.clone()))?
-        } else {
-            Value::Str(q)
-        };
-
-        // Print the output neatly; also set as last
-        match &out {
-            Value::Unit => {
-                writeln!(stdout, "(ok)")?;
-            }
-            Value::Str(s) => {
-                writeln!(stdout, "{}", s)?;
-            }
-            Value::Num(n) => {
-                writeln!(stdout, "{}", n)?;
-            }
-            Value::Bool(b) => {
-                writeln!(stdout, "{}", b)?;
-            }
-            Value::Doc(_) => {
-                writeln!(stdout, "<doc>")?;
-            }
-        }
+        let text = repl.line(&q)?;
+        writeln!(stdout, "{}", text)?;
         stdout.flush()?;
-        rt.last = out;
     }
     // release flag when exiting
     if let Ok(mut flag) = ACTIVE.get().unwrap().lock() {
         *flag = false;
     }
+    repl.finish()?;
     Ok(Value::Unit)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn history_round_trips_and_is_capped() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_repl_hist_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+
+        assert!(load_history(&base)?.is_empty());
+        let lines: Vec<String> = (0..HISTORY_MAX + 2).map(|i| format!("[int@{i}]")).collect();
+        save_history(&base, &lines)?;
+        let loaded = load_history(&base)?;
+        assert_eq!(loaded.len(), HISTORY_MAX);
+        assert_eq!(loaded.first().map(String::as_str), Some("[int@2]"));
+        assert_eq!(loaded.last(), lines.last());
+
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
+    #[test]
+    fn session_runs_the_body_and_saves_its_history() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_repl_session_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        fs::write(base.join(HISTORY_FILE), "[int@1]\n")?;
+
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk"))?;
+        let body = vec![crate::router::parse("[msg@\"> \" + q]>[store@seen]").map_err(anyhow::Error::new)?];
+        let mut repl = Repl::new(&mut rt, Some(body))?;
+        assert_eq!(repl.line("hi")?, "> hi");
+        assert_eq!(repl.line(":history")?, "[int@1]\nhi\n:history");
+        repl.finish()?;
+
+        assert_eq!(rt.get_var("seen"), Some(Value::Str("> hi".into())));
+        assert_eq!(load_history(&base)?, vec!["[int@1]", "hi", ":history"]);
+        let mut echo = Repl::new(&mut rt, None)?;
+        assert_eq!(echo.line("[int@5]")?, "[int@5]");
+
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
+    #[test]
+    fn vars_listing_hides_internal_names() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("b", Value::Num(2.0))?;
+        rt.set_var("a", Value::Str("x".into()))?;
+        rt.set_var("__red_enabled", Value::Bool(true))?;
+        assert_eq!(render_vars(&rt), "a = x\nb = 2");
+        Ok(())
+    }
+}
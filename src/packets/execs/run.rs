@@ -81,10 +81,18 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
 
     // increment depth, eval, then restore
     rt.set_num("__run_depth", (cur_depth as f64) + 1.0)?;
-    let out = rt.eval(&ast)?;
+    // the script's own helpers may be called above their definitions, like a top-level run
+    rt.hoist_functs(&ast).map_err(|e| crate::kernel::runtime::attach_source(e, &src))?;
+    let mark = rt.deferred.len();
+    let out = rt.eval(&ast);
+    // the script's [defer] blocks run when it ends (still inside its cwd), like a funct's
+    let out = rt.run_deferred(mark, out);
+    // an [interrupt] inside the script ends only that script
+    let out = crate::kernel::runtime::catch_interrupt(out)
+        .map_err(|e| crate::kernel::runtime::attach_source(e, &src));
     rt.set_num("__run_depth", cur_depth as f64)?;
 
-    // Restore cwd
+    // Restore cwd, whether or not the script succeeded
     rt.cwd = prev_cwd;
-    Ok(out)
+    out
 }
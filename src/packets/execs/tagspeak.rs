@@ -1,9 +1,10 @@
 use anyhow::{Result, bail};
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::kernel::fs_guard::resolve;
-use crate::kernel::{Arg, Packet, Runtime, Value};
+use crate::kernel::{Arg, Node, Packet, Runtime, Span, Value};
 
 enum Subcommand {
     Run,
@@ -74,6 +75,7 @@ fn run(rt: &mut Runtime, raw_path: String) -> Result<Value> {
         op: "run".to_string(),
         arg: Some(Arg::Str(raw_path)),
         body: None,
+        span: Span::default(),
     };
     super::run::handle(rt, &pkt)
 }
@@ -100,11 +102,86 @@ fn build(rt: &Runtime, raw_path: String) -> Result<Value> {
 
     let src = fs::read_to_string(&resolved)?;
     crate::router::parse(&src).map_err(anyhow::Error::new)?;
+    if let Some(root) = rt.effective_root.as_ref() {
+        check_includes(root, &resolved)?;
+    }
 
     let rel = root_relative(rt, &resolved);
     Ok(Value::Str(format!("build_ok {}", rel)))
 }
 
+// Follows literal [run@...] includes (and the [tagspeak:run@...] / [tagspeak run@...]
+// spellings) depth-first; meeting a file that is already on the stack is a cycle, reported
+// with the whole chain (/a.tgsk -> /b.tgsk -> /a.tgsk). A cycle that passes through a [run]
+// under an [if]/[or]/[else] branch may be guarded recursion, so it is only a warning.
+pub fn check_includes(root: &Path, entry: &Path) -> Result<()> {
+    let root = fs::canonicalize(root)?;
+    let entry = fs::canonicalize(entry)?;
+    walk_includes(&root, &entry, false, &mut Vec::new(), &mut HashSet::new())
+}
+
+// `stack` holds each open file and whether the [run] that reached it was conditional
+fn walk_includes(
+    root: &Path,
+    file: &Path,
+    guarded: bool,
+    stack: &mut Vec<(PathBuf, bool)>,
+    done: &mut HashSet<PathBuf>,
+) -> Result<()> {
+    let show = |f: &Path| format!("/{}", f.strip_prefix(root).unwrap_or(f).to_string_lossy().replace('\\', "/"));
+    if let Some(start) = stack.iter().position(|(f, _)| f == file) {
+        let chain: Vec<String> = stack[start..].iter().map(|(f, _)| show(f)).chain([show(file)]).collect();
+        if guarded || stack[start + 1..].iter().any(|(_, g)| *g) {
+            eprintln!("[build] warning: conditional include cycle {}", chain.join(" -> "));
+            return Ok(());
+        }
+        bail!("E_RUN_CYCLE: include cycle {}", chain.join(" -> "));
+    }
+    if !done.insert(file.to_path_buf()) {
+        return Ok(());
+    }
+    let src = fs::read_to_string(file)?;
+    let ast = crate::router::parse(&src).map_err(|e| anyhow::anyhow!("{}: {e}", show(file)))?;
+    let dir = file.parent().unwrap_or(root);
+    let mut targets = Vec::new();
+    collect_runs(&ast, false, &mut targets);
+
+    stack.push((file.to_path_buf(), guarded));
+    for (raw, cond) in targets {
+        let candidate = match raw.strip_prefix('/') {
+            Some(rel) => PathBuf::from(rel),
+            None => dir.strip_prefix(root).unwrap_or(Path::new("")).join(&raw),
+        };
+        // missing targets are left to [run] itself at runtime
+        if let Ok(target) = fs::canonicalize(resolve(root, &candidate)?) {
+            walk_includes(root, &target, cond, stack, done)?;
+        }
+    }
+    stack.pop();
+    Ok(())
+}
+
+// (target, reached through a conditional branch)
+fn collect_runs(node: &Node, cond: bool, out: &mut Vec<(String, bool)>) {
+    match node {
+        Node::Chain(nodes) | Node::Block(nodes) => nodes.iter().for_each(|n| collect_runs(n, cond, out)),
+        Node::If { then_b, else_b, .. } => then_b.iter().chain(else_b).for_each(|n| collect_runs(n, true, out)),
+        Node::Packet(p) => {
+            let is_run = match p.ns.as_deref() {
+                None => p.op == "run" || p.op.strip_prefix("tagspeak").map(str::trim) == Some("run"),
+                Some("yellow" | "tagspeak") => p.op == "run",
+                Some(_) => false,
+            };
+            if is_run && let Some(Arg::Str(raw)) = &p.arg {
+                out.push((raw.clone(), cond));
+            }
+            if let Some(body) = &p.body {
+                body.iter().for_each(|n| collect_runs(n, cond, out));
+            }
+        }
+    }
+}
+
 fn resolve_within_root(rt: &Runtime, raw: &str) -> Result<PathBuf> {
     let root = rt
         .effective_root
@@ -1,7 +1,11 @@
 use anyhow::{Result, bail};
+use std::fmt;
 use std::fs;
+use std::io::BufReader;
 use std::path::Path;
+use std::time::UNIX_EPOCH;
 
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
 use serde_yaml::Value as YamlValue;
 use toml::Value as TomlValue;
 
@@ -10,16 +14,27 @@ use crate::kernel::fs_guard::resolve;
 use crate::kernel::values::{Document, Value};
 use crate::kernel::{Packet, Runtime};
 
+// [load@/path] -> parse by extension; unknown extensions sniff JSON, then YAML, then TOML
+// [load(format:yaml)@/path] -> force a parser regardless of extension
+// [load(meta)@/path] -> doc {path, size, modified_epoch, ext} without reading the file
+// [load(pointer:/users/0/name)@/path] -> only the value at that JSON Pointer (RFC 6901)
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let meta_only = p.op.replace(' ', "") == "load(meta)";
+    let pointer = parse_pointer_option(&p.op)?;
+    let forced = if meta_only || pointer.is_some() {
+        None
+    } else {
+        parse_format_override(&p.op)?
+    };
     let raw = match &p.arg {
         Some(Arg::Str(s)) => s,
-        _ => anyhow::bail!("load needs @<path>"),
+        _ => anyhow::bail!("E_BAD_ARG: load needs @<path>"),
     };
 
     let root = rt
         .effective_root
         .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("no red.tgsk root"))?;
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
 
     let rel_path = if raw.starts_with('/') {
         &raw[1..]
@@ -33,27 +48,34 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     };
 
     let path = resolve(root, &candidate)?;
-    let content = fs::read_to_string(&path)?;
-    let ext = path
+    if meta_only {
+        return stat(rt, root, &path);
+    }
+    if let Some(tokens) = pointer {
+        return load_pointer(rt, root, &path, &tokens);
+    }
+    let mut ext = path
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
         .to_lowercase();
+    let format = forced.clone().unwrap_or_else(|| ext.clone());
 
     // Parse into a canonical JSON value for in-memory editing
-    let json_val: serde_json::Value = match ext.as_str() {
-        "yaml" | "yml" => {
-            let yv: YamlValue = serde_yaml::from_str(&content)?;
-            // Convert via serde to JSON value
-            serde_json::to_value(yv)?
-        }
-        "toml" => {
-            let tv: TomlValue = toml::from_str(&content)?;
-            serde_json::to_value(tv)?
-        }
-        "json" | "" => serde_json::from_str(&content).unwrap_or(serde_json::Value::Null),
-        other => bail!("unsupported_ext:{other}"),
+    let json_val: serde_json::Value = match format.as_str() {
+        "json" => read_json(rt, &path)?,
+        "yaml" | "yml" => parse_yaml(&fs::read_to_string(&path)?)?,
+        "toml" => parse_toml(&fs::read_to_string(&path)?)?,
+        _ => {
+            let (fmt, json) = sniff(&fs::read_to_string(&path)?)?;
+            ext = fmt.to_string();
+            json
+        }
     };
+    if let Some(fmt) = forced {
+        // [save] re-encodes by ext, so keep the forced format
+        ext = fmt;
+    }
 
     let meta = fs::metadata(&path)?;
     let mtime = meta.modified()?;
@@ -61,11 +83,295 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     Ok(Value::Doc(doc))
 }
 
+// JSON above `rt.stream_threshold` bytes is parsed from a buffered reader so the raw
+// text never sits in memory next to the parsed tree. Either way bad JSON is E_PARSE.
+pub fn read_json(rt: &Runtime, path: &Path) -> Result<serde_json::Value> {
+    let file = fs::File::open(path)?;
+    let parsed = if file.metadata()?.len() > rt.stream_threshold {
+        serde_json::from_reader(BufReader::new(file))
+    } else {
+        serde_json::from_str(&fs::read_to_string(path)?)
+    };
+    parsed.map_err(|e| anyhow::anyhow!("E_PARSE: invalid JSON in {}: {e}", path.display()))
+}
+
+// JSON files are walked with a seed that skips every sibling of the pointer path, so only
+// the pointed value is ever built; other formats are parsed whole and then indexed.
+fn load_pointer(rt: &Runtime, root: &Path, path: &Path, tokens: &[String]) -> Result<Value> {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    let found = match ext.as_str() {
+        "yaml" | "yml" | "toml" => {
+            let content = fs::read_to_string(path)?;
+            let json = if ext == "toml" { parse_toml(&content)? } else { parse_yaml(&content)? };
+            tokens
+                .iter()
+                .try_fold(&json, |cur, tok| step(cur, tok))
+                .cloned()
+        }
+        _ => {
+            let mut de = serde_json::Deserializer::from_reader(BufReader::new(fs::File::open(path)?));
+            let found = PointerSeed { tokens }.deserialize(&mut de)?;
+            de.end()?;
+            found
+        }
+    };
+    let json = match found {
+        Some(json) => json,
+        None => bail!("E_PATH_MISSING: pointer /{} not found", tokens.join("/")),
+    };
+    Ok(match json {
+        serde_json::Value::Null => Value::Unit,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        serde_json::Value::Number(n) => n.as_f64().map(Value::Num).unwrap_or(Value::Unit),
+        serde_json::Value::String(s) => Value::Str(s),
+        json => Value::Doc(Document::scratch(root.to_path_buf(), &rt.cwd, "_pointer.json", json)),
+    })
+}
+
+fn step<'a>(cur: &'a serde_json::Value, tok: &str) -> Option<&'a serde_json::Value> {
+    match cur {
+        serde_json::Value::Object(map) => map.get(tok),
+        serde_json::Value::Array(items) => array_index(tok).and_then(|i| items.get(i)),
+        _ => None,
+    }
+}
+
+// RFC 6901 array indexes: digits only, no leading zeros
+fn array_index(tok: &str) -> Option<usize> {
+    if tok.is_empty() || !tok.bytes().all(|b| b.is_ascii_digit()) || (tok.len() > 1 && tok.starts_with('0')) {
+        return None;
+    }
+    tok.parse().ok()
+}
+
+// Walks one pointer token per nesting level; Ok(None) when the path is missing.
+struct PointerSeed<'a> {
+    tokens: &'a [String],
+}
+
+impl<'de> DeserializeSeed<'de> for PointerSeed<'_> {
+    type Value = Option<serde_json::Value>;
+
+    fn deserialize<D: de::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        if self.tokens.is_empty() {
+            return serde::Deserialize::deserialize(d).map(Some);
+        }
+        d.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for PointerSeed<'_> {
+    type Value = Option<serde_json::Value>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a JSON value")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut found = None;
+        while let Some(key) = map.next_key::<String>()? {
+            if found.is_none() && key == self.tokens[0] {
+                found = map.next_value_seed(PointerSeed { tokens: &self.tokens[1..] })?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(found)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let want = array_index(&self.tokens[0]);
+        let mut found = None;
+        let mut idx = 0usize;
+        loop {
+            if Some(idx) == want {
+                match seq.next_element_seed(PointerSeed { tokens: &self.tokens[1..] })? {
+                    Some(v) => found = v,
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            idx += 1;
+        }
+        Ok(found)
+    }
+
+    // scalars have no children, so any remaining token misses
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+    fn visit_str<E: de::Error>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+}
+
+// load(pointer:/a/b) -> Some(["a", "b"]); `~1` and `~0` decode to `/` and `~`
+fn parse_pointer_option(op: &str) -> Result<Option<Vec<String>>> {
+    let Some(inner) = op.strip_prefix("load(") else {
+        return Ok(None);
+    };
+    let inner = inner.trim_end_matches(')').trim();
+    let Some(ptr) = inner.strip_prefix("pointer:") else {
+        return Ok(None);
+    };
+    parse_pointer(ptr.trim()).map(Some)
+}
+
+// "/a/b" -> ["a", "b"], "" -> [] (the whole document)
+pub fn parse_pointer(ptr: &str) -> Result<Vec<String>> {
+    if ptr.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = ptr.strip_prefix('/') else {
+        bail!("E_BAD_ARG: JSON pointer '{ptr}' must start with '/'");
+    };
+    rest.split('/')
+        .map(|tok| {
+            let mut out = String::new();
+            let mut chars = tok.chars();
+            while let Some(c) = chars.next() {
+                if c != '~' {
+                    out.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('0') => out.push('~'),
+                    Some('1') => out.push('/'),
+                    _ => bail!("E_BAD_ARG: JSON pointer '{ptr}' has a bad '~' escape"),
+                }
+            }
+            Ok(out)
+        })
+        .collect()
+}
+
+fn stat(rt: &Runtime, root: &Path, path: &Path) -> Result<Value> {
+    let meta = fs::metadata(path)?;
+    let modified = meta.modified()?;
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    let json = serde_json::json!({
+        "path": format!("/{}", rel.to_string_lossy().replace('\\', "/")),
+        "size": meta.len(),
+        "modified_epoch": modified.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        "ext": path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase(),
+    });
+    Ok(Value::Doc(Document::scratch(root.to_path_buf(), &rt.cwd, "_meta.json", json)))
+}
+
+fn parse_yaml(content: &str) -> Result<serde_json::Value> {
+    let mut yv: YamlValue = serde_yaml::from_str(content)?;
+    // aliases resolve on parse, but `<<:` merge keys stay literal until applied
+    yv.apply_merge()?;
+    // Convert via serde to JSON value
+    Ok(serde_json::to_value(yv)?)
+}
+
+fn parse_toml(content: &str) -> Result<serde_json::Value> {
+    let tv: TomlValue = toml::from_str(content)?;
+    Ok(serde_json::to_value(tv)?)
+}
+
+// YAML accepts nearly any text as a bare scalar, so only a mapping/sequence counts as a hit
+fn sniff(content: &str) -> Result<(&'static str, serde_json::Value)> {
+    if let Ok(json) = serde_json::from_str(content) {
+        return Ok(("json", json));
+    }
+    if let Ok(json) = parse_yaml(content)
+        && (json.is_object() || json.is_array())
+    {
+        return Ok(("yaml", json));
+    }
+    if let Ok(json) = parse_toml(content) {
+        return Ok(("toml", json));
+    }
+    bail!("E_FORMAT_UNKNOWN: content is not JSON, YAML, or TOML; use [load(format:json|yaml|toml)@path]")
+}
+
+fn parse_format_override(op: &str) -> Result<Option<String>> {
+    let Some(inner) = op.strip_prefix("load(") else {
+        return Ok(None);
+    };
+    let inner = inner.trim_end_matches(')').trim();
+    let fmt = match inner.split_once(':') {
+        Some((key, val)) if key.trim() == "format" => val.trim().to_lowercase(),
+        _ => bail!("E_BAD_ARG: load options expect format:<json|yaml|toml>"),
+    };
+    match fmt.as_str() {
+        "json" | "yaml" | "toml" => Ok(Some(fmt)),
+        "yml" => Ok(Some("yaml".into())),
+        other => bail!("E_FORMAT_UNKNOWN: unsupported format '{other}'"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
 
+    #[test]
+    fn large_json_parses_through_reader() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_stream_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "stream-threshold: 1024\n").unwrap();
+        let rows: Vec<serde_json::Value> = (0..5000)
+            .map(|i| serde_json::json!({"id": i, "name": format!("row-{i}")}))
+            .collect();
+        let body = serde_json::to_string(&serde_json::json!({"rows": rows})).unwrap();
+        assert!(body.len() > 100_000);
+        fs::write(base.join("big.json"), &body).unwrap();
+        let script = base.join("main.tgsk");
+
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        assert_eq!(rt.stream_threshold, 1024, "file above threshold should stream");
+
+        let ast = crate::router::parse("[load@/big.json]").unwrap();
+        match rt.eval(&ast).unwrap() {
+            Value::Doc(doc) => {
+                assert_eq!(doc.json["rows"].as_array().map(Vec::len), Some(5000));
+                assert_eq!(doc.json["rows"][4999]["name"], "row-4999");
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn malformed_json_is_a_parse_error_on_both_paths() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_bad_json_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(base.join("bad.json"), "{\"a\": 1,}").unwrap();
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+        let ast = crate::router::parse("[load@/bad.json]").unwrap();
+
+        let mut errors = Vec::new();
+        for threshold in [u64::MAX, 0] {
+            rt.stream_threshold = threshold;
+            let err = rt.eval(&ast).unwrap_err();
+            assert_eq!(crate::kernel::runtime::error_code(&err).as_deref(), Some("E_PARSE"), "{err:#}");
+            errors.push(format!("{err:#}"));
+        }
+        assert_eq!(errors[0], errors[1]);
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
     #[test]
     fn loads_file_within_red_root() {
         let base = std::env::temp_dir().join(format!("tgsk_load_test_{}", std::process::id()));
@@ -131,4 +437,171 @@ mod tests {
 
         fs::remove_dir_all(base).unwrap();
     }
+
+    #[test]
+    fn toml_arrays_of_tables_load_as_arrays() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_toml_aot_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(
+            base.join("servers.toml"),
+            "[[servers]]\nname = \"alpha\"\nport = 8080\n\n[[servers]]\nname = \"beta\"\nport = 9090\n",
+        )
+        .unwrap();
+        let script = base.join("main.tgsk");
+        let src = "[load@/servers.toml]>[store@cfg]>[get(servers)@cfg]>[store@servers]>\
+                   [iter@servers]{[get(port)@it]>[store@port]>[math@total+port]>[store@total]}";
+
+        let ast = crate::router::parse(src).unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        rt.set_var("total", Value::Num(0.0)).unwrap();
+        rt.eval(&ast).unwrap();
+        match rt.get_var("cfg") {
+            Some(Value::Doc(d)) => {
+                assert_eq!(d.json["servers"].as_array().map(Vec::len), Some(2));
+                assert_eq!(d.json["servers"][1]["name"], "beta");
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+        assert_eq!(rt.get_var("total"), Some(Value::Num(17170.0)));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn flattens_yaml_merge_keys() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_merge_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(
+            base.join("ci.yaml"),
+            "defaults: &defaults\n  image: rust\n  retries: 2\njob:\n  <<: *defaults\n  retries: 5\n",
+        )
+        .unwrap();
+        let script = base.join("main.tgsk");
+        fs::write(&script, "[load@/ci.yaml]>[store@cfg]").unwrap();
+
+        let ast = crate::router::parse(&fs::read_to_string(&script).unwrap()).unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let _ = rt.eval(&ast).unwrap();
+        match rt.get_var("cfg") {
+            Some(Value::Doc(d)) => {
+                assert_eq!(d.json["job"]["image"].as_str(), Some("rust"));
+                assert_eq!(d.json["job"]["retries"].as_i64(), Some(5));
+                assert!(d.json["job"].get("<<").is_none());
+                assert_eq!(d.json["defaults"]["retries"].as_i64(), Some(2));
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn sniffs_extensionless_json() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_sniff_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(base.join("settings"), "{\"hi\": [1, 2]}").unwrap();
+        let script = base.join("main.tgsk");
+        fs::write(&script, "[load@/settings]>[store@cfg]").unwrap();
+
+        let ast = crate::router::parse(&fs::read_to_string(&script).unwrap()).unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let _ = rt.eval(&ast).unwrap();
+        match rt.get_var("cfg") {
+            Some(Value::Doc(d)) => {
+                assert_eq!(d.json["hi"][1].as_i64(), Some(2));
+                assert_eq!(d.ext, "json");
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn forced_format_overrides_extension() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_forced_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(base.join("app.conf"), "name: demo\nport: 8080\n").unwrap();
+        let script = base.join("main.tgsk");
+        fs::write(&script, "[load(format:yaml)@/app.conf]>[store@cfg]").unwrap();
+
+        let ast = crate::router::parse(&fs::read_to_string(&script).unwrap()).unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let _ = rt.eval(&ast).unwrap();
+        match rt.get_var("cfg") {
+            Some(Value::Doc(d)) => {
+                assert_eq!(d.json["name"].as_str(), Some("demo"));
+                assert_eq!(d.json["port"].as_i64(), Some(8080));
+                assert_eq!(d.ext, "yaml");
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn pointer_extracts_nested_value() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_pointer_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        let body = r#"{"meta": {"a/b": 7}, "users": [{"name": "ada"}, {"name": "bob", "tags": ["x"]}]}"#;
+        fs::write(base.join("big.json"), body).unwrap();
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+        let mut load = |script: &str| {
+            let ast = crate::router::parse(script).unwrap();
+            rt.eval(&ast)
+        };
+
+        assert_eq!(load("[load(pointer:/users/1/name)@/big.json]").unwrap(), Value::Str("bob".into()));
+        assert_eq!(load("[load(pointer:/meta/a~1b)@/big.json]").unwrap(), Value::Num(7.0));
+        match load("[load(pointer:/users/1/tags)@/big.json]").unwrap() {
+            Value::Doc(doc) => {
+                assert_eq!(doc.json, serde_json::json!(["x"]));
+                assert!(doc.path.ends_with("_pointer.json"));
+            }
+            other => panic!("unexpected value: {other:?}"),
+        }
+
+        let err = load("[load(pointer:users/0)@/big.json]").unwrap_err();
+        assert!(format!("{err:#}").contains("E_BAD_ARG"), "unexpected error: {err:#}");
+        let err = load("[load(pointer:/users/5/name)@/big.json]").unwrap_err();
+        assert!(format!("{err:#}").contains("E_PATH_MISSING"), "unexpected error: {err:#}");
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn meta_reports_file_size() {
+        let base = std::env::temp_dir().join(format!("tgsk_load_meta_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(base.join("data")).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        let body = "{\"k\": 12345}";
+        fs::write(base.join("data").join("blob.json"), body).unwrap();
+        let script = base.join("main.tgsk");
+        fs::write(&script, "[load(meta)@/data/blob.json]").unwrap();
+
+        let ast = crate::router::parse(&fs::read_to_string(&script).unwrap()).unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let out = rt.eval(&ast).unwrap();
+        let Value::Doc(doc) = out else {
+            panic!("expected doc, got {out:?}");
+        };
+        assert_eq!(doc.json["size"].as_u64(), Some(body.len() as u64));
+        assert_eq!(doc.json["path"], "/data/blob.json");
+        assert_eq!(doc.json["ext"], "json");
+        assert!(doc.json["modified_epoch"].as_u64().unwrap() > 0);
+
+        fs::remove_dir_all(base).unwrap();
+    }
 }
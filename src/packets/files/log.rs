@@ -4,16 +4,15 @@ use std::io::Write;
 use std::path::Path;
 
 use crate::kernel::ast::Arg;
-use crate::kernel::fs_guard::resolve;
+use crate::kernel::fs_guard::{ensure_parent_dirs, resolve};
+use crate::kernel::values::num_to_json;
 use crate::kernel::{Packet, Runtime, Value};
 
 fn to_json(v: &Value) -> serde_json::Value {
     match v {
         Value::Unit => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(*b),
-        Value::Num(n) => serde_json::Number::from_f64(*n)
-            .map(serde_json::Value::Number)
-            .unwrap_or(serde_json::Value::Null),
+        Value::Num(n) => num_to_json(*n).unwrap_or(serde_json::Value::Null),
         Value::Str(s) => serde_json::Value::String(s.clone()),
         Value::Doc(d) => d.json.clone(),
     }
@@ -22,13 +21,13 @@ fn to_json(v: &Value) -> serde_json::Value {
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let raw = match &p.arg {
         Some(Arg::Str(s)) => s,
-        _ => bail!("log needs @<path>"),
+        _ => bail!("E_BAD_ARG: log needs @<path>"),
     };
 
     let root = rt
         .effective_root
         .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("no red.tgsk root"))?;
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
 
     let rel = if raw.starts_with('/') {
         &raw[1..]
@@ -41,6 +40,29 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         rt.cwd.join(rel)
     };
     let path = resolve(root, &candidate)?;
+    let dry_run = rt.dry_run;
+    if !dry_run {
+        ensure_parent_dirs(root, &path)?;
+    }
+
+    // [log(append)@/run.log] -> one line per call: docs and structured bodies as compact
+    // JSON, other values as printed
+    if crate::router::extract_paren(&p.op).map(str::trim) == Some("append") {
+        let line = match &p.body {
+            Some(body) => serde_json::to_string(&build_object_from_body(rt, body)?)?,
+            None => match &rt.last {
+                Value::Doc(d) => serde_json::to_string(&d.json)?,
+                other => other.to_string(),
+            },
+        };
+        if dry_run {
+            eprintln!("[dry-run] would append to {}: {line}", path.display());
+        } else {
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            writeln!(file, "{line}")?;
+        }
+        return Ok(rt.last.clone());
+    }
 
     // Structured mode: if body present, interpret inner packets as literals and build a structured document
     if let Some(body) = &p.body {
@@ -52,15 +74,15 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         match mode {
             Mode::Json => {
                 let s = serde_json::to_string_pretty(&obj)?;
-                write_all(&path, &s)?;
+                write_all(&path, &s, dry_run)?;
             }
             Mode::Yaml => {
                 let s = serde_yaml::to_string(&obj)?;
-                write_all(&path, &s)?;
+                write_all(&path, &s, dry_run)?;
             }
             Mode::Toml => {
                 let s = toml::to_string_pretty(&obj)?;
-                write_all(&path, &s)?;
+                write_all(&path, &s, dry_run)?;
             }
         }
         return Ok(rt.last.clone());
@@ -68,7 +90,7 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
 
     // Fallback: dump last value as pretty JSON
     let json = serde_json::to_string_pretty(&to_json(&rt.last))?;
-    write_all(&path, &json)?;
+    write_all(&path, &json, dry_run)?;
     Ok(rt.last.clone())
 }
 
@@ -99,7 +121,11 @@ fn detect_mode(op: &str, ext: &str) -> Mode {
     }
 }
 
-fn write_all(path: &Path, s: &str) -> Result<()> {
+fn write_all(path: &Path, s: &str, dry_run: bool) -> Result<()> {
+    if dry_run {
+        eprintln!("[dry-run] would write {} bytes to {}", s.len() + 1, path.display());
+        return Ok(());
+    }
     let mut file = OpenOptions::new()
         .create(true)
         .truncate(true)
@@ -135,16 +161,16 @@ fn build_object_from_body(
                 } else if let Some(n) = parse_paren_name(&pkt.op) {
                     n.to_string()
                 } else {
-                    bail!("sect needs @<name> or (name)");
+                    bail!("E_BAD_ARG: sect needs @<name> or (name)");
                 };
                 let inner = pkt
                     .body
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("sect missing body"))?;
+                    .ok_or_else(|| anyhow::anyhow!("E_MISSING_BODY: sect missing body"))?;
                 let obj = build_object_from_body(rt, inner)?;
                 root.insert(name, obj);
             } else {
-                bail!(format!("unsupported packet in [log] body: [{}]", pkt.op));
+                bail!("E_UNSUPPORTED_PACKET: [{}] is not allowed in a [log] body", pkt.op);
             }
         }
     }
@@ -171,9 +197,7 @@ fn value_to_json(v: Value) -> Result<serde_json::Value> {
     Ok(match v {
         Value::Unit => serde_json::Value::Null,
         Value::Bool(b) => serde_json::Value::Bool(b),
-        Value::Num(n) => serde_json::Value::Number(
-            serde_json::Number::from_f64(n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-        ),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Value::Str(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
         Value::Doc(d) => d.json,
     })
@@ -181,21 +205,13 @@ fn value_to_json(v: Value) -> Result<serde_json::Value> {
 
 fn arg_to_json(rt: &Runtime, arg: &Arg) -> Result<serde_json::Value> {
     Ok(match arg {
-        Arg::Number(n) => {
-            if n.fract() == 0.0 && *n >= (i64::MIN as f64) && *n <= (i64::MAX as f64) {
-                serde_json::Value::Number(serde_json::Number::from(*n as i64))
-            } else {
-                serde_json::Value::Number(
-                    serde_json::Number::from_f64(*n)
-                        .ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-                )
-            }
-        }
+        Arg::Number(n) => num_to_json(*n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Arg::Str(s) => serde_json::from_str(s).unwrap_or(serde_json::Value::String(s.clone())),
         Arg::Ident(id) => match id.as_str() {
             "true" => serde_json::Value::Bool(true),
             "false" => serde_json::Value::Bool(false),
             "null" => serde_json::Value::Null,
+            "_" => value_to_json(rt.last.clone())?,
             other => {
                 if let Some(v) = rt.get_var(other) {
                     value_to_json(v)?
@@ -231,6 +247,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn whole_numbers_are_written_like_json_packet() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_log_int_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        let script = base.join("main.tgsk");
+        fs::write(&script, "[int@3]>[log@/n.json]>[json]")?;
+        let node = router::parse(&fs::read_to_string(&script)?).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::from_entry(&script)?;
+        let shown = rt.eval(&node)?;
+        assert_eq!(fs::read_to_string(base.join("n.json"))?.trim(), "3");
+        assert_eq!(shown, Value::Str("3".into()));
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
+    #[test]
+    fn append_keeps_lines_in_order() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_log_append_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        let script = base.join("main.tgsk");
+        fs::write(
+            &script,
+            "[msg@\"started\"]>[log(append)@/run.log]>[int@2]>[log(append)@/run.log]",
+        )?;
+        let node = router::parse(&fs::read_to_string(&script)?).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::from_entry(&script)?;
+        rt.eval(&node)?;
+        assert_eq!(fs::read_to_string(base.join("run.log"))?, "started\n2\n");
+        fs::remove_dir_all(base)?;
+        Ok(())
+    }
+
     #[test]
     fn structured_json_log() -> Result<()> {
         use std::fs;
@@ -0,0 +1,87 @@
+use anyhow::{Result, bail};
+
+use crate::kernel::ast::Arg;
+use crate::kernel::values::{Document, Value};
+use crate::kernel::{Packet, Runtime};
+use crate::packets::modify::deep_merge_with;
+
+// [merge@base,override]               -> new doc: override deep-merged into base, override wins
+// [merge(array:concat)@base,override] -> same, but arrays on both sides are concatenated
+// Neither variable changes; the result is a scratch doc (_merge.json).
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let concat = match crate::router::extract_paren(&p.op).map(str::trim) {
+        None => false,
+        Some("array:concat") => true,
+        Some("array:replace") => false,
+        Some(other) => bail!("E_MODE_UNKNOWN: unknown merge option '{other}'"),
+    };
+    let list = match p.arg.as_ref() {
+        Some(Arg::Str(s)) => s.as_str(),
+        _ => bail!("E_BAD_ARG: merge needs @base,override"),
+    };
+    let (base, over) = match list.split_once(',') {
+        Some((a, b)) if !a.trim().is_empty() && !b.trim().is_empty() => (a.trim(), b.trim()),
+        _ => bail!("E_BAD_ARG: merge needs @base,override"),
+    };
+    let mut doc = match rt.get_var(base) {
+        Some(Value::Doc(d)) => d,
+        _ => bail!("E_HANDLE_UNKNOWN: '{base}' is not a loaded document"),
+    };
+    let patch = match rt.get_var(over) {
+        Some(Value::Doc(d)) => d.json,
+        _ => bail!("E_HANDLE_UNKNOWN: '{over}' is not a loaded document"),
+    };
+    if !doc.json.is_object() || !patch.is_object() {
+        bail!("E_NOT_OBJECT: merge needs two object documents");
+    }
+    deep_merge_with(&mut doc.json, &patch, 0, rt.doc_max_depth, concat)?;
+    Ok(Value::Doc(Document::scratch(doc.root, &rt.cwd, "_merge.json", doc.json)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+    use std::time::SystemTime;
+
+    fn set_doc(rt: &mut Runtime, name: &str, json: serde_json::Value) -> Result<()> {
+        let doc = Document::new(json, format!("{name}.json").into(), "json".into(), SystemTime::now(), Default::default());
+        rt.set_var(name, Value::Doc(doc))
+    }
+
+    fn merged(rt: &mut Runtime, script: &str) -> Result<serde_json::Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        match rt.eval(&node)? {
+            Value::Doc(d) => Ok(d.json),
+            other => bail!("expected doc, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn override_wins_on_objects() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        set_doc(&mut rt, "base", serde_json::json!({"db": {"host": "localhost", "port": 5432}, "tags": ["a"]}))?;
+        set_doc(&mut rt, "prod", serde_json::json!({"db": {"host": "db.prod"}, "tags": ["b"]}))?;
+        let out = merged(&mut rt, "[merge@base,prod]")?;
+        assert_eq!(out, serde_json::json!({"db": {"host": "db.prod", "port": 5432}, "tags": ["b"]}));
+        match &rt.last {
+            Value::Doc(d) => assert_eq!(d.path.file_name(), Some("_merge.json".as_ref())),
+            other => panic!("expected doc, got {other:?}"),
+        }
+        match rt.get_var("base") {
+            Some(Value::Doc(d)) => assert_eq!(d.json["db"]["host"], "localhost"),
+            other => panic!("base changed: {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn concat_mode_appends_arrays() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        set_doc(&mut rt, "base", serde_json::json!({"plugins": ["lint"], "opts": {"paths": ["/a"]}}))?;
+        set_doc(&mut rt, "extra", serde_json::json!({"plugins": ["fmt"], "opts": {"paths": ["/b"]}}))?;
+        let out = merged(&mut rt, "[merge(array:concat)@base,extra]")?;
+        assert_eq!(out, serde_json::json!({"plugins": ["lint", "fmt"], "opts": {"paths": ["/a", "/b"]}}));
+        Ok(())
+    }
+}
@@ -1,25 +1,34 @@
 use crate::kernel::Runtime;
 use crate::kernel::ast::{Arg, Node, Packet};
-use crate::kernel::values::{Document, Value};
+use crate::kernel::values::{Document, Value, num_to_json};
+use crate::packets::query;
 use anyhow::{Result, anyhow, bail};
 use serde_json::Value as JsonValue;
 
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
-    let options = parse_mod_options(&p.op)?;
+    let mut options = parse_mod_options(&p.op)?;
+    options.max_depth = rt.doc_max_depth;
     let handle = match &p.arg {
         Some(Arg::Ident(id)) => id.as_str(),
-        _ => bail!("mod needs @<ident>"),
+        _ => bail!("E_BAD_ARG: mod needs @<ident>"),
     };
-    let body = p.body.as_ref().ok_or_else(|| anyhow!("mod needs body"))?;
+    let body = p.body.as_ref().ok_or_else(|| anyhow!("E_MISSING_BODY: mod needs body"))?;
+    // edits land on a working copy; the variable only sees it once every edit succeeds
     let mut doc = match rt.get_var(handle) {
         Some(Value::Doc(d)) => d,
-        _ => bail!("handle_unknown"),
+        _ => bail!("E_HANDLE_UNKNOWN: '{handle}' is not a loaded document"),
     };
     let before = options.debug.then(|| doc.clone());
 
     for node in body {
-        if let Node::Packet(pkt) = node {
-            apply_edit(rt, &mut doc, pkt, &options)?;
+        if let Node::Packet(pkt) = node
+            && let Err(err) = apply_edit(rt, &mut doc, pkt, &options)
+        {
+            // mod(partial) keeps whatever landed before the failing edit
+            if options.partial {
+                rt.set_var(handle, Value::Doc(doc))?;
+            }
+            return Err(err);
         }
     }
 
@@ -39,6 +48,17 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
 
 fn apply_edit(rt: &Runtime, doc: &mut Document, pkt: &Packet, options: &ModOptions) -> Result<()> {
     let cmd = parse_op(&pkt.op)?;
+    // prefix failures with the edit so long blocks point at the culprit, e.g. `del(user.name): E_PATH_MISSING: ...`
+    run_edit(rt, doc, pkt, &cmd, options).map_err(|e| anyhow!("{}({}): {e}", cmd.name, cmd.path))
+}
+
+fn run_edit(
+    rt: &Runtime,
+    doc: &mut Document,
+    pkt: &Packet,
+    cmd: &EditCommand,
+    options: &ModOptions,
+) -> Result<()> {
     let segments = parse_path(&cmd.path)?;
     match cmd.name.as_str() {
         "comp" => {
@@ -46,55 +66,69 @@ fn apply_edit(rt: &Runtime, doc: &mut Document, pkt: &Packet, options: &ModOptio
                 rt,
                 pkt.arg
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("comp needs value"))?,
+                    .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: comp needs value"))?,
             )?;
             let create = options.force_overwrite;
-            set_value(&mut doc.json, &segments, val, create, true)?;
+            set_value(
+                &mut doc.json,
+                &segments,
+                val,
+                create,
+                true,
+                options.max_depth,
+            )?;
         }
         "comp!" => {
             let val = arg_to_json(
                 rt,
                 pkt.arg
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("comp! needs value"))?,
+                    .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: comp! needs value"))?,
             )?;
-            set_value(&mut doc.json, &segments, val, true, true)?;
+            set_value(&mut doc.json, &segments, val, true, true, options.max_depth)?;
         }
         "merge" => {
             let val = arg_to_json(
                 rt,
                 pkt.arg
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("merge needs value"))?,
+                    .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: merge needs value"))?,
             )?;
             if !val.is_object() {
-                bail!("merge requires object value");
+                bail!("E_NOT_OBJECT: merge requires object value");
             }
-            let target = navigate(&mut doc.json, &segments, true)?;
-            deep_merge(target, &val);
+            let target = navigate(&mut doc.json, &segments, true, options.max_depth)?;
+            deep_merge(target, &val, segments.len(), options.max_depth)?;
         }
         "del" => {
-            delete(&mut doc.json, &segments)?;
+            delete(&mut doc.json, &segments, options.max_depth)?;
         }
         "ins" | "insert" => {
             let val = arg_to_json(
                 rt,
                 pkt.arg
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("ins needs value"))?,
+                    .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: ins needs value"))?,
+            )?;
+            set_value(
+                &mut doc.json,
+                &segments,
+                val,
+                false,
+                false,
+                options.max_depth,
             )?;
-            set_value(&mut doc.json, &segments, val, false, false)?;
         }
         "push" => {
             let val = arg_to_json(
                 rt,
                 pkt.arg
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("push needs value"))?,
+                    .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: push needs value"))?,
             )?;
-            let target = navigate(&mut doc.json, &segments, true)?;
+            let target = navigate(&mut doc.json, &segments, true, options.max_depth)?;
             if !target.is_array() {
-                bail!("not_array");
+                bail!("E_NOT_ARRAY: target is not an array");
             }
             target.as_array_mut().unwrap().push(val);
         }
@@ -103,37 +137,44 @@ fn apply_edit(rt: &Runtime, doc: &mut Document, pkt: &Packet, options: &ModOptio
                 rt,
                 pkt.arg
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("set needs value"))?,
+                    .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: set needs value"))?,
             )?;
             let overwrite = match cmd.modifier.as_deref() {
                 Some("missing") => false,
                 Some("overwrite") => true,
                 None => true,
-                Some(other) => bail!("unknown set modifier '{other}'"),
+                Some(other) => bail!("E_EDIT_SYNTAX: unknown set modifier '{other}'"),
             };
             let overwrite = overwrite || options.force_overwrite;
             if !overwrite && path_exists_read(&doc.json, &segments) {
                 return Ok(());
             }
-            set_value(&mut doc.json, &segments, val, true, overwrite)?;
+            set_value(
+                &mut doc.json,
+                &segments,
+                val,
+                true,
+                overwrite,
+                options.max_depth,
+            )?;
         }
         "remove" | "delete" => {
-            delete(&mut doc.json, &segments)?;
+            delete(&mut doc.json, &segments, options.max_depth)?;
         }
         "append" => {
             let val = arg_to_json(
                 rt,
                 pkt.arg
                     .as_ref()
-                    .ok_or_else(|| anyhow::anyhow!("append needs value"))?,
+                    .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: append needs value"))?,
             )?;
-            let target = navigate(&mut doc.json, &segments, true)?;
+            let target = navigate(&mut doc.json, &segments, true, options.max_depth)?;
             if !target.is_array() {
-                bail!("not_array");
+                bail!("E_NOT_ARRAY: target is not an array");
             }
             target.as_array_mut().unwrap().push(val);
         }
-        other => bail!("unknown edit op: {other}"),
+        other => bail!("E_EDIT_SYNTAX: unknown edit op: {other}"),
     }
     Ok(())
 }
@@ -141,21 +182,21 @@ fn apply_edit(rt: &Runtime, doc: &mut Document, pkt: &Packet, options: &ModOptio
 fn parse_op(op: &str) -> Result<EditCommand> {
     let start = op
         .find('(')
-        .ok_or_else(|| anyhow::anyhow!("edit missing ("))?;
+        .ok_or_else(|| anyhow::anyhow!("E_EDIT_SYNTAX: edit missing ("))?;
     let end = op
         .rfind(')')
-        .ok_or_else(|| anyhow::anyhow!("edit missing )"))?;
+        .ok_or_else(|| anyhow::anyhow!("E_EDIT_SYNTAX: edit missing )"))?;
     let name = op[..start].to_string();
     let inner = op[start + 1..end].trim();
     if inner.is_empty() {
-        bail!("edit missing path");
+        bail!("E_EDIT_SYNTAX: edit missing path");
     }
     let mut parts = inner.splitn(2, ',');
     let path = parts
         .next()
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
-        .ok_or_else(|| anyhow!("edit missing path"))?;
+        .ok_or_else(|| anyhow!("E_EDIT_SYNTAX: edit missing path"))?;
     let modifier = parts
         .next()
         .map(|s| s.trim().to_ascii_lowercase())
@@ -174,45 +215,23 @@ enum Segment {
 }
 
 fn parse_path(path: &str) -> Result<Vec<Segment>> {
-    let mut segs = Vec::new();
-    let mut buf = String::new();
-    let mut chars = path.chars().peekable();
-    while let Some(c) = chars.next() {
-        match c {
-            '.' => {
-                if !buf.is_empty() {
-                    segs.push(Segment::Key(buf.clone()));
-                    buf.clear();
-                }
-            }
-            '[' => {
-                if !buf.is_empty() {
-                    segs.push(Segment::Key(buf.clone()));
-                    buf.clear();
-                }
-                let mut num = String::new();
-                for ch in chars.by_ref() {
-                    if ch == ']' {
-                        break;
-                    }
-                    num.push(ch);
-                }
-                segs.push(Segment::Index(num.parse()?));
-            }
-            _ => buf.push(c),
-        }
-    }
-    if !buf.is_empty() {
-        segs.push(Segment::Key(buf));
-    }
-    Ok(segs)
+    query::parse_path(path)?
+        .into_iter()
+        .map(|seg| match seg {
+            query::Segment::Key(k) => Ok(Segment::Key(k)),
+            query::Segment::Index(i) => Ok(Segment::Index(i)),
+            query::Segment::Wildcard => bail!("E_BAD_ARG: [*] only reads; modify needs a concrete path"),
+        })
+        .collect()
 }
 
 fn navigate<'a>(
     root: &'a mut JsonValue,
     segs: &[Segment],
     create: bool,
+    max_depth: usize,
 ) -> Result<&'a mut JsonValue> {
+    check_depth(segs.len(), max_depth)?;
     let mut cur = root;
     for seg in segs {
         match seg {
@@ -221,7 +240,7 @@ fn navigate<'a>(
                     if create {
                         *cur = JsonValue::Object(Default::default());
                     } else {
-                        bail!("path_missing");
+                        bail!("E_PATH_MISSING: path not found");
                     }
                 }
                 cur = cur
@@ -235,7 +254,7 @@ fn navigate<'a>(
                     if create {
                         *cur = JsonValue::Array(Vec::new());
                     } else {
-                        bail!("path_missing");
+                        bail!("E_PATH_MISSING: path not found");
                     }
                 }
                 let arr = cur.as_array_mut().unwrap();
@@ -243,7 +262,7 @@ fn navigate<'a>(
                     if create {
                         arr.resize(i + 1, JsonValue::Null);
                     } else {
-                        bail!("path_missing");
+                        bail!("E_PATH_MISSING: path not found");
                     }
                 }
                 cur = &mut arr[*i];
@@ -259,24 +278,25 @@ fn set_value(
     val: JsonValue,
     create: bool,
     overwrite: bool,
+    max_depth: usize,
 ) -> Result<()> {
     if segs.is_empty() {
-        bail!("empty path");
+        bail!("E_PATH_EMPTY: empty path");
     }
     let (head, last) = segs.split_at(segs.len() - 1);
-    let parent = navigate(root, head, create)?;
+    let parent = navigate(root, head, create, max_depth)?;
     match last[0].clone() {
         Segment::Key(k) => {
             if !parent.is_object() {
                 if create {
                     *parent = JsonValue::Object(Default::default());
                 } else {
-                    bail!("path_missing");
+                    bail!("E_PATH_MISSING: path not found");
                 }
             }
             let obj = parent.as_object_mut().unwrap();
             if !overwrite && obj.contains_key(&k) {
-                bail!("exists");
+                bail!("E_PATH_EXISTS: path already exists");
             }
             obj.insert(k, val);
         }
@@ -285,7 +305,7 @@ fn set_value(
                 if create {
                     *parent = JsonValue::Array(Vec::new());
                 } else {
-                    bail!("path_missing");
+                    bail!("E_PATH_MISSING: path not found");
                 }
             }
             let arr = parent.as_array_mut().unwrap();
@@ -293,11 +313,11 @@ fn set_value(
                 if create {
                     arr.resize(i + 1, JsonValue::Null);
                 } else {
-                    bail!("path_missing");
+                    bail!("E_PATH_MISSING: path not found");
                 }
             }
             if !overwrite && arr[i] != JsonValue::Null {
-                bail!("exists");
+                bail!("E_PATH_EXISTS: path already exists");
             }
             arr[i] = val;
         }
@@ -305,27 +325,27 @@ fn set_value(
     Ok(())
 }
 
-fn delete(root: &mut JsonValue, segs: &[Segment]) -> Result<()> {
+fn delete(root: &mut JsonValue, segs: &[Segment], max_depth: usize) -> Result<()> {
     if segs.is_empty() {
-        bail!("empty path");
+        bail!("E_PATH_EMPTY: empty path");
     }
     let (head, last) = segs.split_at(segs.len() - 1);
-    let parent = navigate(root, head, false)?;
+    let parent = navigate(root, head, false, max_depth)?;
     match last[0].clone() {
         Segment::Key(k) => {
             let obj = parent
                 .as_object_mut()
-                .ok_or_else(|| anyhow::anyhow!("path_missing"))?;
+                .ok_or_else(|| anyhow::anyhow!("E_PATH_MISSING: path not found"))?;
             if obj.remove(&k).is_none() {
-                bail!("path_missing");
+                bail!("E_PATH_MISSING: path not found");
             }
         }
         Segment::Index(i) => {
             let arr = parent
                 .as_array_mut()
-                .ok_or_else(|| anyhow::anyhow!("path_missing"))?;
+                .ok_or_else(|| anyhow::anyhow!("E_PATH_MISSING: path not found"))?;
             if i >= arr.len() {
-                bail!("path_missing");
+                bail!("E_PATH_MISSING: path not found");
             }
             arr.remove(i);
         }
@@ -364,26 +384,73 @@ fn path_exists_read(root: &JsonValue, segs: &[Segment]) -> bool {
     true
 }
 
-fn deep_merge(dest: &mut JsonValue, src: &JsonValue) {
+// `depth` counts levels below the document root so a merge deep inside a path
+// shares the same budget as the path itself.
+fn deep_merge(dest: &mut JsonValue, src: &JsonValue, depth: usize, max_depth: usize) -> Result<()> {
+    deep_merge_with(dest, src, depth, max_depth, false)
+}
+
+// Same as the [mod] merge; `concat_arrays` appends array items instead of replacing the array.
+pub fn deep_merge_with(
+    dest: &mut JsonValue,
+    src: &JsonValue,
+    depth: usize,
+    max_depth: usize,
+    concat_arrays: bool,
+) -> Result<()> {
+    check_depth(depth, max_depth)?;
     match (dest, src) {
         (JsonValue::Object(a), JsonValue::Object(b)) => {
             for (k, v) in b {
-                deep_merge(a.entry(k.clone()).or_insert(JsonValue::Null), v);
+                deep_merge_with(
+                    a.entry(k.clone()).or_insert(JsonValue::Null),
+                    v,
+                    depth + 1,
+                    max_depth,
+                    concat_arrays,
+                )?;
             }
         }
+        (JsonValue::Array(a), JsonValue::Array(b)) if concat_arrays => {
+            check_depth(depth + json_depth(src), max_depth)?;
+            a.extend(b.iter().cloned());
+        }
         (dest, src) => {
+            // the copied subtree counts against the budget too
+            check_depth(depth + json_depth(src), max_depth)?;
             *dest = src.clone();
         }
     }
+    Ok(())
+}
+
+fn check_depth(depth: usize, max_depth: usize) -> Result<()> {
+    if depth > max_depth {
+        bail!("E_DOC_TOO_DEEP: document nesting exceeds max depth {max_depth}");
+    }
+    Ok(())
+}
+
+// iterative so measuring a hostile document can't itself overflow the stack
+fn json_depth(v: &JsonValue) -> usize {
+    let mut max = 0;
+    let mut stack = vec![(v, 0usize)];
+    while let Some((cur, depth)) = stack.pop() {
+        max = max.max(depth);
+        match cur {
+            JsonValue::Object(m) => stack.extend(m.values().map(|c| (c, depth + 1))),
+            JsonValue::Array(a) => stack.extend(a.iter().map(|c| (c, depth + 1))),
+            _ => {}
+        }
+    }
+    max
 }
 
 fn value_to_json(v: Value) -> Result<JsonValue> {
     Ok(match v {
         Value::Unit => JsonValue::Null,
         Value::Bool(b) => JsonValue::Bool(b),
-        Value::Num(n) => JsonValue::Number(
-            serde_json::Number::from_f64(n).ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-        ),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Value::Str(s) => serde_json::from_str(&s).unwrap_or(JsonValue::String(s)),
         Value::Doc(d) => d.json,
     })
@@ -391,21 +458,14 @@ fn value_to_json(v: Value) -> Result<JsonValue> {
 
 fn arg_to_json(rt: &Runtime, arg: &Arg) -> Result<JsonValue> {
     Ok(match arg {
-        Arg::Number(n) => {
-            if n.fract() == 0.0 && *n >= i64::MIN as f64 && *n <= i64::MAX as f64 {
-                JsonValue::Number((*n as i64).into())
-            } else {
-                JsonValue::Number(
-                    serde_json::Number::from_f64(*n)
-                        .ok_or_else(|| anyhow::anyhow!("invalid number"))?,
-                )
-            }
-        }
+        Arg::Number(n) => num_to_json(*n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
         Arg::Str(s) => serde_json::from_str(s).unwrap_or(JsonValue::String(s.clone())),
         Arg::Ident(id) => match id.as_str() {
             "true" => JsonValue::Bool(true),
             "false" => JsonValue::Bool(false),
             "null" => JsonValue::Null,
+            // `_` is the pipeline value flowing into this packet
+            "_" => value_to_json(rt.last.clone())?,
             other => {
                 if let Some(v) = rt.get_var(other) {
                     value_to_json(v)?
@@ -422,17 +482,20 @@ fn arg_to_json(rt: &Runtime, arg: &Arg) -> Result<JsonValue> {
 struct ModOptions {
     force_overwrite: bool,
     debug: bool,
+    partial: bool,
+    // docs.max_depth / TAGSPEAK_MAX_DOC_DEPTH
+    max_depth: usize,
 }
 
 fn parse_mod_options(op: &str) -> Result<ModOptions> {
     let trimmed = op.trim();
     if !trimmed.eq_ignore_ascii_case("mod") && !trimmed.to_ascii_lowercase().starts_with("mod(") {
-        bail!("unsupported mod form '{op}'");
+        bail!("E_BAD_ARG: unsupported mod form '{op}'");
     }
     let mut options = ModOptions::default();
     if let Some(inner) = trimmed.strip_prefix("mod(") {
         if !inner.ends_with(')') {
-            bail!("malformed mod options");
+            bail!("E_BAD_ARG: malformed mod options");
         }
         let inner = &inner[..inner.len() - 1];
         if inner.trim().is_empty() {
@@ -443,7 +506,8 @@ fn parse_mod_options(op: &str) -> Result<ModOptions> {
             match flag.as_str() {
                 "overwrite" => options.force_overwrite = true,
                 "debug" => options.debug = true,
-                other => bail!("unknown mod option '{other}'"),
+                "partial" => options.partial = true,
+                other => bail!("E_BAD_ARG: unknown mod option '{other}'"),
             }
         }
     }
@@ -528,6 +592,82 @@ mod tests {
         assert_eq!(after["user"]["name"], "Jen");
     }
 
+    #[test]
+    fn merge_rejects_documents_past_max_depth() {
+        let mut deep = json!(1);
+        for _ in 0..200 {
+            deep = json!({ "n": deep });
+        }
+        let mut rt = Runtime::new().unwrap();
+        rt.set_var("doc", Value::Doc(doc_from_json(json!({}))))
+            .unwrap();
+        rt.set_var("patch", Value::Doc(doc_from_json(deep)))
+            .unwrap();
+        let ast = router::parse("[mod@doc]{[merge(cfg)@patch]}").unwrap();
+        let err = rt.eval(&ast).expect_err("expected depth failure");
+        assert!(
+            err.to_string().contains("E_DOC_TOO_DEEP"),
+            "unexpected error: {err}"
+        );
+        // the limit is read once into the runtime
+        rt.doc_max_depth = 256;
+        rt.eval(&ast).unwrap();
+    }
+
+    #[test]
+    fn underscore_arg_uses_piped_value() {
+        let mut rt = Runtime::new().unwrap();
+        rt.set_var("doc", Value::Doc(doc_from_json(json!({})))).unwrap();
+        let ast = router::parse("[msg@\"Jo\"]>[mod@doc]{[set(name)@_]}").unwrap();
+        rt.eval(&ast).unwrap();
+        match rt.get_var("doc") {
+            Some(Value::Doc(doc)) => assert_eq!(doc.json, json!({"name": "Jo"})),
+            other => panic!("doc missing: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn failing_edit_names_op_and_path() {
+        let mut rt = Runtime::new().unwrap();
+        rt.set_var("doc", Value::Doc(doc_from_json(json!({"user": {}}))))
+            .unwrap();
+        let ast = router::parse("[mod@doc]{[set(user.name)@\"Jen\"][del(user.email)]}").unwrap();
+        let err = rt.eval(&ast).expect_err("expected missing path failure");
+        assert!(
+            err.to_string().contains("del(user.email): E_PATH_MISSING"),
+            "unexpected error: {err}"
+        );
+    }
+
+    fn run_failing_mod(script: &str, initial: serde_json::Value) -> serde_json::Value {
+        let mut rt = Runtime::new().unwrap();
+        rt.set_var("doc", Value::Doc(doc_from_json(initial))).unwrap();
+        let ast = router::parse(script).unwrap();
+        assert!(rt.eval(&ast).is_err(), "expected mod failure");
+        match rt.get_var("doc").unwrap() {
+            Value::Doc(doc) => doc.json,
+            other => panic!("unexpected value {other:?}"),
+        }
+    }
+
+    #[test]
+    fn failed_block_leaves_doc_untouched() {
+        let after = run_failing_mod(
+            "[mod@doc]{[set(a)@1][set(b)@2][del(missing)][set(c)@3]}",
+            json!({"keep": true}),
+        );
+        assert_eq!(after, json!({"keep": true}));
+    }
+
+    #[test]
+    fn partial_keeps_edits_before_failure() {
+        let after = run_failing_mod(
+            "[mod(partial)@doc]{[set(a)@1][set(b)@2][del(missing)][set(c)@3]}",
+            json!({"keep": true}),
+        );
+        assert_eq!(after, json!({"keep": true, "a": 1, "b": 2}));
+    }
+
     #[test]
     fn mod_overwrite_upgrades_comp() {
         let after = run_mod("[mod(overwrite)@doc]{[comp(user.score)@42]}", json!({}));
@@ -2,64 +2,102 @@ use anyhow::{Result, bail};
 use serde_json::Value as JsonValue;
 
 use crate::kernel::ast::Arg;
+use crate::kernel::boolops::cmp_eval;
 use crate::kernel::values::{Document, Value};
 use crate::kernel::{Packet, Runtime};
+use crate::packets::conditionals::split_comparator;
 
 // Query packets operating on in-memory documents:
 // [get(path)@handle]    -> extracts value at path from document variable and returns it
+// [get(parent:path)@handle] -> the object/array holding the value at path (Unit if missing)
 // [exists(path)@handle] -> returns true if path exists in the document
 // Path syntax mirrors [mod] (dot keys and [idx] for arrays): e.g., user.name, items[0]
+// A [*] segment maps the rest of the path over every array element:
+// [get(items[*].price)@doc] -> array of prices; elements missing the field yield null,
+// or are dropped with [get(items[*].price, compact)@doc]
+// [count(items[*])@doc]              -> number of array elements
+// [count(items[*].active==true)@doc] -> number of elements matching the comparison
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let (mode, path) = parse_mode_and_path(&p.op)?;
+    let (path, compact) = match path.rsplit_once(',') {
+        Some((path, flag)) if flag.trim().eq_ignore_ascii_case("compact") => {
+            (path.trim().to_string(), true)
+        }
+        Some((_, flag)) => bail!("E_BAD_ARG: unknown query flag '{}'", flag.trim()),
+        None => (path, false),
+    };
     let handle = match p.arg.as_ref() {
         Some(Arg::Ident(id)) => id,
-        _ => bail!("query needs @<handle>"),
+        _ => bail!("E_BAD_ARG: query needs @<handle>"),
     };
 
     let doc = match rt.get_var(handle) {
         Some(Value::Doc(d)) => d,
-        _ => bail!("handle_unknown"),
+        _ => bail!("E_HANDLE_UNKNOWN: '{handle}' is not a loaded document"),
     };
 
-    let segs = parse_path(&path)?;
+    if mode == "count" {
+        return count(rt, &doc, &path);
+    }
+    if mode == "get"
+        && let Some(leaf) = path.strip_prefix("parent:")
+    {
+        return parent(rt, &doc, leaf.trim());
+    }
+
+    let segs = query_path(&path)?;
     match mode.as_str() {
         "get" => {
-            if let Some(v) = navigate_read(rt, &doc.json, &segs) {
-                Ok(json_to_value(v, &doc))
+            if let Some(v) = read_path(rt, &doc.json, &segs, compact) {
+                Ok(json_to_value(&v, &doc))
             } else {
                 Ok(Value::Unit)
             }
         }
-        "exists" => Ok(Value::Bool(navigate_read(rt, &doc.json, &segs).is_some())),
-        _ => bail!("unknown_query_mode"),
+        "exists" => Ok(Value::Bool(
+            read_path(rt, &doc.json, &segs, compact).is_some(),
+        )),
+        _ => bail!("E_MODE_UNKNOWN: unknown query mode"),
     }
 }
 
+// Reads `path` from `doc` like [get] does; None when the path is missing.
+pub fn read_value(rt: &Runtime, doc: &Document, path: &str) -> Result<Option<Value>> {
+    let segs = query_path(path)?;
+    Ok(read_path(rt, &doc.json, &segs, false).map(|v| json_to_value(&v, doc)))
+}
+
 fn parse_mode_and_path(op: &str) -> Result<(String, String)> {
     let start = op
         .find('(')
-        .ok_or_else(|| anyhow::anyhow!("query missing ("))?;
+        .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: query missing ("))?;
     let end = op
         .rfind(')')
-        .ok_or_else(|| anyhow::anyhow!("query missing )"))?;
+        .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: query missing )"))?;
     let name = op[..start].to_string();
     let path = op[start + 1..end].to_string();
     Ok((name, path))
 }
 
 #[derive(Clone)]
-enum Segment {
+pub enum Segment {
     Key(String),
     Index(usize),
+    Wildcard,
 }
 
-fn parse_path(path: &str) -> Result<Vec<Segment>> {
-    // Special-case: a bare numeric path like "0" means index 0
+// The query flavour of the path grammar: a bare numeric path like "0" means index 0
+fn query_path(path: &str) -> Result<Vec<Segment>> {
     if !path.contains('.') && !path.contains('[') && path.chars().all(|c| c.is_ascii_digit()) {
         let idx: usize = path.parse()?;
         return Ok(vec![Segment::Index(idx)]);
     }
+    parse_path(path)
+}
 
+// The one dot-path tokenizer, shared with [mod] and [search]: `a.b`, `items[0]`, `items[*]`,
+// `config\.version` or `["config.version"]` for keys holding a dot
+pub fn parse_path(path: &str) -> Result<Vec<Segment>> {
     let mut segs = Vec::new();
     let mut buf = String::new();
     let mut chars = path.chars().peekable();
@@ -76,6 +114,10 @@ fn parse_path(path: &str) -> Result<Vec<Segment>> {
                     segs.push(Segment::Key(buf.clone()));
                     buf.clear();
                 }
+                if chars.peek() == Some(&'"') {
+                    segs.push(Segment::Key(quoted_key(&mut chars)?));
+                    continue;
+                }
                 let mut num = String::new();
                 for ch in chars.by_ref() {
                     if ch == ']' {
@@ -83,8 +125,14 @@ fn parse_path(path: &str) -> Result<Vec<Segment>> {
                     }
                     num.push(ch);
                 }
-                segs.push(Segment::Index(num.parse()?));
+                if num.trim() == "*" {
+                    segs.push(Segment::Wildcard);
+                } else {
+                    segs.push(Segment::Index(num.parse()?));
+                }
             }
+            // `config\.version` is one key
+            '\\' => buf.push(chars.next().unwrap_or('\\')),
             _ => buf.push(c),
         }
     }
@@ -94,6 +142,101 @@ fn parse_path(path: &str) -> Result<Vec<Segment>> {
     Ok(segs)
 }
 
+// `["config.version"]` -> the key verbatim; `chars` sits on the opening quote
+fn quoted_key(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String> {
+    chars.next();
+    let mut key = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => key.push(chars.next().unwrap_or('\\')),
+            Some('"') => break,
+            Some(ch) => key.push(ch),
+            None => bail!("E_BAD_ARG: unterminated quoted key in path"),
+        }
+    }
+    if chars.next() != Some(']') {
+        bail!("E_BAD_ARG: quoted key needs a closing ] in path");
+    }
+    Ok(key)
+}
+
+fn count(rt: &Runtime, doc: &Document, expr: &str) -> Result<Value> {
+    let (path, pred) = match split_comparator(expr) {
+        Some((lhs, cmp, rhs)) => (lhs, Some((cmp, literal_value(rt, rhs)?))),
+        None => (expr.trim(), None),
+    };
+    let segs = query_path(path)?;
+    let items = match read_path(rt, &doc.json, &segs, false) {
+        Some(JsonValue::Array(items)) => items,
+        Some(_) => bail!("E_NOT_ARRAY: count needs an array path (try items[*])"),
+        None => return Ok(Value::Num(0.0)),
+    };
+    let n = match pred {
+        None => items.len(),
+        Some((cmp, rhs)) => {
+            let mut hits = 0;
+            for item in &items {
+                if cmp_eval(&cmp, &json_to_value(item, doc), &rhs)? {
+                    hits += 1;
+                }
+            }
+            hits
+        }
+    };
+    Ok(Value::Num(n as f64))
+}
+
+fn parent(rt: &Runtime, doc: &Document, path: &str) -> Result<Value> {
+    let segs = query_path(path)?;
+    if segs.iter().any(|s| matches!(s, Segment::Wildcard)) {
+        bail!("E_BAD_ARG: get(parent:...) needs a path without [*]");
+    }
+    let Some((_, container)) = segs.split_last() else {
+        return Ok(Value::Unit);
+    };
+    if navigate_read(rt, &doc.json, &segs).is_none() {
+        return Ok(Value::Unit);
+    }
+    Ok(navigate_read(rt, &doc.json, container)
+        .map(|v| json_to_value(v, doc))
+        .unwrap_or(Value::Unit))
+}
+
+// Right-hand side of a count predicate: quoted string, number, bool, or variable.
+fn literal_value(rt: &Runtime, src: &str) -> Result<Value> {
+    if src.len() >= 2 && src.starts_with('"') && src.ends_with('"') {
+        return Ok(Value::Str(serde_json::from_str(src)?));
+    }
+    if let Ok(n) = src.parse::<f64>() {
+        return Ok(Value::Num(n));
+    }
+    Ok(match src {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        other => rt
+            .get_var(other)
+            .unwrap_or_else(|| Value::Str(other.to_string())),
+    })
+}
+
+// Resolves a path that may contain [*]; plain paths defer to navigate_read.
+fn read_path(rt: &Runtime, root: &JsonValue, segs: &[Segment], compact: bool) -> Option<JsonValue> {
+    let Some(star) = segs.iter().position(|s| matches!(s, Segment::Wildcard)) else {
+        return navigate_read(rt, root, segs).cloned();
+    };
+    let arr = navigate_read(rt, root, &segs[..star])?.as_array()?;
+    let rest = &segs[star + 1..];
+    let mut out = Vec::with_capacity(arr.len());
+    for item in arr {
+        match read_path(rt, item, rest, compact) {
+            Some(v) => out.push(v),
+            None if compact => {}
+            None => out.push(JsonValue::Null),
+        }
+    }
+    Some(JsonValue::Array(out))
+}
+
 fn navigate_read<'a>(rt: &Runtime, root: &'a JsonValue, segs: &[Segment]) -> Option<&'a JsonValue> {
     let mut cur = root;
     for seg in segs {
@@ -138,12 +281,15 @@ fn navigate_read<'a>(rt: &Runtime, root: &'a JsonValue, segs: &[Segment]) -> Opt
                 let arr = cur.as_array()?;
                 cur = arr.get(*i)?;
             }
+            // read_path splits on wildcards before navigating
+            Segment::Wildcard => return None,
         }
     }
     Some(cur)
 }
 
-fn json_to_value(v: &JsonValue, meta: &Document) -> Value {
+// Scalars become plain values; arrays/objects become Docs carrying `meta`'s path and root
+pub fn json_to_value(v: &JsonValue, meta: &Document) -> Value {
     match v {
         JsonValue::Null => Value::Unit,
         JsonValue::Bool(b) => Value::Bool(*b),
@@ -189,6 +335,44 @@ mod tests {
         )
     }
 
+    #[test]
+    fn get_parent_returns_the_record() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let doc = mk_doc(serde_json::json!({"users": [
+            {"id": 1, "contact": {"email": "a@x.io"}},
+            {"id": 2, "contact": {"email": "b@x.io"}}
+        ]}));
+        rt.set_var("doc", Value::Doc(doc))?;
+
+        let node = crate::router::parse("[get(parent:users[1].id)@doc]").map_err(anyhow::Error::new)?;
+        match rt.eval(&node)? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!({"id": 2, "contact": {"email": "b@x.io"}})),
+            other => bail!("expected doc, got {other:?}"),
+        }
+        let node = crate::router::parse("[get(parent:users[5].id)@doc]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Unit);
+        Ok(())
+    }
+
+    #[test]
+    fn keys_with_literal_dots() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let doc = mk_doc(serde_json::json!({"config.version": "2.1", "config": {"version": "nested"}}));
+        rt.set_var("doc", Value::Doc(doc))?;
+
+        for script in [r#"[get(config\.version)@doc]"#, r#"[get(["config.version"])@doc]"#] {
+            let node = crate::router::parse(script).map_err(anyhow::Error::new)?;
+            assert_eq!(rt.eval(&node)?, Value::Str("2.1".into()), "{script}");
+        }
+        let node = crate::router::parse("[get(config.version)@doc]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Str("nested".into()));
+
+        let node = crate::router::parse(r#"[mod@doc]{[set(["config.version"])@"v3"]}>[get(config\.version)@doc]"#)
+            .map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Str("v3".into()));
+        Ok(())
+    }
+
     #[test]
     fn get_with_variable_index() -> Result<()> {
         let mut rt = Runtime::new()?;
@@ -220,4 +404,44 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn get_plucks_field_across_array() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let doc = mk_doc(serde_json::json!({
+            "items": [{"price": 3}, {"name": "free"}, {"price": 7}]
+        }));
+        rt.set_var("doc", Value::Doc(doc))?;
+
+        let node = crate::router::parse("[get(items[*].price)@doc]").map_err(anyhow::Error::new)?;
+        match rt.eval(&node)? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!([3, null, 7])),
+            other => bail!("expected doc, got {other:?}"),
+        }
+
+        let node = crate::router::parse("[get(items[*].price, compact)@doc]")
+            .map_err(anyhow::Error::new)?;
+        match rt.eval(&node)? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!([3, 7])),
+            other => bail!("expected doc, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn count_elements_and_matches() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let doc = mk_doc(serde_json::json!({
+            "items": [{"active": true}, {"active": false}, {"active": true}, {}]
+        }));
+        rt.set_var("doc", Value::Doc(doc))?;
+
+        let node = crate::router::parse("[count(items[*])@doc]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Num(4.0));
+
+        let node = crate::router::parse("[count(items[*].active==true)@doc]")
+            .map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Num(2.0));
+        Ok(())
+    }
 }
@@ -0,0 +1,74 @@
+use anyhow::{Result, bail};
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::kernel::ast::Arg;
+use crate::kernel::fs_guard::resolve;
+use crate::kernel::values::{Document, Value};
+use crate::kernel::{Packet, Runtime};
+
+// [readlines@/app.log]           -> Doc array of the file's lines (no trailing \n / \r\n)
+// [readlines(nonempty)@/app.log] -> same, skipping blank and whitespace-only lines
+// The file is read through a buffered reader one line at a time, so only the lines
+// themselves are kept, never the whole text.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let nonempty = match crate::router::extract_paren(&p.op).map(str::trim) {
+        None => false,
+        Some("nonempty") => true,
+        Some(other) => bail!("E_MODE_UNKNOWN: readlines mode unsupported: {other}"),
+    };
+    let raw = match &p.arg {
+        Some(Arg::Str(s)) => s,
+        _ => bail!("E_BAD_ARG: readlines needs @<path>"),
+    };
+    let root = rt
+        .effective_root
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+    let candidate = match raw.strip_prefix('/') {
+        Some(rel) => Path::new(rel).to_path_buf(),
+        None => rt.cwd.join(raw),
+    };
+    let path = resolve(&root, &candidate)?;
+
+    let mut lines = Vec::new();
+    for line in BufReader::new(fs::File::open(&path)?).lines() {
+        let line = line?;
+        if nonempty && line.trim().is_empty() {
+            continue;
+        }
+        lines.push(serde_json::Value::String(line));
+    }
+    let json = serde_json::Value::Array(lines);
+    Ok(Value::Doc(Document::scratch(root, &rt.cwd, "_readlines.json", json)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    #[test]
+    fn reads_lines_and_skips_blanks() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_readlines_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        fs::write(base.join("app.log"), "start\r\n\nstop\n")?;
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk"))?;
+
+        let node = router::parse("[readlines@/app.log]").map_err(anyhow::Error::new)?;
+        match rt.eval(&node)? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!(["start", "", "stop"])),
+            other => bail!("expected doc, got {other:?}"),
+        }
+        let node = router::parse("[readlines(nonempty)@/app.log]").map_err(anyhow::Error::new)?;
+        match rt.eval(&node)? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!(["start", "stop"])),
+            other => bail!("expected doc, got {other:?}"),
+        }
+        let _ = fs::remove_dir_all(&base);
+        Ok(())
+    }
+}
@@ -4,38 +4,127 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use tempfile::NamedTempFile;
 
-use crate::kernel::ast::Arg;
-use crate::kernel::values::{Document, Value};
+use crate::kernel::ast::{Arg, Node};
+use crate::kernel::fs_guard::{ensure_parent_dirs, resolve};
+use crate::kernel::values::{Document, Value, num_to_json};
 use crate::kernel::{Packet, Runtime};
 
+// [save@doc]                     -> register the piped doc, later calls write it back to its file
+// [save(/out/result.json)@doc]    -> write a copy to another path in the red box (format from its
+//                                    extension); `doc` keeps pointing at its own file
+// [save(/out/result.json, rebind)@doc] -> same, then `doc` points at the new file from now on
+// [save(/out/report.md)@report]  -> a string (var or piped) is written verbatim, no serialization
+// [save(patch:/users/0/name)@"New"]{[file@/data.json]} -> set one JSON Pointer in a file
+// Missing parent directories are created, as long as they stay inside the red root.
+// In dry-run mode nothing is written; the would-be target is reported on stderr instead.
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if let Some(ptr) = crate::router::extract_paren(&p.op).and_then(|o| o.trim().strip_prefix("patch:")) {
+        return patch(rt, p, ptr.trim());
+    }
     let handle = match &p.arg {
         Some(Arg::Ident(id)) => id.as_str(),
-        _ => bail!("save needs @<ident>"),
+        _ => bail!("E_BAD_ARG: save needs @<ident>"),
+    };
+    let (target, rebind) = match crate::router::extract_paren(&p.op) {
+        Some(raw) => {
+            let (raw, rebind) = match raw.rsplit_once(',') {
+                Some((path, opt)) if opt.trim() == "rebind" => (path, true),
+                Some((_, opt)) => bail!("E_MODE_UNKNOWN: unknown save option '{}'", opt.trim()),
+                None => (raw, false),
+            };
+            (Some(resolve_target(rt, raw.trim())?), rebind)
+        }
+        None => (None, false),
     };
 
-    if let Some(Value::Doc(doc)) = rt.get_var(handle) {
-        // already registered -> attempt write
-        let mut doc = doc;
-        if doc.json == doc.last_json {
-            return Ok(Value::Doc(doc));
+    if let Some(path) = &target {
+        let text = match (rt.get_var(handle), &rt.last) {
+            (Some(Value::Str(s)), _) => Some(s),
+            (None | Some(Value::Unit), Value::Str(s)) => Some(s.clone()),
+            _ => None,
+        };
+        if let Some(text) = text {
+            let root = rt
+                .effective_root
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+            if rt.dry_run {
+                eprintln!("[dry-run] would save {} bytes to {}", text.len(), path.display());
+            } else {
+                ensure_parent_dirs(root, path)?;
+                write_atomic(path, text.as_bytes())?;
+            }
+            rt.set_var(handle, Value::Str(text.clone()))?;
+            return Ok(Value::Str(text));
         }
+    }
 
-        let current_mtime = fs::metadata(&doc.path)?.modified()?;
-        if current_mtime != doc.mtime {
-            bail!("changed_on_disk");
+    let registered = match rt.get_var(handle) {
+        Some(Value::Doc(doc)) => Some(doc),
+        _ => None,
+    };
+    if registered.is_some() || target.is_some() {
+        // already registered (or given an explicit target) -> attempt write
+        let mut doc = match registered {
+            Some(doc) => doc,
+            None => match rt.last.clone() {
+                Value::Doc(doc) => doc,
+                _ => bail!("E_NO_DOC: save needs document in pipeline"),
+            },
+        };
+        match target {
+            Some(path) if !rebind => {
+                // export: write a copy, leave the stored doc (and its path) alone
+                let mut copy = doc.clone();
+                if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                    copy.ext = ext.to_ascii_lowercase();
+                }
+                let bytes = encode(&copy)?;
+                let root = rt
+                    .effective_root
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+                if rt.dry_run {
+                    eprintln!("[dry-run] would save {} bytes to {}", bytes.len(), path.display());
+                } else {
+                    ensure_parent_dirs(root, &path)?;
+                    write_atomic(&path, &bytes)?;
+                }
+                return Ok(Value::Doc(doc));
+            }
+            Some(path) => {
+                if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+                    doc.ext = ext.to_ascii_lowercase();
+                }
+                doc.path = path;
+            }
+            None => {
+                if doc.json == doc.last_json {
+                    return Ok(Value::Doc(doc));
+                }
+                // a doc that was never written yet has nothing on disk to conflict with
+                if doc.path.exists() {
+                    let current_mtime = fs::metadata(&doc.path)?.modified()?;
+                    if current_mtime != doc.mtime {
+                        bail!("E_CHANGED_ON_DISK: file changed since it was loaded");
+                    }
+                }
+            }
         }
 
         let bytes = encode(&doc)?;
 
-        let tmp_path = temp_path(&doc.path);
-        {
-            let dir = tmp_path.parent().unwrap_or(Path::new("."));
-            let mut tmp = NamedTempFile::new_in(dir)?;
-            tmp.write_all(&bytes)?;
-            tmp.persist(&tmp_path)?;
+        let root = rt
+            .effective_root
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+        if rt.dry_run {
+            eprintln!("[dry-run] would save {} bytes to {}", bytes.len(), doc.path.display());
+            rt.set_var(handle, Value::Doc(doc.clone()))?;
+            return Ok(Value::Doc(doc));
         }
-        fs::rename(&tmp_path, &doc.path)?;
+        ensure_parent_dirs(root, &doc.path)?;
+        write_atomic(&doc.path, &bytes)?;
         let meta = fs::metadata(&doc.path)?;
         doc.mtime = meta.modified()?;
         doc.last_json = doc.json.clone();
@@ -48,9 +137,124 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
                 rt.set_var(handle, Value::Doc(doc.clone()))?;
                 Ok(Value::Doc(doc))
             }
-            _ => bail!("save needs document in pipeline"),
+            _ => bail!("E_NO_DOC: save needs document in pipeline"),
+        }
+    }
+}
+
+// Loads the file named by the body's [file@path], sets the pointed value (the @arg, else the
+// piped value) and writes it back atomically. Refuses with E_CHANGED_ON_DISK if the file
+// changed while it was being patched. A pointer may name an existing value, a new key in an
+// existing object, or one past the end (or `-`) of an existing array.
+fn patch(rt: &mut Runtime, p: &Packet, ptr: &str) -> Result<Value> {
+    let tokens = crate::packets::load::parse_pointer(ptr)?;
+    let file = p.body.iter().flatten().find_map(|n| match n {
+        Node::Packet(f) if f.ns.is_none() && f.op == "file" => match &f.arg {
+            Some(Arg::Str(path)) => Some(path.clone()),
+            _ => None,
+        },
+        _ => None,
+    });
+    let Some(file) = file else {
+        bail!("E_BAD_ARG: save(patch:...) needs a body with [file@/path]");
+    };
+    let value = match &p.arg {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    let value = value_to_json(value)?;
+
+    let load = Packet {
+        ns: None,
+        op: "load".into(),
+        arg: Some(Arg::Str(file)),
+        body: None,
+        span: p.span,
+    };
+    let mut doc = match crate::packets::load::handle(rt, &load)? {
+        Value::Doc(doc) => doc,
+        other => bail!("E_NO_DOC: save(patch:...) could not load a document, got {other}"),
+    };
+    set_pointer(&mut doc.json, &tokens, value, ptr)?;
+    let bytes = encode(&doc)?;
+    if rt.dry_run {
+        eprintln!("[dry-run] would patch {ptr} in {}", doc.path.display());
+        return Ok(Value::Doc(doc));
+    }
+    if fs::metadata(&doc.path)?.modified()? != doc.mtime {
+        bail!("E_CHANGED_ON_DISK: file changed since it was loaded");
+    }
+    write_atomic(&doc.path, &bytes)?;
+    doc.mtime = fs::metadata(&doc.path)?.modified()?;
+    doc.last_json = doc.json.clone();
+    Ok(Value::Doc(doc))
+}
+
+fn set_pointer(json: &mut serde_json::Value, tokens: &[String], value: serde_json::Value, ptr: &str) -> Result<()> {
+    let Some((last, parents)) = tokens.split_last() else {
+        *json = value;
+        return Ok(());
+    };
+    let mut cur = json;
+    for tok in parents {
+        let next = match cur {
+            serde_json::Value::Object(map) => map.get_mut(tok),
+            serde_json::Value::Array(items) => tok.parse::<usize>().ok().and_then(|i| items.get_mut(i)),
+            _ => None,
+        };
+        cur = match next {
+            Some(next) => next,
+            None => bail!("E_PATH_MISSING: pointer {ptr} not found"),
+        };
+    }
+    match cur {
+        serde_json::Value::Object(map) => {
+            map.insert(last.clone(), value);
         }
+        serde_json::Value::Array(items) => match last.parse::<usize>() {
+            Ok(i) if i < items.len() => items[i] = value,
+            Ok(i) if i == items.len() => items.push(value),
+            _ if last == "-" => items.push(value),
+            _ => bail!("E_PATH_MISSING: pointer {ptr} not found"),
+        },
+        _ => bail!("E_PATH_MISSING: pointer {ptr} not found"),
     }
+    Ok(())
+}
+
+fn value_to_json(v: Value) -> Result<serde_json::Value> {
+    Ok(match v {
+        Value::Unit => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(b),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
+        Value::Str(s) => serde_json::Value::String(s),
+        Value::Doc(d) => d.json,
+    })
+}
+
+// write to a temp file beside `path`, then rename over it
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let tmp_path = temp_path(path);
+    {
+        let dir = tmp_path.parent().unwrap_or(Path::new("."));
+        let mut tmp = NamedTempFile::new_in(dir)?;
+        tmp.write_all(bytes)?;
+        tmp.persist(&tmp_path)?;
+    }
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn resolve_target(rt: &Runtime, raw: &str) -> Result<PathBuf> {
+    let root = rt
+        .effective_root
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
+    let candidate = match raw.strip_prefix('/') {
+        Some(rel) => PathBuf::from(rel),
+        None => rt.cwd.join(raw),
+    };
+    resolve(root, &candidate)
 }
 
 fn encode(doc: &Document) -> Result<Vec<u8>> {
@@ -61,7 +265,7 @@ fn encode(doc: &Document) -> Result<Vec<u8>> {
             let raw = serde_json::to_string_pretty(&doc.json)?;
             cleanup_trailing_commas(&raw)
         }
-        other => bail!("format_unsupported: {other}"),
+        other => bail!("E_FORMAT_UNKNOWN: unsupported format '{other}'"),
     };
     Ok(s.into_bytes())
 }
@@ -87,8 +291,8 @@ fn cleanup_trailing_commas(s: &str) -> String {
     out
 }
 
-fn temp_path(path: &PathBuf) -> PathBuf {
-    let mut tmp = path.clone();
+fn temp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.to_path_buf();
     if let Some(ext) = tmp.extension() {
         let mut e = ext.to_os_string();
         e.push(".tmp");
@@ -126,6 +330,127 @@ mod tests {
         fs::remove_dir_all(base).unwrap();
     }
 
+    #[test]
+    fn creates_missing_parent_dirs() {
+        let base = std::env::temp_dir().join(format!("tgsk_save_dirs_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(base.join("config.json"), "{\"a\":1}").unwrap();
+        let script = base.join("main.tgsk");
+        fs::write(&script, "[load@/config.json]>[save@cfg]>[save(/build/out/result.json)@cfg]").unwrap();
+
+        let ast = crate::router::parse(&fs::read_to_string(&script).unwrap()).unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        rt.eval(&ast).unwrap();
+        assert!(base.join("build").join("out").is_dir());
+        let out: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(base.join("build/out/result.json")).unwrap()).unwrap();
+        assert_eq!(out, serde_json::json!({"a": 1}));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn target_path_does_not_rebind_unless_asked() {
+        let base = std::env::temp_dir().join(format!("tgsk_save_rebind_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(base.join("config.json"), "{\"a\":1}").unwrap();
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+        let path_of = |rt: &Runtime| match rt.get_var("cfg") {
+            Some(Value::Doc(d)) => fs::canonicalize(d.path).unwrap(),
+            other => panic!("expected doc, got {other:?}"),
+        };
+
+        let ast = crate::router::parse("[load@/config.json]>[save@cfg]>[save(/copy.json)@cfg]").unwrap();
+        rt.eval(&ast).unwrap();
+        assert!(base.join("copy.json").exists());
+        assert_eq!(path_of(&rt), fs::canonicalize(base.join("config.json")).unwrap());
+
+        let ast = crate::router::parse("[save(/moved.json, rebind)@cfg]").unwrap();
+        rt.eval(&ast).unwrap();
+        assert_eq!(path_of(&rt), fs::canonicalize(base.join("moved.json")).unwrap());
+
+        let ast = crate::router::parse("[save(/x.json, sideways)@cfg]").unwrap();
+        assert!(rt.eval(&ast).is_err());
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn dry_run_skips_the_write() {
+        let base = std::env::temp_dir().join(format!("tgsk_save_dry_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(base.join("config.json"), "{\"a\":1}").unwrap();
+        let script = base.join("main.tgsk");
+
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        rt.dry_run = true;
+        let ast = crate::router::parse("[load@/config.json]>[save@cfg]>[save(/out/result.json)@cfg]>[msg@\"hi\"]>[save(/note.txt)@note]")
+            .unwrap();
+        rt.eval(&ast).unwrap();
+        assert!(!base.join("out").exists());
+        assert!(!base.join("note.txt").exists());
+        assert!(matches!(rt.get_var("cfg"), Some(Value::Doc(_))));
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn patch_sets_one_pointer_and_keeps_the_rest() {
+        let base = std::env::temp_dir().join(format!("tgsk_save_patch_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        fs::write(
+            base.join("data.json"),
+            "{\"users\":[{\"name\":\"Ann\",\"age\":30},{\"name\":\"Bo\"}],\"meta\":{\"v\":1}}",
+        )
+        .unwrap();
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk")).unwrap();
+
+        let ast = crate::router::parse("[save(patch:/users/0/name)@\"New\"]{[file@/data.json]}").unwrap();
+        rt.eval(&ast).unwrap();
+        let out: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(base.join("data.json")).unwrap()).unwrap();
+        assert_eq!(
+            out,
+            serde_json::json!({"users": [{"name": "New", "age": 30}, {"name": "Bo"}], "meta": {"v": 1}})
+        );
+
+        let ast = crate::router::parse("[save(patch:/nope/x)@1]{[file@/data.json]}").unwrap();
+        let err = rt.eval(&ast).unwrap_err();
+        assert!(err.to_string().contains("E_PATH_MISSING"), "{err}");
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
+    #[test]
+    fn writes_strings_verbatim() {
+        let base = std::env::temp_dir().join(format!("tgsk_save_text_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        let script = base.join("main.tgsk");
+
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        let report = "# Report\n\n- one\n- \"two\"\n";
+        rt.set_var("report", Value::Str(report.into())).unwrap();
+        let ast = crate::router::parse("[save(/out/report.md)@report]").unwrap();
+        rt.eval(&ast).unwrap();
+        assert_eq!(fs::read_to_string(base.join("out/report.md")).unwrap(), report);
+
+        let ast = crate::router::parse("[msg@\"line 1\nline 2\"]>[save(/notes.txt)@notes]").unwrap();
+        rt.eval(&ast).unwrap();
+        assert_eq!(fs::read_to_string(base.join("notes.txt")).unwrap(), "line 1\nline 2");
+
+        fs::remove_dir_all(base).unwrap();
+    }
+
     #[test]
     fn deleting_last_key_writes_valid_json() {
         let base = std::env::temp_dir().join(format!("tgsk_trailing_test_{}", std::process::id()));
@@ -149,4 +474,41 @@ mod tests {
 
         fs::remove_dir_all(base).unwrap();
     }
+
+    #[test]
+    fn saves_keys_in_insertion_order() {
+        let base = std::env::temp_dir().join(format!("tgsk_order_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        let script = base.join("main.tgsk");
+        fs::write(
+            &script,
+            "[load@/config.toml]>[save@cfg]>[mod@cfg]{[set(mid)@9]}>[save@cfg]",
+        )
+        .unwrap();
+
+        let mut outputs = Vec::new();
+        for _ in 0..2 {
+            fs::write(
+                base.join("config.toml"),
+                "zeta = 1\nalpha = 2\n\n[server]\nport = 80\nhost = \"a\"\n",
+            )
+            .unwrap();
+            let ast = crate::router::parse(&fs::read_to_string(&script).unwrap()).unwrap();
+            let mut rt = Runtime::from_entry(&script).unwrap();
+            let _ = rt.eval(&ast).unwrap();
+            outputs.push(fs::read_to_string(base.join("config.toml")).unwrap());
+        }
+
+        assert_eq!(outputs[0], outputs[1]);
+        let out = &outputs[0];
+        let zeta = out.find("zeta").unwrap();
+        let alpha = out.find("alpha").unwrap();
+        let port = out.find("port").unwrap();
+        let host = out.find("host").unwrap();
+        assert!(zeta < alpha && port < host, "order lost:\n{out}");
+
+        fs::remove_dir_all(base).unwrap();
+    }
 }
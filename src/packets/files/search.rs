@@ -1,6 +1,7 @@
 use anyhow::{Result, bail};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use serde_yaml::Value as YamlValue;
 use toml::Value as TomlValue;
@@ -9,6 +10,7 @@ use crate::kernel::Runtime;
 use crate::kernel::ast::{Arg, Node, Packet};
 use crate::kernel::fs_guard::resolve;
 use crate::kernel::values::{Document, Value};
+use crate::packets::query::{self, Segment};
 use crate::router; // for parsing helpers
 
 /// Opens a file, detects its format, and returns the value at the requested path.
@@ -20,12 +22,12 @@ use crate::router; // for parsing helpers
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     // Parse path from op like search(path)
     let raw_path =
-        router::extract_paren(&p.op).ok_or_else(|| anyhow::anyhow!("search needs (path)"))?;
+        router::extract_paren(&p.op).ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: search needs (path)"))?;
 
     let root = rt
         .effective_root
-        .as_ref()
-        .ok_or_else(|| anyhow::anyhow!("no red.tgsk root"))?;
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: no red.tgsk root"))?;
 
     let rel = if raw_path.starts_with('/') {
         &raw_path[1..]
@@ -37,25 +39,25 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     } else {
         rt.cwd.join(rel)
     };
-    let path = resolve(root, &candidate)?;
+    let path = resolve(&root, &candidate)?;
     let ext = path
         .extension()
         .and_then(|e| e.to_str())
         .unwrap_or("")
         .to_lowercase();
-    let content = fs::read_to_string(&path)?;
 
     match ext.as_str() {
         // TagSpeak file: search for a packet snippet
         "tgsk" | "" => {
+            let content = fs::read_to_string(&path)?;
             let ast = router::parse(&content).map_err(anyhow::Error::new)?;
             let label_src = match &p.arg {
                 Some(Arg::Str(s)) => s,
-                _ => bail!("search needs @\"[packet]\""),
+                _ => bail!("E_BAD_ARG: search needs @\"[packet]\""),
             };
             let target = router::parse_single_packet(label_src)?;
             let pkt = find_packet(&ast, &target.ns, &target.op)
-                .ok_or_else(|| anyhow::anyhow!("packet_not_found"))?;
+                .ok_or_else(|| anyhow::anyhow!("E_NOT_FOUND: packet not found"))?;
             if let Some(arg) = &pkt.arg {
                 rt.resolve_arg(arg)
             } else if let Some(body) = &pkt.body {
@@ -66,31 +68,53 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         }
         // Structured data: traverse by key path
         "json" | "yaml" | "yml" | "toml" => {
-            let json_val: serde_json::Value = match ext.as_str() {
-                "json" => serde_json::from_str(&content)?,
-                "yaml" | "yml" => {
-                    let yv: YamlValue = serde_yaml::from_str(&content)?;
-                    serde_json::to_value(yv)?
-                }
-                "toml" => {
-                    let tv: TomlValue = toml::from_str(&content)?;
-                    serde_json::to_value(tv)?
-                }
-                _ => unreachable!(),
-            };
-
+            let json_val = parsed_file(rt, &path, &ext)?;
             let key_path = match &p.arg {
                 Some(Arg::Str(s)) => s,
-                _ => bail!("search needs @\"key.path\""),
+                _ => bail!("E_BAD_ARG: search needs @\"key.path\""),
             };
             let val = traverse_json(&json_val, key_path)
-                .ok_or_else(|| anyhow::anyhow!("path_not_found"))?;
-            json_to_value(val, &path, &ext, root)
+                .ok_or_else(|| anyhow::anyhow!("E_PATH_MISSING: path not found"))?;
+            json_to_value(val, &path, &ext, &root)
         }
-        other => bail!(format!("unsupported_ext:{other}")),
+        other => bail!("E_FORMAT_UNKNOWN: unsupported extension '{other}'"),
     }
 }
 
+// Parsed structured files are kept per runtime, keyed by path and checked against the
+// file's mtime, so repeated searches of an unchanged file skip the read and parse.
+// Forks share the map; a fork that parses something new gets its own copy of the map
+// (the parsed trees themselves stay shared).
+fn parsed_file(rt: &mut Runtime, path: &Path, ext: &str) -> Result<Arc<serde_json::Value>> {
+    let mtime = fs::metadata(path)?.modified()?;
+    if let Some((cached_mtime, json)) = rt.search_cache.get(path)
+        && *cached_mtime == mtime
+    {
+        return Ok(Arc::clone(json));
+    }
+    let json = match ext {
+        "json" => crate::packets::files::load::read_json(rt, path)?,
+        _ => parse_text(&fs::read_to_string(path)?, ext)?,
+    };
+    let json = Arc::new(json);
+    Arc::make_mut(&mut rt.search_cache).insert(path.to_path_buf(), (mtime, Arc::clone(&json)));
+    Ok(json)
+}
+
+fn parse_text(content: &str, ext: &str) -> Result<serde_json::Value> {
+    Ok(match ext {
+        "yaml" | "yml" => {
+            let yv: YamlValue = serde_yaml::from_str(content)?;
+            serde_json::to_value(yv)?
+        }
+        "toml" => {
+            let tv: TomlValue = toml::from_str(content)?;
+            serde_json::to_value(tv)?
+        }
+        other => bail!("E_FORMAT_UNKNOWN: unsupported extension '{other}'"),
+    })
+}
+
 fn find_packet<'a>(node: &'a Node, ns: &Option<String>, op: &str) -> Option<&'a Packet> {
     match node {
         Node::Packet(pkt) => {
@@ -129,13 +153,12 @@ fn traverse_json<'a>(value: &'a serde_json::Value, path: &str) -> Option<&'a ser
     if path.is_empty() {
         return Some(cur);
     }
-    for seg in path.split('.') {
-        cur = match cur {
-            serde_json::Value::Object(map) => map.get(seg)?,
-            serde_json::Value::Array(list) => {
-                let idx: usize = seg.parse().ok()?;
-                list.get(idx)?
-            }
+    for seg in query::parse_path(path).ok()? {
+        cur = match (cur, seg) {
+            (serde_json::Value::Object(map), Segment::Key(k)) => map.get(&k)?,
+            // `items.0` reads like `items[0]`
+            (serde_json::Value::Array(list), Segment::Key(k)) => list.get(k.parse::<usize>().ok()?)?,
+            (serde_json::Value::Array(list), Segment::Index(i)) => list.get(i)?,
             _ => return None,
         };
     }
@@ -190,6 +213,15 @@ mod tests {
         fs::remove_dir_all(base).unwrap();
     }
 
+    #[test]
+    fn paths_follow_the_query_grammar() {
+        let json = serde_json::json!({"cfg": {"a.b": [10, 20]}});
+        for path in [r#"cfg["a.b"][1]"#, r"cfg.a\.b.1", r"cfg.a\.b[1]"] {
+            assert_eq!(traverse_json(&json, path), Some(&serde_json::json!(20)), "{path}");
+        }
+        assert_eq!(traverse_json(&json, "cfg.a.b"), None);
+    }
+
     #[test]
     fn search_json_value() {
         let base =
@@ -220,4 +252,32 @@ mod tests {
 
         fs::remove_dir_all(base).unwrap();
     }
+
+    #[test]
+    fn reuses_parsed_file_until_mtime_changes() {
+        let base = std::env::temp_dir().join(format!("tgsk_search_cache_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base).unwrap();
+        fs::write(base.join("red.tgsk"), "").unwrap();
+        let data = base.join("data.json");
+        fs::write(&data, "{\"n\":1}").unwrap();
+        let mtime = fs::metadata(&data).unwrap().modified().unwrap();
+        let script = base.join("main.tgsk");
+
+        let ast = crate::router::parse("[search(/data.json)@\"n\"]").unwrap();
+        let mut rt = Runtime::from_entry(&script).unwrap();
+        assert_eq!(rt.eval(&ast).unwrap(), Value::Num(1.0));
+        assert!(Arc::ptr_eq(&rt.search_cache, &rt.fork().search_cache));
+
+        // same mtime: the cached parse wins, so the new content is not read
+        fs::write(&data, "{\"n\":2}").unwrap();
+        fs::File::options().write(true).open(&data).unwrap().set_modified(mtime).unwrap();
+        assert_eq!(rt.eval(&ast).unwrap(), Value::Num(1.0));
+
+        let later = mtime + std::time::Duration::from_secs(5);
+        fs::File::options().write(true).open(&data).unwrap().set_modified(later).unwrap();
+        assert_eq!(rt.eval(&ast).unwrap(), Value::Num(2.0));
+
+        fs::remove_dir_all(base).unwrap();
+    }
 }
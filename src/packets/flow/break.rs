@@ -0,0 +1,48 @@
+use crate::kernel::runtime::FlowSignal;
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::{Result, bail};
+
+// [break]    -> leave the innermost [loop]/[iter]; the outer loop carries on
+// [break(2)] -> leave two nested loops at once
+// The loop's result is the last completed iteration's value.
+pub fn handle(_rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let levels = match crate::router::extract_paren(&p.op) {
+        None => 1,
+        Some(raw) => match raw.trim().parse::<usize>() {
+            Ok(n) if n >= 1 => n,
+            _ => bail!("E_BAD_ARG: break levels must be a whole number >= 1, got '{raw}'"),
+        },
+    };
+    Err(FlowSignal::Break(levels).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn break_leaves_only_innermost_loop() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let script = "[int@0]>[store@outer]>[int@0]>[store@inner]>[loop@3]{[math@outer+1]>[store@outer]>[loop@5]{[math@inner+1]>[store@inner]>[break]}}";
+        eval(&mut rt, script)?;
+        assert_eq!(rt.get_num("outer"), Some(3.0));
+        assert_eq!(rt.get_num("inner"), Some(3.0));
+        Ok(())
+    }
+
+    #[test]
+    fn break_two_leaves_both_loops() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let script = "[int@0]>[store@outer]>[int@0]>[store@inner]>[loop@3]{[math@outer+1]>[store@outer]>[loop@5]{[math@inner+1]>[store@inner]>[break(2)]}}>[msg@\"after\"]";
+        assert_eq!(eval(&mut rt, script)?, Value::Str("after".into()));
+        assert_eq!(rt.get_num("outer"), Some(1.0));
+        assert_eq!(rt.get_num("inner"), Some(1.0));
+        Ok(())
+    }
+}
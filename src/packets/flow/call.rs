@@ -1,8 +1,17 @@
 use crate::kernel::ast::Arg;
 use crate::kernel::ast::Node;
+use crate::kernel::runtime::FlowSignal;
+use crate::kernel::values::{Document, num_to_json};
 use crate::kernel::{Packet, Runtime, Value};
+use crate::packets::coalesce::resolve_token;
+use crate::packets::funct::{Param, split_args};
 use anyhow::{Result, bail};
+use std::path::PathBuf;
+use std::time::SystemTime;
 
+// [call@step]                -> run the funct's block
+// [call("Ana", "Hi")@greet]  -> bind args to the funct's params first; args are quoted
+// strings, numbers, true/false, or var names. Params get their old values back afterwards.
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let name = match p.arg.as_ref() {
         Some(Arg::Ident(id)) => id.as_str().to_string(),
@@ -18,13 +27,100 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     if rt.call_depth >= rt.max_call_depth {
         bail!("E_CALL_DEPTH_EXCEEDED: max recursion depth {} reached", rt.max_call_depth);
     }
+    let args = call_args(rt, p)?;
+    let bindings = match rt.funct_params.get(&name).cloned() {
+        Some(params) => bind_params(rt, &name, &params, args)?,
+        None if args.is_empty() => Vec::new(),
+        None => bail!("E_ARITY: funct '{name}' takes no args"),
+    };
+    let saved: Vec<(String, Option<Value>)> = bindings
+        .iter()
+        .map(|(param, _)| (param.clone(), rt.vars.get(param).cloned()))
+        .collect();
+    for (param, val) in bindings {
+        rt.set_var(&param, val)?;
+    }
     rt.call_depth += 1;
+    let mark = rt.deferred.len();
     let out = rt.eval(&Node::Block(body));
+    // the funct's [defer] blocks run before its params are restored, even on error/[return]
+    let out = rt.run_deferred(mark, out);
     rt.call_depth = rt.call_depth.saturating_sub(1);
-    let out = out?;
+    for (param, prev) in saved {
+        match prev {
+            Some(v) => rt.vars.insert(param, v),
+            None => rt.vars.remove(&param),
+        };
+    }
+    // a [return] anywhere inside the body (nested blocks, loops, ifs) ends this call only
+    match out {
+        Err(err) => match err.downcast::<FlowSignal>() {
+            Ok(FlowSignal::Return(v)) => Ok(v),
+            // loops outside the funct are not the body's to break
+            Ok(FlowSignal::Break(_)) => bail!("E_BREAK_OUTSIDE_LOOP: [break] escaped funct '{name}'"),
+            Ok(other) => Err(other.into()),
+            Err(err) => Err(err),
+        },
+        ok => ok,
+    }
+}
+
+fn call_args(rt: &Runtime, p: &Packet) -> Result<Vec<Value>> {
+    let Some(src) = crate::router::extract_paren(&p.op) else {
+        return Ok(Vec::new());
+    };
+    let parts = split_args(src);
+    if parts.iter().all(|s| s.trim().is_empty()) {
+        return Ok(Vec::new());
+    }
+    parts.into_iter().map(|s| resolve_token(rt, s.trim())).collect()
+}
+
+// Only required params count toward arity; defaults fill gaps, ...rest takes the extras.
+fn bind_params(rt: &Runtime, name: &str, params: &[Param], args: Vec<Value>) -> Result<Vec<(String, Value)>> {
+    let required = params.iter().filter(|p| p.default.is_none() && !p.variadic).count();
+    let positional = params.iter().filter(|p| !p.variadic).count();
+    let variadic = params.iter().any(|p| p.variadic);
+    if args.len() < required {
+        bail!("E_ARITY: funct '{name}' needs at least {required} args, got {}", args.len());
+    }
+    if !variadic && args.len() > positional {
+        bail!("E_ARITY: funct '{name}' takes at most {positional} args, got {}", args.len());
+    }
+    let mut args = args.into_iter();
+    let mut out = Vec::with_capacity(params.len());
+    for param in params {
+        let val = if param.variadic {
+            let rest = args.by_ref().map(value_to_json).collect::<Result<Vec<_>>>()?;
+            Value::Doc(Document::new(
+                serde_json::Value::Array(rest),
+                PathBuf::from(format!("_{}.json", param.name)),
+                "json".into(),
+                SystemTime::now(),
+                rt.effective_root.clone().unwrap_or_default(),
+            ))
+        } else {
+            match (args.next(), &param.default) {
+                (Some(v), _) => v,
+                (None, Some(raw)) => resolve_token(rt, raw)?,
+                (None, None) => Value::Unit,
+            }
+        };
+        out.push((param.name.clone(), val));
+    }
     Ok(out)
 }
 
+fn value_to_json(v: Value) -> Result<serde_json::Value> {
+    Ok(match v {
+        Value::Unit => serde_json::Value::Null,
+        Value::Bool(b) => serde_json::Value::Bool(b),
+        Value::Num(n) => num_to_json(n).ok_or_else(|| anyhow::anyhow!("E_NUMBER_INVALID: invalid number"))?,
+        Value::Str(s) => serde_json::Value::String(s),
+        Value::Doc(d) => d.json,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +135,72 @@ mod tests {
         assert_eq!(rt.get_num("x"), Some(3.0));
         Ok(())
     }
+
+    #[test]
+    fn nested_return_ends_only_the_call() -> Result<()> {
+        let script = "[funct:find]{[int@0]>[store@i]>[loop@5]{[math@i+1]>[store@i]>[if@(i==3)]>[then]{[return@i]}}>[msg@\"after loop\"]>[store@reached]}>[call@find]>[store@found]>[msg@\"after call\"]>[store@done]";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::new()?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_num("found"), Some(3.0));
+        assert_eq!(rt.get_num("i"), Some(3.0));
+        assert_eq!(rt.get_var("reached"), None);
+        assert_eq!(rt.get_var("done"), Some(Value::Str("after call".into())));
+        Ok(())
+    }
+
+    #[test]
+    fn early_return_from_a_loop_keeps_the_outer_loop_index() -> Result<()> {
+        let script = "[funct:first]{[loop@3]{[return@__loop_index]}}\
+            >[msg@\"\"]>[store@seen]\
+            >[loop@2]{[call@first]>[msg@seen + __loop_index + \",\"]>[store@seen]}";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::new()?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("seen"), Some(Value::Str("0,1,".into())));
+        assert_eq!(rt.get_var("__loop_index"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn return_inside_a_loop_tag_funct_ends_that_pass() -> Result<()> {
+        let script = "[funct:step]{[math@__loop_index*10]>[store@v]>[return@v]>[msg@\"no\"]>[store@bad]}\
+            >[loop:step@3]>[store@out]";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::new()?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_num("out"), Some(20.0));
+        assert_eq!(rt.get_var("bad"), None);
+        assert_eq!(rt.call_depth, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn defaults_fill_missing_args() -> Result<()> {
+        let script = "[funct:greet(name, greeting=\"Hello\")]{[msg@greeting + \", \" + name]}\
+            >[call(\"Ana\")@greet]>[store@a]>[call(\"Bo\", \"Hi\")@greet]>[store@b]";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::new()?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("a"), Some(Value::Str("Hello, Ana".into())));
+        assert_eq!(rt.get_var("b"), Some(Value::Str("Hi, Bo".into())));
+        assert_eq!(rt.get_var("name"), None);
+        let err = rt.eval(&router::parse("[call@greet]").map_err(anyhow::Error::new)?).unwrap_err();
+        assert!(err.to_string().contains("E_ARITY"), "unexpected error: {err}");
+        Ok(())
+    }
+
+    #[test]
+    fn rest_param_collects_extra_args() -> Result<()> {
+        let script = "[funct:log(level, ...rest)]{[len@rest]}>[call(\"warn\", 1, \"two\", true)@log]";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        let mut rt = Runtime::new()?;
+        assert_eq!(rt.eval(&node)?, Value::Num(3.0));
+        let script = "[funct:pack(...rest)]{[var@rest]}>[call(1, \"a\")@pack]";
+        match rt.eval(&router::parse(script).map_err(anyhow::Error::new)?)? {
+            Value::Doc(d) => assert_eq!(d.json, serde_json::json!([1, "a"])),
+            other => panic!("expected a Doc, got {other:?}"),
+        }
+        Ok(())
+    }
 }
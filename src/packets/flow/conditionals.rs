@@ -1,5 +1,5 @@
 use crate::kernel::ast::CmpBase;
-use crate::kernel::{Arg, BExpr, Comparator, Node, Packet, Runtime, Value};
+use crate::kernel::{Arg, BExpr, Comparator, Node, Packet, Runtime, Span, Value};
 use anyhow::Result;
 
 // [myth] goal: branch like a choose-your-own-adventure (no paper cuts)
@@ -7,6 +7,178 @@ pub fn handle(_rt: &mut Runtime, _p: &crate::kernel::Packet) -> Result<Value> {
     Ok(Value::Unit)
 }
 
+// comparison operators, checked in order (two-char spellings before their one-char prefixes)
+const CMP_OPS: [(&str, Comparator); 21] = [
+    (
+        "[~=]",
+        Comparator {
+            base: CmpBase::Approx,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "[approx]",
+        Comparator {
+            base: CmpBase::Approx,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "~=",
+        Comparator {
+            base: CmpBase::Approx,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "[!=]",
+        Comparator {
+            base: CmpBase::Eq,
+            include_eq: false,
+            negate: true,
+        },
+    ),
+    (
+        "[ne]",
+        Comparator {
+            base: CmpBase::Eq,
+            include_eq: false,
+            negate: true,
+        },
+    ),
+    (
+        "!=",
+        Comparator {
+            base: CmpBase::Eq,
+            include_eq: false,
+            negate: true,
+        },
+    ),
+    (
+        "[>=]",
+        Comparator {
+            base: CmpBase::Gt,
+            include_eq: true,
+            negate: false,
+        },
+    ),
+    (
+        "[ge]",
+        Comparator {
+            base: CmpBase::Gt,
+            include_eq: true,
+            negate: false,
+        },
+    ),
+    (
+        ">=",
+        Comparator {
+            base: CmpBase::Gt,
+            include_eq: true,
+            negate: false,
+        },
+    ),
+    (
+        "[<=]",
+        Comparator {
+            base: CmpBase::Lt,
+            include_eq: true,
+            negate: false,
+        },
+    ),
+    (
+        "[le]",
+        Comparator {
+            base: CmpBase::Lt,
+            include_eq: true,
+            negate: false,
+        },
+    ),
+    (
+        "<=",
+        Comparator {
+            base: CmpBase::Lt,
+            include_eq: true,
+            negate: false,
+        },
+    ),
+    (
+        "[>]",
+        Comparator {
+            base: CmpBase::Gt,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "[gt]",
+        Comparator {
+            base: CmpBase::Gt,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        ">",
+        Comparator {
+            base: CmpBase::Gt,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "[<]",
+        Comparator {
+            base: CmpBase::Lt,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "[lt]",
+        Comparator {
+            base: CmpBase::Lt,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "<",
+        Comparator {
+            base: CmpBase::Lt,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "[=]",
+        Comparator {
+            base: CmpBase::Eq,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "[eq]",
+        Comparator {
+            base: CmpBase::Eq,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+    (
+        "==",
+        Comparator {
+            base: CmpBase::Eq,
+            include_eq: false,
+            negate: false,
+        },
+    ),
+];
+
 pub fn parse_cond(src: &str) -> BExpr {
     let s = src.trim();
     if s.starts_with('(') && s.ends_with(')') {
@@ -39,171 +211,31 @@ pub fn parse_cond(src: &str) -> BExpr {
         }
     }
 
-    // comparison operators
-    let ops: [(&str, Comparator); 18] = [
-        (
-            "[!=]",
-            Comparator {
-                base: CmpBase::Eq,
-                include_eq: false,
-                negate: true,
-            },
-        ),
-        (
-            "[ne]",
-            Comparator {
-                base: CmpBase::Eq,
-                include_eq: false,
-                negate: true,
-            },
-        ),
-        (
-            "!=",
-            Comparator {
-                base: CmpBase::Eq,
-                include_eq: false,
-                negate: true,
-            },
-        ),
-        (
-            "[>=]",
-            Comparator {
-                base: CmpBase::Gt,
-                include_eq: true,
-                negate: false,
-            },
-        ),
-        (
-            "[ge]",
-            Comparator {
-                base: CmpBase::Gt,
-                include_eq: true,
-                negate: false,
-            },
-        ),
-        (
-            ">=",
-            Comparator {
-                base: CmpBase::Gt,
-                include_eq: true,
-                negate: false,
-            },
-        ),
-        (
-            "[<=]",
-            Comparator {
-                base: CmpBase::Lt,
-                include_eq: true,
-                negate: false,
-            },
-        ),
-        (
-            "[le]",
-            Comparator {
-                base: CmpBase::Lt,
-                include_eq: true,
-                negate: false,
-            },
-        ),
-        (
-            "<=",
-            Comparator {
-                base: CmpBase::Lt,
-                include_eq: true,
-                negate: false,
-            },
-        ),
-        (
-            "[>]",
-            Comparator {
-                base: CmpBase::Gt,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            "[gt]",
-            Comparator {
-                base: CmpBase::Gt,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            ">",
-            Comparator {
-                base: CmpBase::Gt,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            "[<]",
-            Comparator {
-                base: CmpBase::Lt,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            "[lt]",
-            Comparator {
-                base: CmpBase::Lt,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            "<",
-            Comparator {
-                base: CmpBase::Lt,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            "[=]",
-            Comparator {
-                base: CmpBase::Eq,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            "[eq]",
-            Comparator {
-                base: CmpBase::Eq,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-        (
-            "==",
-            Comparator {
-                base: CmpBase::Eq,
-                include_eq: false,
-                negate: false,
-            },
-        ),
-    ];
+    if let Some((lhs_src, cmp, rhs_src)) = split_comparator(s) {
+        if let (Some(lhs), Some(rhs)) = (parse_atom(lhs_src), parse_atom(rhs_src)) {
+            return BExpr::Cmp {
+                lhs: Box::new(lhs),
+                cmp,
+                rhs: Box::new(rhs),
+            };
+        } else {
+            return BExpr::Lit(src.to_string());
+        }
+    }
 
-    for (pat, cmp) in ops.iter() {
+    BExpr::Lit(src.to_string())
+}
+
+// Splits `lhs <op> rhs` on the first comparator found; shared with query predicates.
+pub fn split_comparator(s: &str) -> Option<(&str, Comparator, &str)> {
+    for (pat, cmp) in CMP_OPS.iter() {
         if let Some(idx) = s.find(pat) {
             let lhs_src = s[..idx].trim();
             let rhs_src = s[idx + pat.len()..].trim();
-            if let (Some(lhs), Some(rhs)) = (parse_atom(lhs_src), parse_atom(rhs_src)) {
-                return BExpr::Cmp {
-                    lhs: Box::new(lhs),
-                    cmp: cmp.clone(),
-                    rhs: Box::new(rhs),
-                };
-            } else {
-                return BExpr::Lit(src.to_string());
-            }
+            return Some((lhs_src, cmp.clone(), rhs_src));
         }
     }
-
-    BExpr::Lit(src.to_string())
+    None
 }
 
 fn parse_atom(tok: &str) -> Option<Node> {
@@ -216,6 +248,7 @@ fn parse_atom(tok: &str) -> Option<Node> {
             op: "math".into(),
             arg: Some(Arg::Number(n)),
             body: None,
+            span: Span::default(),
         }))
     } else if t.starts_with('"') && t.ends_with('"') {
         // string literal
@@ -225,6 +258,7 @@ fn parse_atom(tok: &str) -> Option<Node> {
             op: "msg".into(),
             arg: Some(Arg::Str(inner)),
             body: None,
+            span: Span::default(),
         }))
     } else if is_ident_like(t) {
         // resolve identifier as a runtime variable (string/number/bool)
@@ -233,12 +267,32 @@ fn parse_atom(tok: &str) -> Option<Node> {
             op: "var".into(),
             arg: Some(Arg::Ident(t.to_string())),
             body: None,
+            span: Span::default(),
+        }))
+    } else if let Some((handle, path)) = split_doc_path(t) {
+        // user.age -> [get(age)@user]; a missing path reads as Unit
+        Some(Node::Packet(Packet {
+            ns: None,
+            op: format!("get({path})"),
+            arg: Some(Arg::Ident(handle.to_string())),
+            body: None,
+            span: Span::default(),
         }))
     } else {
         None
     }
 }
 
+// `doc.a.b` -> ("doc", "a.b") when every segment is an identifier
+fn split_doc_path(s: &str) -> Option<(&str, &str)> {
+    let (handle, path) = s.split_once('.')?;
+    if is_ident_like(handle) && path.split('.').all(is_ident_like) {
+        Some((handle, path))
+    } else {
+        None
+    }
+}
+
 fn is_ident_like(s: &str) -> bool {
     let mut it = s.chars();
     match it.next() {
@@ -255,14 +309,16 @@ pub fn eval_cond(rt: &mut Runtime, cond: &BExpr) -> Result<bool> {
             if is_ident_like(s) {
                 // Treat bare identifiers as variable truthiness
                 Ok(rt.get_var(s).and_then(|v| v.as_bool()).unwrap_or(false))
+            } else if let Some(node) = split_doc_path(s).and_then(|_| parse_atom(s)) {
+                // dotted names read a Doc path, then use its truthiness
+                let mut tmp = rt.fork();
+                Ok(tmp.eval(&node)?.as_bool().unwrap_or(false))
             } else if let Ok(n) = s.parse::<f64>() {
                 // Numeric literals: non-zero = true
                 Ok(n != 0.0 && !n.is_nan())
             } else {
                 let node = crate::router::parse(s).map_err(anyhow::Error::new)?;
-                let mut tmp = Runtime::new()?;
-                tmp.vars = rt.vars.clone();
-                tmp.tags = rt.tags.clone();
+                let mut tmp = rt.fork();
                 // [myth] goal: numbers <= 0 and empty strings are false
                 Ok(tmp.eval(&node)?.as_bool().unwrap_or(false))
             }
@@ -271,10 +327,12 @@ pub fn eval_cond(rt: &mut Runtime, cond: &BExpr) -> Result<bool> {
         BExpr::Or(a, b) => Ok(eval_cond(rt, a)? || eval_cond(rt, b)?),
         BExpr::Not(e) => Ok(!eval_cond(rt, e)?),
         BExpr::Cmp { lhs, cmp, rhs } => {
-            let mut tmp = Runtime::new()?;
-            tmp.vars = rt.vars.clone();
+            let mut tmp = rt.fork();
             let lv = tmp.eval(lhs)?;
             let rv = tmp.eval(rhs)?;
+            if matches!(cmp.base, CmpBase::Approx) {
+                return crate::kernel::boolops::cmp_eval_with(cmp, &lv, &rv, rt.approx_tol);
+            }
             crate::kernel::boolops::cmp_eval(cmp, &lv, &rv)
         }
     }
@@ -309,4 +367,38 @@ mod tests {
         assert_eq!(rt.get_num("x"), Some(30.0));
         Ok(())
     }
+
+    #[test]
+    fn approx_comparator_in_conditions() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("a", Value::Num(0.1 + 0.2))?;
+        rt.set_var("b", Value::Num(0.3))?;
+        assert!(eval_cond(&mut rt, &parse_cond("(a ~= b)"))?);
+        assert!(eval_cond(&mut rt, &parse_cond("(a [approx] 0.3)"))?);
+        assert!(!eval_cond(&mut rt, &parse_cond("(a == b)"))?);
+
+        // the tolerance lives on the runtime, resolved once
+        rt.approx_tol = crate::kernel::boolops::Tolerance { rel: 0.01, abs: 0.0 };
+        assert!(eval_cond(&mut rt, &parse_cond("(a ~= 0.302)"))?);
+        let node = router::parse("[math@0.302]>[cmp:approx@a]").map_err(anyhow::Error::new)?;
+        assert_eq!(rt.eval(&node)?, Value::Bool(true));
+        Ok(())
+    }
+
+    #[test]
+    fn dotted_names_read_doc_paths() -> Result<()> {
+        use crate::kernel::values::Document;
+        use std::time::SystemTime;
+        let mut rt = Runtime::new()?;
+        let json = serde_json::json!({"age": 21, "profile": {"admin": true}});
+        let doc = Document::new(json, "user.json".into(), "json".into(), SystemTime::now(), Default::default());
+        rt.set_var("user", Value::Doc(doc))?;
+        let script = "[if@(user.age > 18)]>[then]{[math@1]>[store@x]}>[else]>[then]{[math@2]>[store@x]}>\
+                      [if@(user.profile.admin)]>[then]{[math@3]>[store@y]}";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_num("x"), Some(1.0));
+        assert_eq!(rt.get_num("y"), Some(3.0));
+        Ok(())
+    }
 }
@@ -0,0 +1,74 @@
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::{Result, bail};
+
+// [defer]{...} -> queue the block to run when the enclosing funct returns (or, outside a
+// funct, when the script ends), even if it fails or leaves through [return]. A [run] script
+// and each [loop:tag] pass are scopes of their own. Several defers run newest first, like
+// Go. The piped value passes through.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if p.arg.is_some() {
+        bail!("E_BAD_ARG: defer takes no @arg, only a {{...}} body");
+    }
+    let body = match &p.body {
+        Some(body) => body.clone(),
+        None => bail!("E_MISSING_BODY: defer needs body"),
+    };
+    rt.deferred.push(body);
+    Ok(rt.last.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn deferred_blocks_run_after_an_early_return() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("log", Value::Str(String::new()))?;
+        let script = "[funct:work]{\
+            [defer]{[msg@log + \"a\"]>[store@log]}\
+            >[defer]{[msg@log + \"b\"]>[store@log]}\
+            >[return@7]\
+            >[msg@log + \"never\"]>[store@log]}\
+            >[call@work]>[store@out]";
+        eval(&mut rt, script)?;
+        assert_eq!(rt.get_var("out"), Some(Value::Num(7.0)));
+        assert_eq!(rt.get_var("log"), Some(Value::Str("ba".into())));
+        assert!(rt.deferred.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn deferred_blocks_run_when_the_script_fails() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let err = eval(&mut rt, "[defer]{[msg@\"cleaned\"]>[store@done]}>[assert@(1==2)]").unwrap_err();
+        assert!(err.to_string().contains("E_ASSERT"), "{err}");
+        assert_eq!(rt.get_var("done"), Some(Value::Str("cleaned".into())));
+        Ok(())
+    }
+
+    #[test]
+    fn run_scripts_and_loop_tags_flush_their_own_defers() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_defer_run_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        std::fs::create_dir_all(&base)?;
+        std::fs::write(base.join("red.tgsk"), "")?;
+        std::fs::write(base.join("child.tgsk"), "[defer]{[msg@log + \"c\"]>[store@log]}>[msg@log + \"r\"]>[store@log]")?;
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk"))?;
+        rt.set_var("log", Value::Str(String::new()))?;
+        let script = "[run@/child.tgsk]>[msg@log + \"|\"]>[store@log]\
+            >[funct:pass]{[defer]{[msg@log + \"d\"]>[store@log]}>[msg@log + \"p\"]>[store@log]}\
+            >[loop:pass@2]";
+        eval(&mut rt, script)?;
+        assert_eq!(rt.get_var("log"), Some(Value::Str("rc|pdpd".into())));
+        assert!(rt.deferred.is_empty());
+        let _ = std::fs::remove_dir_all(&base);
+        Ok(())
+    }
+}
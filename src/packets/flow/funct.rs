@@ -1,16 +1,107 @@
+use crate::kernel::ast::Node;
 use crate::kernel::{Packet, Runtime, Value};
 use anyhow::{Result, bail};
 
+// One entry of `[funct:name(a, b="dflt", ...rest)]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub default: Option<String>, // raw token, resolved at call time like a [call] arg
+    pub variadic: bool,
+}
+
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     // [funct:tag]{ ... }  => ns = Some("funct"), op = "tag", body = Some(...)
-    let tag = p.op.as_str();
+    // [funct:greet(name, greeting="Hello")]{ ... } also declares params for [call(...)@greet]
     let body = p
         .body
         .as_ref()
         .ok_or_else(|| anyhow::anyhow!("funct requires a {{ ... }} body"))?;
+    define(rt, &p.op, body)?;
+    Ok(Value::Unit)
+}
+
+// Registers `head` (`tag` or `tag(params)`) and returns the bare tag name.
+pub fn define(rt: &mut Runtime, head: &str, body: &[Node]) -> Result<String> {
+    let (tag, params) = match head.split_once('(') {
+        Some((tag, _)) => (tag.trim(), parse_params(crate::router::extract_paren(head).unwrap_or(""))?),
+        None => (head.trim(), Vec::new()),
+    };
     if tag.is_empty() {
         bail!("funct needs a tag name: [funct:tag]{{...}}");
     }
-    rt.register_tag(tag, body.clone());
-    Ok(Value::Unit)
+    rt.register_tag(tag, body.to_vec());
+    if params.is_empty() {
+        rt.funct_params.remove(tag);
+    } else {
+        rt.funct_params.insert(tag.to_string(), params);
+    }
+    Ok(tag.to_string())
+}
+
+// required params first, then defaults, then at most one trailing ...rest
+fn parse_params(src: &str) -> Result<Vec<Param>> {
+    let mut params: Vec<Param> = Vec::new();
+    for raw in split_args(src) {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            continue;
+        }
+        if params.last().is_some_and(|p| p.variadic) {
+            bail!("E_BAD_ARG: funct param '{raw}' comes after a ...rest param");
+        }
+        let param = if let Some(name) = raw.strip_prefix("...") {
+            Param { name: name.trim().to_string(), default: None, variadic: true }
+        } else if let Some((name, default)) = raw.split_once('=') {
+            Param { name: name.trim().to_string(), default: Some(default.trim().to_string()), variadic: false }
+        } else {
+            if params.iter().any(|p| p.default.is_some()) {
+                bail!("E_BAD_ARG: required funct param '{raw}' follows one with a default");
+            }
+            Param { name: raw.to_string(), default: None, variadic: false }
+        };
+        let valid = param.name.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && param.name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            bail!("E_BAD_ARG: funct param '{raw}' is not a valid name");
+        }
+        if params.iter().any(|p| p.name == param.name) {
+            bail!("E_BAD_ARG: funct param '{}' is declared twice", param.name);
+        }
+        params.push(param);
+    }
+    Ok(params)
+}
+
+// split on ',' outside of double quotes and nested {}/[]; also used by [with@{...}]
+pub fn split_args(src: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0usize;
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escape = false;
+    for (idx, ch) in src.char_indices() {
+        if in_string {
+            if escape {
+                escape = false;
+            } else if ch == '\\' {
+                escape = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match ch {
+            '"' => in_string = true,
+            '{' | '[' => depth += 1,
+            '}' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&src[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&src[start..]);
+    parts
 }
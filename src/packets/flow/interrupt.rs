@@ -0,0 +1,41 @@
+use crate::kernel::runtime::FlowSignal;
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::Result;
+
+// [interrupt@"done"] -> stop the script now; "done" becomes the script's result
+// [interrupt]        -> same, carrying the piped value
+// Loops, calls, and [try] blocks do not stop it; [run] returns it as the child's result.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let value = match &p.arg {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    Err(FlowSignal::Interrupt(value).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn top_level_interrupt_is_the_result() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        assert_eq!(eval(&mut rt, "[interrupt@\"done\"]")?, Value::Str("done".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn interrupt_escapes_loops_and_try() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let script = "[int@0]>[store@n]>[loop@5]{[try]{[math@n+1]>[store@n]>[interrupt@n]}[catch]{[msg@\"caught\"]}}>[msg@\"after\"]";
+        assert_eq!(eval(&mut rt, script)?, Value::Num(1.0));
+        assert_eq!(rt.get_var("err"), None);
+        Ok(())
+    }
+}
@@ -1,9 +1,17 @@
 use anyhow::{Result, bail};
 
+use crate::kernel::runtime::{handle_loop_signal, restore_loop_meta, save_loop_meta, set_loop_meta};
 use crate::kernel::values::Document;
 use crate::kernel::{Node, Packet, Runtime, Value};
 
+// [iter@handle]{...}                  -> array items as `it`, positions as `idx`
+// [iter:entries(k,v)@handle]{...}     -> object entries in stored order, key as `k`, value as `v`
+// [iter:entries(k,v@handle)]{...}     -> same, handle inside the parens
+// Both also bind __loop_index / __loop_first / __loop_last / __loop_count per pass.
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    if p.ns.as_deref() == Some("iter") {
+        return entries(rt, p);
+    }
     let handle = match &p.arg {
         Some(crate::kernel::ast::Arg::Ident(id)) => id,
         Some(crate::kernel::ast::Arg::Str(s)) => s,
@@ -23,14 +31,74 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     }
     let arr = doc.json.as_array().unwrap();
 
+    let saved = save_loop_meta(rt);
     let mut last = Value::Unit;
-    for (idx, item) in arr.iter().enumerate() {
-        let it_val = json_to_value(item, &doc);
-        rt.set_var("it", it_val)?;
-        rt.set_var("idx", Value::Num(idx as f64))?;
-        last = rt.eval(&Node::Block(body.clone()))?;
-    }
-    Ok(last)
+    // restored even when the body leaves early with [return] or an error
+    let done = (|| -> Result<()> {
+        for (idx, item) in arr.iter().enumerate() {
+            let it_val = json_to_value(item, &doc);
+            rt.set_var("it", it_val)?;
+            rt.set_var("idx", Value::Num(idx as f64))?;
+            set_loop_meta(rt, idx, arr.len())?;
+            if !handle_loop_signal(rt.eval(&Node::Block(body.clone())), &mut last)? {
+                break;
+            }
+        }
+        Ok(())
+    })();
+    restore_loop_meta(rt, saved);
+    done.map(|_| last)
+}
+
+fn entries(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let inner = p
+        .op
+        .strip_prefix("entries(")
+        .and_then(|s| s.strip_suffix(')'))
+        .ok_or_else(|| anyhow::anyhow!("E_BAD_ARG: use [iter:entries(k,v)@handle]{{...}}"))?;
+    let (names, inline_handle) = match inner.split_once('@') {
+        Some((names, handle)) => (names, Some(handle.trim())),
+        None => (inner, None),
+    };
+    let (key_name, val_name) = match names.split_once(',') {
+        Some((k, v)) if !k.trim().is_empty() && !v.trim().is_empty() => (k.trim(), v.trim()),
+        _ => bail!("E_BAD_ARG: entries needs two names: entries(k,v)"),
+    };
+    let handle = match (inline_handle, &p.arg) {
+        (Some(h), _) => h,
+        (None, Some(crate::kernel::ast::Arg::Ident(id))) => id.as_str(),
+        (None, Some(crate::kernel::ast::Arg::Str(s))) => s.as_str(),
+        _ => bail!("E_BAD_ARG: iter:entries needs @<handle>"),
+    };
+    let body = p
+        .body
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("E_MISSING_BODY: iter needs body"))?;
+
+    let doc = match rt.get_var(handle) {
+        Some(Value::Doc(d)) => d,
+        _ => bail!("E_HANDLE_UNKNOWN: '{handle}' is not a loaded document"),
+    };
+    let obj = match doc.json.as_object() {
+        Some(obj) => obj,
+        None => bail!("E_NOT_OBJECT: iter:entries needs an object"),
+    };
+
+    let saved = save_loop_meta(rt);
+    let mut last = Value::Unit;
+    let done = (|| -> Result<()> {
+        for (idx, (key, item)) in obj.iter().enumerate() {
+            rt.set_var(key_name, Value::Str(key.clone()))?;
+            rt.set_var(val_name, json_to_value(item, &doc))?;
+            set_loop_meta(rt, idx, obj.len())?;
+            if !handle_loop_signal(rt.eval(&Node::Block(body.clone())), &mut last)? {
+                break;
+            }
+        }
+        Ok(())
+    })();
+    restore_loop_meta(rt, saved);
+    done.map(|_| last)
 }
 
 fn json_to_value(v: &serde_json::Value, meta: &Document) -> Value {
@@ -46,3 +114,49 @@ fn json_to_value(v: &serde_json::Value, meta: &Document) -> Value {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    #[test]
+    fn entries_walk_object_in_stored_order() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let json = serde_json::json!({"b": 1, "a": "two"});
+        let doc = Document::new(
+            json,
+            PathBuf::from("cfg.json"),
+            "json".into(),
+            SystemTime::now(),
+            PathBuf::new(),
+        );
+        rt.set_var("cfg", Value::Doc(doc))?;
+        rt.set_var("seen", Value::Str(String::new()))?;
+        let script = "[iter:entries(k,v)@cfg]{[msg@seen + k + \"=\" + v + \";\"]>[store@seen]}";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("seen"), Some(Value::Str("b=1;a=two;".into())));
+        Ok(())
+    }
+
+    #[test]
+    fn binds_first_and_last_flags() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let json = serde_json::json!(["a", "b", "c"]);
+        let doc = Document::new(json, PathBuf::from("xs.json"), "json".into(), SystemTime::now(), PathBuf::new());
+        rt.set_var("xs", Value::Doc(doc))?;
+        rt.set_var("out", Value::Str(String::new()))?;
+        let script = "[iter@xs]{[if@(__loop_first)]>[then]{[msg@out + \"^\"]>[store@out]}\
+            >[msg@out + it]>[store@out]\
+            >[if@(__loop_last)]>[then]{[msg@out + \"$\" + __loop_count]>[store@out]}\
+            >[else]>[then]{[msg@out + \",\"]>[store@out]}}";
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)?;
+        assert_eq!(rt.get_var("out"), Some(Value::Str("^a,b,c$3".into())));
+        assert_eq!(rt.get_var("__loop_index"), None);
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
 use crate::kernel::ast::{Arg, Node};
+use crate::kernel::runtime::{
+    FlowSignal, handle_loop_signal, restore_loop_meta, save_loop_meta, set_loop_meta,
+};
 use crate::kernel::{Packet, Runtime, Value};
 use anyhow::{Result, bail};
 
@@ -52,11 +55,7 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
 
     // choose body: inline or tag
     if let Some(body) = &p.body {
-        let mut last = Value::Unit;
-        for _ in 0..count {
-            last = rt.eval(&Node::Block(body.clone()))?;
-        }
-        return Ok(last);
+        return run_body(rt, body, count, false);
     }
 
     // tag-based body
@@ -78,9 +77,45 @@ pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
         })?
         .clone();
 
-    let mut last = Value::Unit;
-    for _ in 0..count {
-        last = rt.eval(&Node::Block(body.clone()))?;
+    run_body(rt, &body, count, true)
+}
+
+// `funct` marks a [loop:tag] / [loop3@tag] body: each pass invokes the funct, as a
+// [call] would, so it counts toward the call depth, its [defer] blocks run at the end of
+// that pass, and a [return] ends only that pass with the returned value
+fn run_body(rt: &mut Runtime, body: &[Node], count: usize, funct: bool) -> Result<Value> {
+    if funct && rt.call_depth >= rt.max_call_depth {
+        bail!("E_CALL_DEPTH_EXCEEDED: max recursion depth {} reached", rt.max_call_depth);
     }
-    Ok(last)
+    let saved = save_loop_meta(rt);
+    let mut last = Value::Unit;
+    // a [return] or error leaving the body must still put the outer loop's meta back
+    let done = (|| -> Result<()> {
+        for idx in 0..count {
+            set_loop_meta(rt, idx, count)?;
+            let mark = rt.deferred.len();
+            if funct {
+                rt.call_depth += 1;
+            }
+            let mut pass = rt.eval(&Node::Block(body.to_vec()));
+            if funct {
+                pass = rt.run_deferred(mark, pass);
+                rt.call_depth = rt.call_depth.saturating_sub(1);
+                pass = match pass {
+                    Err(err) => match err.downcast::<FlowSignal>() {
+                        Ok(FlowSignal::Return(v)) => Ok(v),
+                        Ok(other) => Err(other.into()),
+                        Err(err) => Err(err),
+                    },
+                    ok => ok,
+                };
+            }
+            if !handle_loop_signal(pass, &mut last)? {
+                break;
+            }
+        }
+        Ok(())
+    })();
+    restore_loop_meta(rt, saved);
+    done.map(|_| last)
 }
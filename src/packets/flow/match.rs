@@ -0,0 +1,95 @@
+use crate::kernel::ast::{CmpBase, Comparator, Node};
+use crate::kernel::boolops::cmp_eval;
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::{Result, bail};
+
+// [match@state]{[case@"idle"]{...}[case@"busy"]{...}[default]{...}}
+// Subject comes from the arg, or the piped value when no arg is given. Cases are compared
+// in order with the same equality rules as [eq]; the first hit runs and its result is
+// returned. No hit and no [default] yields Unit.
+const EQ: Comparator = Comparator {
+    base: CmpBase::Eq,
+    include_eq: false,
+    negate: false,
+};
+
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let body = match &p.body {
+        Some(body) => body,
+        None => bail!("E_MISSING_BODY: match needs body of [case]/[default]"),
+    };
+    let subject = match &p.arg {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+
+    let mut fallback: Option<&Packet> = None;
+    for node in body {
+        let arm = match node {
+            Node::Packet(arm) if arm.ns.is_none() => arm,
+            _ => bail!("E_BAD_ARG: match body only takes [case@value]{{...}} and [default]{{...}}"),
+        };
+        match arm.op.as_str() {
+            "case" => {
+                let want = match &arm.arg {
+                    Some(arg) => rt.resolve_arg(arg)?,
+                    None => bail!("E_BAD_ARG: case needs @value"),
+                };
+                if cmp_eval(&EQ, &subject, &want)? {
+                    return run_arm(rt, arm);
+                }
+            }
+            "default" => fallback = Some(arm),
+            other => bail!("E_BAD_ARG: unexpected [{other}] inside match"),
+        }
+    }
+    match fallback {
+        Some(arm) => run_arm(rt, arm),
+        None => Ok(Value::Unit),
+    }
+}
+
+fn run_arm(rt: &mut Runtime, arm: &Packet) -> Result<Value> {
+    match &arm.body {
+        Some(body) => rt.eval(&Node::Block(body.clone())),
+        None => Ok(Value::Unit),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    const SCRIPT: &str =
+        "[match@state]{[case@\"idle\"]{[msg@\"start\"]}[case@\"busy\"]{[msg@\"wait\"]}[default]{[msg@\"reset\"]}}";
+
+    #[test]
+    fn runs_first_matching_case() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("state", Value::Str("busy".into()))?;
+        assert_eq!(eval(&mut rt, SCRIPT)?, Value::Str("wait".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn falls_back_to_default() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("state", Value::Str("broken".into()))?;
+        assert_eq!(eval(&mut rt, SCRIPT)?, Value::Str("reset".into()));
+        Ok(())
+    }
+
+    #[test]
+    fn numeric_subject_from_pipe() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let out = eval(&mut rt, "[int@2]>[match]{[case@1]{[msg@\"one\"]}[case@2]{[msg@\"two\"]}}")?;
+        assert_eq!(out, Value::Str("two".into()));
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::kernel::ast::{Arg, Node};
+use crate::kernel::fs_guard::resolve;
+use crate::kernel::runtime::FlowSignal;
+use crate::kernel::{Packet, Runtime, Value};
+use crate::packets::save::write_atomic;
+use anyhow::{Result, bail};
+use std::io;
+use std::path::Path;
+
+// markers live at the red root, one per line
+const ONCE_FILE: &str = ".tagspeak_once";
+
+// [once@provision_db]{...} -> runs the block unless `provision_db` is already recorded in
+// <red root>/.tagspeak_once, then records it. A failing block records nothing, so the next
+// run tries again; leaving it through [return]/[break]/[interrupt] counts as finished.
+// Skipped blocks yield Unit. A dry run leaves the marker unrecorded.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let body = match &p.body {
+        Some(body) => body,
+        None => bail!("E_MISSING_BODY: once needs body"),
+    };
+    let marker = match &p.arg {
+        Some(Arg::Ident(s)) | Some(Arg::Str(s)) => s.trim().to_string(),
+        _ => bail!("E_BAD_ARG: once needs @<marker>"),
+    };
+    if marker.is_empty() || marker.contains(['\n', '\r']) {
+        bail!("E_BAD_ARG: once marker must be a single non-empty line");
+    }
+    let root = rt
+        .effective_root
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("E_BOX_REQUIRED: [once] needs a red.tgsk root"))?;
+
+    if load_markers(&root)?.contains(&marker) {
+        return Ok(Value::Unit);
+    }
+    let out = rt.eval(&Node::Block(body.clone()));
+    if matches!(&out, Err(err) if !err.is::<FlowSignal>()) {
+        return out;
+    }
+    if rt.dry_run {
+        eprintln!("[dry-run] would record once marker '{marker}'");
+        return out;
+    }
+    // re-read: a nested [once] inside the body may have recorded its own marker meanwhile
+    let mut markers = load_markers(&root)?;
+    if !markers.contains(&marker) {
+        markers.push(marker);
+        save_markers(&root, &markers)?;
+    }
+    out
+}
+
+fn load_markers(root: &Path) -> Result<Vec<String>> {
+    let path = resolve(root, Path::new(ONCE_FILE))?;
+    let text = match std::fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(text.lines().map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect())
+}
+
+fn save_markers(root: &Path, markers: &[String]) -> Result<()> {
+    let path = resolve(root, Path::new(ONCE_FILE))?;
+    let mut text = markers.join("\n");
+    text.push('\n');
+    // a write cut short must not truncate the markers already recorded
+    write_atomic(&path, text.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+    use std::fs;
+
+    #[test]
+    fn body_runs_once_per_marker() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_once_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        let run = || -> Result<Option<Value>> {
+            // a fresh runtime each time, like the script being run again
+            let mut rt = Runtime::from_entry(&base.join("main.tgsk"))?;
+            let node = router::parse("[once@seed]{[store(inc)@runs]}").map_err(anyhow::Error::new)?;
+            rt.eval(&node)?;
+            Ok(rt.get_var("runs"))
+        };
+
+        assert_eq!(run()?, Some(Value::Num(1.0)));
+        assert_eq!(run()?, None);
+        assert_eq!(fs::read_to_string(base.join(ONCE_FILE))?, "seed\n");
+        let _ = fs::remove_dir_all(&base);
+        Ok(())
+    }
+
+    #[test]
+    fn nested_and_returning_blocks_keep_their_markers() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_once_nested_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk"))?;
+        let script = "[once@a]{[once@b]{[int@1]}}\
+            >[funct:setup]{[once@c]{[return@1]}}>[call@setup]";
+        rt.eval(&router::parse(script).map_err(anyhow::Error::new)?)?;
+        assert_eq!(fs::read_to_string(base.join(ONCE_FILE))?, "b\na\nc\n");
+        let _ = fs::remove_dir_all(&base);
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::kernel::ast::Node;
+use crate::kernel::runtime::FlowSignal;
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::{Result, bail};
+use std::time::Duration;
+
+// [retry(3,500)]{...} -> run the block; on a packet error run it again, up to 3 more
+// times, sleeping 500ms before each retry. The last error surfaces if every attempt fails.
+// [retry(3)]{...}     -> same without the pause
+// Flow signals ([interrupt], [return], [break]) are not failures and pass straight through.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let body = match &p.body {
+        Some(body) => body,
+        None => bail!("E_MISSING_BODY: retry needs body"),
+    };
+    let (retries, pause) = parse_opts(crate::router::extract_paren(&p.op).unwrap_or(""))?;
+    let mut attempt = 0;
+    loop {
+        match rt.eval(&Node::Block(body.clone())) {
+            Ok(v) => return Ok(v),
+            Err(err) if err.is::<FlowSignal>() => return Err(err),
+            Err(err) if attempt >= retries => return Err(err),
+            Err(_) => {
+                attempt += 1;
+                if !pause.is_zero() {
+                    rt.sleep(pause)?;
+                }
+            }
+        }
+    }
+}
+
+// "3,500" -> (3 retries, 500ms)
+fn parse_opts(raw: &str) -> Result<(usize, Duration)> {
+    let mut parts = raw.split(',').map(str::trim);
+    let retries = match parts.next().map(str::parse::<usize>) {
+        Some(Ok(n)) => n,
+        _ => bail!("E_BAD_ARG: retry needs a count: [retry(3,500)]{{...}}"),
+    };
+    let pause = match parts.next() {
+        Some(ms) => match ms.parse::<u64>() {
+            Ok(ms) => Duration::from_millis(ms),
+            Err(_) => bail!("E_BAD_ARG: retry backoff '{ms}' must be milliseconds"),
+        },
+        None => Duration::ZERO,
+    };
+    if parts.next().is_some() {
+        bail!("E_BAD_ARG: retry takes (count,ms)");
+    }
+    Ok((retries, pause))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn succeeds_after_two_failures() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let script = "[retry(3,1)]{[store(inc)@n]>[if(n<3)]>[then]{[get(a)@nope]}>[msg@\"ok\"]}";
+        let out = eval(&mut rt, script)?;
+        assert_eq!(out, Value::Str("ok".into()));
+        assert_eq!(rt.get_var("n"), Some(Value::Num(3.0)));
+        Ok(())
+    }
+
+    #[test]
+    fn surfaces_last_error_when_attempts_run_out() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let err = eval(&mut rt, "[retry(2)]{[store(inc)@n]>[get(a)@nope]}").unwrap_err();
+        assert!(format!("{err:#}").contains("E_HANDLE_UNKNOWN"), "got: {err:#}");
+        assert_eq!(rt.get_var("n"), Some(Value::Num(3.0)));
+        Ok(())
+    }
+}
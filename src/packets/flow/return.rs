@@ -0,0 +1,15 @@
+use crate::kernel::runtime::FlowSignal;
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::Result;
+
+// [return@x] -> leave the enclosing funct; x becomes the result of its [call]
+// [return]   -> same, returning the piped value
+// Works from any depth inside the funct body (ifs, loops, iters); under [loop:tag] it
+// ends that pass only. Outside a funct it fails with E_RETURN_OUTSIDE_FUNCT.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let value = match &p.arg {
+        Some(arg) => rt.resolve_arg(arg)?,
+        None => rt.last.clone(),
+    };
+    Err(FlowSignal::Return(value).into())
+}
@@ -0,0 +1,78 @@
+use crate::kernel::ast::{Arg, Node};
+use crate::kernel::runtime::{FlowSignal, error_code};
+use crate::kernel::{Packet, Runtime, Value};
+use anyhow::{Result, bail};
+
+// [try]{...}[catch]{...}       -> on error, binds `err` (message) and `err_code` (E_* or Unit), runs catch
+// [try]{...}[catch@e]{...}     -> same, bound to `e` / `e_code`
+// The runtime pairs a [try] with the [catch] that directly follows it; a lone [try]
+// swallows the error (still binding `err`) and yields Unit.
+// [interrupt] and [return] also travel as errors (FlowSignal); they are passed through
+// untouched so they still reach the construct that owns them.
+pub fn handle(rt: &mut Runtime, p: &Packet, catch: Option<&Packet>) -> Result<Value> {
+    let body = match &p.body {
+        Some(body) => body,
+        None => bail!("E_MISSING_BODY: try needs body"),
+    };
+    let err = match rt.eval(&Node::Block(body.clone())) {
+        Ok(v) => return Ok(v),
+        Err(err) if err.is::<FlowSignal>() => return Err(err),
+        Err(err) => err,
+    };
+
+    let name = match catch.and_then(|c| c.arg.as_ref()) {
+        Some(Arg::Ident(id)) | Some(Arg::Str(id)) => id.as_str(),
+        None => "err",
+        Some(_) => bail!("E_BAD_ARG: catch needs @<ident>"),
+    };
+    let code = error_code(&err).map(Value::Str).unwrap_or(Value::Unit);
+    rt.set_var(name, Value::Str(message(&err)))?;
+    rt.set_var(&format!("{name}_code"), code)?;
+
+    match catch.and_then(|c| c.body.as_ref()) {
+        Some(handler) => rt.eval(&Node::Block(handler.clone())),
+        None => Ok(Value::Unit),
+    }
+}
+
+// plain detail, not the boxed render: the catch block sees what the packet said
+fn message(err: &anyhow::Error) -> String {
+    match err.downcast_ref::<crate::kernel::runtime::RuntimeError>() {
+        Some(rt_err) => rt_err.detail.clone(),
+        None => format!("{err:#}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn catch_binds_error_and_recovers() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let out = eval(&mut rt, "[try]{[get(a)@nope]}[catch]{[msg@\"recovered\"]}")?;
+        assert_eq!(out, Value::Str("recovered".into()));
+        assert_eq!(rt.get_var("err_code"), Some(Value::Str("E_HANDLE_UNKNOWN".into())));
+        match rt.get_var("err") {
+            Some(Value::Str(msg)) => assert!(msg.contains("'nope'"), "unexpected message: {msg}"),
+            other => panic!("err not bound: {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn successful_try_skips_catch() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        let out = eval(&mut rt, "[try]{[int@1]>[store@x]}[catch@e]{[int@2]>[store@x]}")?;
+        assert_eq!(out, Value::Num(1.0));
+        assert_eq!(rt.get_var("x"), Some(Value::Num(1.0)));
+        assert_eq!(rt.get_var("e"), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use crate::kernel::ast::Arg;
+use crate::kernel::{Packet, Runtime, Value};
+use crate::packets::conditionals::{eval_cond, parse_cond};
+use anyhow::{Result, bail};
+use std::time::{Duration, Instant};
+
+// [wait_for@(ready)]                                -> re-check the condition every 200ms for up to
+//                                                      5s; true as soon as it holds, false on timeout
+// [wait_for(timeout:5000,interval:200)@(ready)]     -> same with explicit milliseconds (`=` works too)
+// The condition is checked once more right at the deadline, so a zero timeout is a single check.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let src = match p.arg.as_ref() {
+        Some(Arg::CondSrc(s)) | Some(Arg::Ident(s)) => s.clone(),
+        _ => bail!("E_BAD_ARG: wait_for needs @(cond)"),
+    };
+    let (timeout, interval) = parse_opts(crate::router::extract_paren(&p.op).unwrap_or(""))?;
+    let cond = parse_cond(&src);
+    let deadline = Instant::now() + timeout;
+    loop {
+        if eval_cond(rt, &cond)? {
+            return Ok(Value::Bool(true));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(Value::Bool(false));
+        }
+        rt.sleep(interval.min(deadline - now))?;
+    }
+}
+
+// "timeout:5000,interval:200" -> (5s, 200ms); either key may be left out
+fn parse_opts(raw: &str) -> Result<(Duration, Duration)> {
+    let mut timeout = Duration::from_millis(5000);
+    let mut interval = Duration::from_millis(200);
+    for part in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (key, ms) = match part.split_once([':', '=']) {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => bail!("E_BAD_ARG: wait_for option '{part}' needs key:ms"),
+        };
+        let ms = match ms.parse::<u64>() {
+            Ok(ms) => Duration::from_millis(ms),
+            Err(_) => bail!("E_BAD_ARG: wait_for {key} '{ms}' must be milliseconds"),
+        };
+        match key {
+            "timeout" => timeout = ms,
+            "interval" => interval = ms,
+            other => bail!("E_BAD_ARG: unknown wait_for option '{other}' (use timeout, interval)"),
+        }
+    }
+    if interval.is_zero() {
+        bail!("E_BAD_ARG: wait_for interval must be above 0");
+    }
+    Ok((timeout, interval))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+    use std::fs;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn returns_true_once_the_condition_holds() -> Result<()> {
+        let base = std::env::temp_dir().join(format!("tgsk_wait_for_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&base);
+        fs::create_dir_all(&base)?;
+        fs::write(base.join("red.tgsk"), "")?;
+        let mut rt = Runtime::from_entry(&base.join("main.tgsk"))?;
+        eval(&mut rt, "[funct:ready]{[try]{[load@/ready.json]>[bool@true]}}")?;
+
+        let flag = base.join("ready.json");
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(60));
+            fs::write(flag, "{}")
+        });
+        let started = Instant::now();
+        let out = eval(&mut rt, "[wait_for(timeout:3000,interval:20)@([call@ready])]")?;
+        writer.join().unwrap()?;
+        assert_eq!(out, Value::Bool(true));
+        assert!(started.elapsed() < Duration::from_millis(3000));
+
+        let out = eval(&mut rt, "[wait_for(timeout:50,interval:10)@(never)]")?;
+        assert_eq!(out, Value::Bool(false));
+        let _ = fs::remove_dir_all(&base);
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::kernel::ast::{Arg, Node};
+use crate::kernel::values::Document;
+use crate::kernel::{Packet, Runtime, Value};
+use crate::packets::funct::split_args;
+use crate::packets::query::json_to_value;
+use crate::packets::store::check_type;
+use anyhow::{Result, bail};
+
+// [with@{a:1,b:"two"}]{...} -> binds a and b only while the block runs
+// [with@cfg]{...}            -> same, one binding per top-level key of the Doc `cfg`
+// Afterwards every bound name gets its previous value back, or is unset if it had none,
+// even when the block fails.
+pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
+    let body = match &p.body {
+        Some(body) => body,
+        None => bail!("E_MISSING_BODY: with needs body"),
+    };
+    let bindings = match &p.arg {
+        Some(Arg::Str(src)) if src.trim_start().starts_with('{') => parse_object(rt, src)?,
+        Some(Arg::Ident(id)) => match rt.get_var(id) {
+            Some(Value::Doc(d)) => match &d.json {
+                serde_json::Value::Object(map) => map
+                    .iter()
+                    .map(|(k, v)| (k.clone(), json_to_value(v, &d)))
+                    .collect(),
+                _ => bail!("E_TYPE_MISMATCH: with@{id} needs an object Doc"),
+            },
+            _ => bail!("E_TYPE_MISMATCH: with@{id} needs an object Doc"),
+        },
+        _ => bail!("E_BAD_ARG: with needs @{{name:value,...}} or @<doc>"),
+    };
+
+    for (name, val) in &bindings {
+        check_type(rt, name, val)?;
+    }
+    let saved: Vec<(String, Option<Value>)> = bindings
+        .iter()
+        .map(|(name, _)| (name.clone(), rt.vars.get(name).cloned()))
+        .collect();
+    for (name, val) in bindings {
+        rt.set_var(&name, val)?;
+    }
+    let out = rt.eval(&Node::Block(body.clone()));
+    for (name, prev) in saved {
+        match prev {
+            Some(v) => {
+                rt.vars.insert(name, v);
+            }
+            None => {
+                rt.vars.remove(&name);
+            }
+        }
+    }
+    out
+}
+
+// {a:1, b:"two", c:other_var} or strict JSON
+fn parse_object(rt: &Runtime, src: &str) -> Result<Vec<(String, Value)>> {
+    let src = src.trim();
+    let root = rt.effective_root.clone().unwrap_or_default();
+    let meta = Document::scratch(root, &rt.cwd, "_with.json", serde_json::Value::Null);
+    if let Ok(serde_json::Value::Object(map)) = serde_json::from_str(src) {
+        return Ok(map.iter().map(|(k, v)| (k.clone(), json_to_value(v, &meta))).collect());
+    }
+    let inner = match src.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+        Some(inner) => inner,
+        None => bail!("E_BAD_ARG: with needs @{{name:value,...}}"),
+    };
+    let mut out = Vec::new();
+    for entry in split_args(inner) {
+        let entry = entry.trim();
+        if entry.is_empty() {
+            continue;
+        }
+        let (key, raw) = match entry.split_once(':') {
+            Some(pair) => pair,
+            None => bail!("E_BAD_ARG: with entry '{entry}' needs name:value"),
+        };
+        let key = key.trim().trim_matches('"');
+        if key.is_empty() {
+            bail!("E_BAD_ARG: with entry '{entry}' needs a name");
+        }
+        out.push((key.to_string(), literal(rt, raw.trim(), &meta)?));
+    }
+    Ok(out)
+}
+
+fn literal(rt: &Runtime, raw: &str, meta: &Document) -> Result<Value> {
+    if raw.starts_with('"') || raw.starts_with('{') || raw.starts_with('[') {
+        let json: serde_json::Value = serde_json::from_str(raw)
+            .map_err(|e| anyhow::anyhow!("E_BAD_ARG: with value {raw}: {e}"))?;
+        return Ok(json_to_value(&json, meta));
+    }
+    if let Ok(n) = raw.parse::<f64>() {
+        return Ok(Value::Num(n));
+    }
+    Ok(match raw {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        "null" => Value::Unit,
+        "_" => rt.last.clone(),
+        name => rt.get_var(name).unwrap_or(Value::Unit),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router;
+
+    fn eval(rt: &mut Runtime, script: &str) -> Result<Value> {
+        let node = router::parse(script).map_err(anyhow::Error::new)?;
+        rt.eval(&node)
+    }
+
+    #[test]
+    fn bindings_end_with_the_block() -> Result<()> {
+        let mut rt = Runtime::new()?;
+        rt.set_var("b", Value::Str("outer".into()))?;
+        let out = eval(&mut rt, "[with@{a:1,b:\"inner\"}]{[msg@b + a]}")?;
+        assert_eq!(out, Value::Str("inner1".into()));
+        assert_eq!(rt.get_var("a"), None);
+        assert_eq!(rt.get_var("b"), Some(Value::Str("outer".into())));
+        Ok(())
+    }
+}
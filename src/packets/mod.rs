@@ -1,49 +1,72 @@
 // Grouped packet modules by role
 pub mod core {
     pub mod array;
+    pub mod assert;
+    pub mod base64;
     pub mod bool;
     pub mod cd;
+    pub mod changed;
+    pub mod coalesce;
     pub mod compare;
     pub mod dump;
     pub mod env;
+    pub mod hash;
     pub mod help;
     pub mod input;
     pub mod int;
+    pub mod json;
     pub mod len;
     pub mod lint;
     pub mod math;
     pub mod msg;
     pub mod note;
+    pub mod now;
     pub mod obj;
     pub mod parse;
     pub mod print;
     pub mod rand;
+    pub mod range;
     pub mod reflect;
     pub mod store;
+    pub mod template;
+    pub mod uuid;
     pub mod var;
 }
 
 pub mod files {
     pub mod load;
     pub mod log;
+    pub mod merge;
     pub mod modify;
     pub mod query;
+    pub mod readlines;
     pub mod save;
     pub mod search;
 }
 
 pub mod flow {
+    pub mod r#break;
     pub mod call;
     pub mod conditionals;
+    pub mod defer;
     pub mod funct;
+    pub mod interrupt;
     pub mod iter;
     pub mod r#loop;
+    pub mod r#match;
+    pub mod once;
+    pub mod retry;
+    pub mod r#return;
+    pub mod r#try;
+    pub mod wait_for;
+    pub mod with;
 }
 
 pub mod execs {
     pub mod confirm;
     pub mod exec;
     pub mod http;
+    pub mod import;
     pub mod red;
     pub mod repl;
     pub mod run;
@@ -61,10 +84,10 @@ pub mod ui {
 // Re-export for backward compatibility with existing paths
 #[allow(unused_imports)]
 pub use core::{
-    array, bool, cd, compare, dump, env, help, input, int, len, lint, math, msg, note, obj, parse,
-    print, rand, reflect, store, var,
+    array, assert, base64, bool, cd, changed, coalesce, compare, dump, env, hash, help, input, int, json, len, lint, math, msg, note, now, obj, parse,
+    print, rand, range, reflect, store, template, uuid, var,
 };
-pub use execs::{confirm, exec, http, red, repl, run, tagspeak};
-pub use files::{load, log, modify, query, save, search};
-pub use flow::{call, conditionals, funct, iter, r#loop};
+pub use execs::{confirm, exec, http, import, red, repl, run, tagspeak};
+pub use files::{load, log, merge, modify, query, readlines, save, search};
+pub use flow::{r#break, call, conditionals, defer, funct, interrupt, iter, r#loop, r#match, once, retry, r#return, r#try, wait_for, with};
 pub use ui::{alert as ui_alert, select as ui_select, window as ui_window, app as ui_app, scope as ui_scope};
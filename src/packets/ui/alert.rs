@@ -13,20 +13,8 @@ fn is_noninteractive() -> bool {
 pub fn handle(rt: &mut Runtime, p: &Packet) -> Result<Value> {
     let prior = rt.last.clone();
     let msg = match p.arg.as_ref() {
-        Some(arg) => match rt.resolve_arg(arg)? {
-            Value::Str(s) => s,
-            Value::Num(n) => format!("{}", n),
-            Value::Bool(b) => format!("{}", b),
-            Value::Doc(_) => String::from("<doc>"),
-            Value::Unit => String::from("()"),
-        },
-        None => match &prior {
-            Value::Str(s) => s.clone(),
-            Value::Num(n) => format!("{}", n),
-            Value::Bool(b) => format!("{}", b),
-            Value::Doc(_) => String::from("<doc>"),
-            Value::Unit => String::from("()"),
-        },
+        Some(arg) => rt.resolve_arg(arg)?.to_string(),
+        None => prior.to_string(),
     };
 
     // Prefer GUI when available and interactive, otherwise console
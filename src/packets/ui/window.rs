@@ -286,7 +286,7 @@ fn render_gui(rt: &mut Runtime, title: &str, nodes: &mut [UiNode], _root: &Layou
                 unsafe {
                     // SAFETY: we ensure the runtime outlives the app run
                     let rt: &mut Runtime = &mut *self.rt_ptr;
-                    let node = Node::Packet(Packet { ns: None, op: "call".to_string(), arg: Some(Arg::Str(call)), body: None });
+                    let node = Node::Packet(Packet { ns: None, op: "call".to_string(), arg: Some(Arg::Str(call)), body: None, span: crate::kernel::ast::Span::default() });
                     let _ = rt.eval(&node);
                 }
                 // Keep window open; allow multiple interactions
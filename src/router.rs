@@ -1,6 +1,6 @@
 use crate::error_style::{friendly_hint, render_error_box, unexpected_hint};
 use crate::interpreter::Scanner;
-use crate::kernel::ast::{Arg, Node, Packet};
+use crate::kernel::ast::{Arg, Comment, Node, Packet, Span};
 use crate::kernel::packet_catalog::{is_known_packet, suggest_packet};
 use anyhow::{Result as AnyResult, bail};
 use std::fmt;
@@ -16,14 +16,69 @@ pub fn parse(src: &str) -> Result<Node, ParseError> {
     }
 }
 
+// Comments sit only between packets (never inside `[...]`), so one pass that skips
+// packet heads and their quoted args finds exactly the ones the parser dropped.
+pub fn comments(src: &str) -> Vec<Comment> {
+    let bytes = src.as_bytes();
+    let mut out = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut i = 0usize;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if depth > 0 {
+            match b {
+                b'\\' => i += 1,
+                b'"' => in_string = !in_string,
+                b'[' if !in_string => depth += 1,
+                b']' if !in_string => depth -= 1,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        let end = match (b, bytes.get(i + 1)) {
+            (b'[', _) => {
+                depth = 1;
+                i += 1;
+                continue;
+            }
+            (b'#', _) | (b'/', Some(b'/')) => bytes[i..]
+                .iter()
+                .position(|&c| c == b'\n' || c == b'\r')
+                .map_or(bytes.len(), |n| i + n),
+            (b'/', Some(b'*')) => src[i + 2..].find("*/").map_or(bytes.len(), |n| i + 2 + n + 2),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        let line_start = src[..i].rfind('\n').map_or(0, |n| n + 1);
+        out.push(Comment {
+            text: src[i..end].trim_end().to_string(),
+            span: Span {
+                start: i,
+                end,
+                line: src[..i].matches('\n').count() + 1,
+                col: src[line_start..i].chars().count() + 1,
+            },
+            trailing: !src[line_start..i].trim().is_empty(),
+        });
+        i = end;
+    }
+    out
+}
+
+// One parse problem, for callers that render their own error UI.
 #[derive(Debug, Clone)]
 pub struct ParseDiagnostic {
-    pub line: usize,
-    pub col: usize,
-    pub summary: String,
-    pub panel: String,
+    pub line: usize,     // 1-based source line
+    pub col: usize,      // 1-based column, counted in chars
+    pub summary: String, // one line, e.g. "Unknown packet on line 3"
+    pub panel: String,   // the boxed snippet + caret + hint the CLI prints under the summary
 }
 
+// Every diagnostic from one parse, sorted by (line, col); Display renders them all.
 #[derive(Debug)]
 pub struct ParseError {
     diagnostics: Vec<ParseDiagnostic>,
@@ -75,6 +130,10 @@ impl fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
+// Chaining: `>` is only a separator. `[a]>[b]`, `[a][b]` and `[a] [b]` (newlines and
+// comments included) parse to the same sibling list, before or after a block, and each
+// packet reads the previous one's result. What `>` does change: a `{` right after a
+// packet (whitespace allowed) is that packet's body, while `[a]>{...}` is a bare block.
 fn parse_chain(sc: &mut Scanner, diagnostics: &mut Vec<ParseDiagnostic>) -> Node {
     let mut nodes = Vec::new();
 
@@ -109,7 +168,7 @@ fn parse_chain(sc: &mut Scanner, diagnostics: &mut Vec<ParseDiagnostic>) -> Node
                             Some(src) => src,
                             None => continue,
                         };
-                    if let Some(node) = parse_if(sc, cond_src, diagnostics) {
+                    if let Some(node) = parse_if(sc, cond_src, packet_start, diagnostics) {
                         nodes.push(node);
                     }
                 } else {
@@ -134,6 +193,7 @@ fn parse_chain(sc: &mut Scanner, diagnostics: &mut Vec<ParseDiagnostic>) -> Node
                             Some(_) => unreachable!(),
                             None => continue,
                         }
+                        pkt.span.end = sc.pos();
                     }
                     nodes.push(Node::Packet(pkt));
                 }
@@ -200,6 +260,7 @@ fn parse_block(sc: &mut Scanner, diagnostics: &mut Vec<ParseDiagnostic>) -> Opti
 }
 
 fn parse_packet(sc: &mut Scanner) -> AnyResult<Packet> {
+    let start = sc.pos();
     let (inner, _) = sc.read_until_balanced('[', ']')?;
     let (ns_part, op_part, arg_part) = split_packet_parts(&inner);
 
@@ -218,27 +279,9 @@ fn parse_packet(sc: &mut Scanner) -> AnyResult<Packet> {
     }
     let op = op_trimmed.to_string();
 
-    let arg = if let Some(raw) = arg_part {
-        let raw_trimmed = raw.trim();
-        if raw_trimmed.is_empty() {
-            None
-        } else if raw_trimmed.starts_with('"') && raw_trimmed.contains('+') {
-            Some(Arg::Str(raw_trimmed.to_string()))
-        } else if raw_trimmed.starts_with('"') {
-            let mut sc = Scanner::new(raw_trimmed);
-            let s = sc.read_quoted()?;
-            Some(Arg::Str(s))
-        } else if raw_trimmed.starts_with('(') {
-            Some(Arg::CondSrc(raw_trimmed.to_string()))
-        } else if let Ok(n) = raw_trimmed.parse::<f64>() {
-            Some(Arg::Number(n))
-        } else if is_ident_like(raw_trimmed) {
-            Some(Arg::Ident(raw_trimmed.to_string()))
-        } else {
-            Some(Arg::Str(raw_trimmed.to_string()))
-        }
-    } else {
-        None
+    let arg = match arg_part {
+        Some(raw) => parse_arg(raw)?,
+        None => None,
     };
 
     Ok(Packet {
@@ -246,6 +289,29 @@ fn parse_packet(sc: &mut Scanner) -> AnyResult<Packet> {
         op,
         arg,
         body: None,
+        span: sc.span(start, sc.pos()),
+    })
+}
+
+/// Classify the raw text after `@` into an [`Arg`]. Empty text yields `None`.
+pub fn parse_arg(raw: &str) -> AnyResult<Option<Arg>> {
+    let raw_trimmed = raw.trim();
+    Ok(if raw_trimmed.is_empty() {
+        None
+    } else if raw_trimmed.starts_with('"') && raw_trimmed.contains('+') {
+        Some(Arg::Str(raw_trimmed.to_string()))
+    } else if raw_trimmed.starts_with('"') {
+        let mut sc = Scanner::new(raw_trimmed);
+        let s = sc.read_quoted()?;
+        Some(Arg::Str(s))
+    } else if raw_trimmed.starts_with('(') {
+        Some(Arg::CondSrc(raw_trimmed.to_string()))
+    } else if let Ok(n) = raw_trimmed.parse::<f64>() {
+        Some(Arg::Number(n))
+    } else if is_ident_like(raw_trimmed) {
+        Some(Arg::Ident(raw_trimmed.to_string()))
+    } else {
+        Some(Arg::Str(raw_trimmed.to_string()))
     })
 }
 
@@ -352,6 +418,7 @@ fn is_ident_like(s: &str) -> bool {
 fn parse_if(
     sc: &mut Scanner,
     cond_src: String,
+    start: usize,
     diagnostics: &mut Vec<ParseDiagnostic>,
 ) -> Option<Node> {
     use crate::packets::conditionals::parse_cond;
@@ -421,6 +488,7 @@ fn parse_if(
         cond,
         then_b,
         else_b,
+        span: sc.span(start, sc.pos()),
     })
 }
 
@@ -503,6 +571,7 @@ fn parse_or_else(sc: &mut Scanner, diagnostics: &mut Vec<ParseDiagnostic>) -> Op
             cond: parse_cond(&src),
             then_b,
             else_b,
+            span: sc.span(or_start, sc.pos()),
         });
         Some(nodes)
     } else if starts_with(sc, "[else]") {
@@ -980,7 +1049,12 @@ fn resync_after_error(sc: &mut Scanner, origin: usize) {
                 sc.i = pos + 1;
                 return;
             }
-            Some('[') | Some('{') | Some('>') => {
+            // a stray '[' (e.g. `x[` or `[[`) would swallow the valid packets after it
+            Some('[') if starts_packet(sc, pos) => {
+                sc.i = pos;
+                return;
+            }
+            Some('{') | Some('>') => {
                 sc.i = pos;
                 return;
             }
@@ -1005,10 +1079,53 @@ fn resync_after_error(sc: &mut Scanner, origin: usize) {
     sc.i = limit;
 }
 
+// lookahead: does a well-formed `[...]` packet begin at `pos`?
+fn starts_packet(sc: &Scanner, pos: usize) -> bool {
+    let mut probe = sc.subscanner(pos, sc.limit());
+    parse_packet(&mut probe).is_ok()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn chaining_with_or_without_arrows_is_identical() -> anyhow::Result<()> {
+        use crate::kernel::{Runtime, Value};
+        let pairs = [
+            ("[int@1]>[store@x]", "[int@1][store@x]"),
+            ("[int@1]>[store@x]", "[int@1]\n  [store@x]"),
+            ("[loop@2]{[store(inc)@n]}>[var@n]>[store@x]", "[loop@2]{[store(inc)@n]}[var@n][store@x]"),
+            ("[funct:f]{[int@5]}>[call@f]>[store@x]", "[funct:f]{[int@5]} [call@f] [store@x]"),
+            (
+                "[if@(1==2)]>[then]{[int@1]}>[else]>[then]{[int@2]}>[store@x]",
+                "[if@(1==2)][then]{[int@1]}[else][then]{[int@2]}[store@x]",
+            ),
+            ("[try]{[assert@(1==2)]}>[catch]{[int@3]}>[store@x]", "[try]{[assert@(1==2)]}[catch]{[int@3]}[store@x]"),
+        ];
+        for (arrows, adjacent) in pairs {
+            let run = |src: &str| -> anyhow::Result<(Value, Option<Value>)> {
+                let mut rt = Runtime::new()?;
+                let out = rt.eval(&parse(src).map_err(anyhow::Error::new)?)?;
+                Ok((out, rt.get_var("x")))
+            };
+            let expected = run(arrows)?;
+            assert!(expected.1.is_some(), "{arrows} stored nothing");
+            assert_eq!(run(adjacent)?, expected, "{arrows} vs {adjacent}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn non_ascii_text_survives_strings_and_conditions() -> anyhow::Result<()> {
+        use crate::kernel::{Runtime, Value};
+        let mut rt = Runtime::new()?;
+        let node = parse("[msg@\"héllo ✓\"]>[store@greet]>[if@(greet==\"héllo ✓\")]>[then]{[msg@\"ja ü\"]}")?;
+        assert_eq!(rt.eval(&node)?, Value::Str("ja ü".into()));
+        assert_eq!(rt.get_var("greet"), Some(Value::Str("héllo ✓".into())));
+        Ok(())
+    }
+
     #[test]
     fn parse_collects_multiple_errors() {
         let src = "[math@1+1]]\n[if]{[then]{[print]}}\n";
@@ -1043,6 +1160,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn resync_keeps_valid_packet_between_errors() {
+        let src = "[stor@1] x[ [msg@\"ok\"] [prnt@2]\n";
+        let err = parse(src).expect_err("expected parse failure with diagnostics");
+        let cols: Vec<(usize, &str)> = err
+            .diagnostics()
+            .iter()
+            .map(|d| (d.col, d.summary.as_str()))
+            .collect();
+        assert_eq!(
+            cols,
+            vec![
+                (1, "Unknown packet on line 1"),
+                (24, "Unknown packet on line 1"),
+            ],
+            "unexpected diagnostics:\n{err}"
+        );
+    }
+
+    #[test]
+    fn diagnostics_report_line_and_col() {
+        let src = "[msg@\"ok\"]\n  [prnt@1]\n[msg@\"fine\"]\n\t[stor@2]\n";
+        let err = parse(src).expect_err("expected parse failure with diagnostics");
+        let spots: Vec<(usize, usize)> = err.diagnostics().iter().map(|d| (d.line, d.col)).collect();
+        assert_eq!(spots, vec![(2, 3), (4, 2)], "unexpected diagnostics:\n{err}");
+        assert!(err.diagnostics().iter().all(|d| !d.panel.is_empty()));
+    }
+
     #[test]
     fn parse_suggests_packet_typo() {
         let src = "[stor@value]";
@@ -1089,4 +1234,26 @@ mod tests {
             other => panic!("unexpected node shape: {other:?}"),
         }
     }
+
+    #[test]
+    fn parse_records_packet_spans() {
+        let src = "[math@1+1]\n  [print]{[msg@\"hi\"]}\n";
+        let node = parse(src).expect("expected parse success");
+        let Node::Chain(nodes) = node else {
+            panic!("expected chain, got {node:?}");
+        };
+        let Node::Packet(first) = &nodes[0] else {
+            panic!("expected packet, got {:?}", nodes[0]);
+        };
+        assert_eq!((first.span.start, first.span.end), (0, 10));
+        assert_eq!((first.span.line, first.span.col), (1, 1));
+        let Node::Packet(second) = &nodes[1] else {
+            panic!("expected packet, got {:?}", nodes[1]);
+        };
+        assert_eq!(&src[second.span.start..second.span.end], "[print]{[msg@\"hi\"]}");
+        assert_eq!((second.span.line, second.span.col), (2, 3));
+        let inner = &second.body.as_ref().unwrap()[0];
+        assert_eq!(&src[inner.span().start..inner.span().end], "[msg@\"hi\"]");
+        assert_eq!((inner.span().line, inner.span().col), (2, 11));
+    }
 }
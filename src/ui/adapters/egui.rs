@@ -92,7 +92,7 @@ pub fn render(tree: &TagTree, rt: &mut Runtime) -> Result<()> {
                     // Capture scope for context-bound writes during this call
                     let prev_cap = rt.get_var("__scope_capture");
                     if let Some(sc) = pending_scope { let _ = rt.set_var("__scope_capture", crate::kernel::values::Value::Str(sc)); }
-                    let packet = crate::kernel::ast::Packet { ns: None, op: "call".to_string(), arg: Some(crate::kernel::ast::Arg::Str(call)), body: None };
+                    let packet = crate::kernel::ast::Packet { ns: None, op: "call".to_string(), arg: Some(crate::kernel::ast::Arg::Str(call)), body: None, span: crate::kernel::ast::Span::default() };
                     let _ = rt.eval(&crate::kernel::ast::Node::Packet(packet));
                     // restore
                     match prev_cap {